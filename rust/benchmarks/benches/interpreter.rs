@@ -30,6 +30,10 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("memory/10000", |b| {
         b.iter(|| assert_eq!(benchmarks::run(&mut args), expected))
     });
+    let (mut args, expected) = RunArgs::memory_expansion(10000);
+    c.bench_function("memory/expansion/10000", |b| {
+        b.iter(|| assert_eq!(benchmarks::run(&mut args), expected))
+    });
     let (mut args, expected) = RunArgs::jumpdest_analysis(0x6000);
     c.bench_function("analysis/jumpdest", |b| {
         b.iter(|| assert_eq!(benchmarks::run(&mut args), expected))
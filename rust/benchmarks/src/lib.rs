@@ -276,6 +276,30 @@ impl RunArgs {
         )
     }
 
+    /// Repeated 32-byte `MSTORE`s at ever-increasing offsets, so every iteration expands memory
+    /// by another word instead of rewriting the same bytes - exercising the quadratic memory-gas
+    /// accounting (`Memory::reserve_cost`) on every step rather than just once.
+    pub fn memory_expansion(size: u32) -> (Self, u32) {
+        fn memory_expansion_ref(_input: u32) -> u32 {
+            // The loop never stores to offset 0 (it stops as soon as the counter reaches zero,
+            // before that iteration's store), so the first word of memory is always left zero.
+            0
+        }
+
+        const CODE: [u8; 28] = [
+            PUSH1, 4, CALLDATALOAD, // load the iteration count from calldata
+            JUMPDEST, // loop_start (pc 3)
+            DUP1, ISZERO, PUSH1, 22, JUMPI, // if counter == 0, jump to `end`
+            DUP1, DUP1, PUSH1, 0x20, MUL, MSTORE, // mem[counter * 32] = counter
+            PUSH1, 1, SWAP1, SUB, // counter -= 1
+            PUSH1, 3, JUMP, // back to loop_start
+            JUMPDEST, // end (pc 22)
+            PUSH1, 0x20, PUSH1, 0, RETURN,
+        ];
+
+        (Self::new(&CODE, size, None), memory_expansion_ref(size))
+    }
+
     const fn analysis_code_len(max_len: usize, filler_len: usize) -> usize {
         let code_start_len = 10;
         let code_end_len = 6;
@@ -402,6 +426,26 @@ impl RunArgs {
         Self::analysis(size, &SHORT_CODE, &LONG_CODE)
     }
 
+    /// Switches this instance to emit an EIP-3155 JSON-lines trace (one object per executed op,
+    /// plus a final summary) to stdout on every subsequent [`run`], so e.g. `fib`/`arithmetic` can
+    /// be piped into a differential trace comparison against another EVM instead of only checked
+    /// against `expected` for the final return value.
+    pub fn enable_tracing(&mut self) {
+        self.instance
+            .set_option("tracing", "json")
+            .expect("\"tracing\"/\"json\" is always a recognized set_option key/value");
+    }
+
+    /// Caps every subsequent [`run`] at `max_steps` dispatched opcodes, so a fuzz-discovered or
+    /// hand-written input that loops unboundedly within its gas limit fails fast with
+    /// `EVMC_INTERNAL_ERROR` (`FailStatus::Interrupted`) instead of hanging the harness - see
+    /// `types::interrupt` for why gas alone doesn't bound this.
+    pub fn set_max_steps(&mut self, max_steps: u64) {
+        self.instance
+            .set_option("max-steps", &max_steps.to_string())
+            .expect("a decimal max-steps value is always a recognized set_option key/value");
+    }
+
     fn new(code: &'static [u8], size: u32, func: Option<[u8; 4]>) -> Self {
         let instance = Instance::default();
         let mut host = null_ptr_host_interface();
@@ -1,16 +1,65 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
 use benchmarks::RunArgs;
 use clap::{Parser, ValueEnum};
 
+/// Wraps [`System`] with a count of every `alloc`/`realloc`, so
+/// [`Benchmark::FfiOverheadAllocCount`] can show how much of the allocator traffic listed in
+/// `RunArgs::ffi_overhead`'s doc comment is still paid once the interpreter's buffer pools are
+/// warm, instead of just asserting it by reading the pooling code.
+struct CountingAllocator;
+
+static ALLOC_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+// SAFETY: every method forwards straight to `System`, which already satisfies `GlobalAlloc`'s
+// contract; the only addition is an `Ordering::Relaxed` counter bump, which has no safety
+// requirements of its own.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOC_COUNT.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOC_COUNT.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
     runs: u64,
     benchmark: Benchmark,
+    /// Emit an EIP-3155 JSON-lines trace of every run to stdout, e.g. to diff `fib20`/
+    /// `arithmetic280` opcode-for-opcode against another EVM's trace of the same program.
+    #[arg(long)]
+    trace: bool,
+    /// Cap every run at this many dispatched opcodes, failing fast instead of hanging on an input
+    /// that loops unboundedly within its gas limit. Requires the `interrupt` feature.
+    #[arg(long)]
+    max_steps: Option<u64>,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum Benchmark {
     FfiOverhead,
+    /// Like `FfiOverhead`, but reports allocator traffic instead of timing: one warmup run to
+    /// fill the buffer pools, then the allocation count accrued over `runs` further calls. With
+    /// `buffer-pool` and `alloc-reuse` enabled this should settle at (close to) zero per run; with
+    /// them disabled every run pays for the `CodeReader` analysis, memory and output allocations
+    /// fresh.
+    FfiOverheadAllocCount,
     Inc1,
     Fib20,
     Sha1000,
@@ -27,8 +76,28 @@ enum Benchmark {
 fn main() {
     let args = Args::parse();
 
+    if matches!(args.benchmark, Benchmark::FfiOverheadAllocCount) {
+        let (mut run_args, expected) = RunArgs::ffi_overhead(1);
+        // Warm up the buffer pools (and any caches) before measuring, the same way a long-running
+        // host would have by the time it's handling steady-state traffic.
+        assert_eq!(benchmarks::run(&mut run_args), expected);
+        let before = ALLOC_COUNT.load(Ordering::Relaxed);
+        for _ in 0..args.runs {
+            assert_eq!(benchmarks::run(&mut run_args), expected);
+        }
+        let after = ALLOC_COUNT.load(Ordering::Relaxed);
+        let total = after - before;
+        println!(
+            "{total} allocations over {} steady-state runs ({:.2} per run)",
+            args.runs,
+            total as f64 / args.runs as f64
+        );
+        return;
+    }
+
     let benches: Vec<fn() -> (RunArgs, u32)> = match args.benchmark {
         Benchmark::FfiOverhead => vec![|| RunArgs::ffi_overhead(1)],
+        Benchmark::FfiOverheadAllocCount => unreachable!("handled above"),
         Benchmark::Inc1 => vec![|| RunArgs::inc(1)],
         Benchmark::Fib20 => vec![|| RunArgs::fib(20)],
         Benchmark::Sha1000 => vec![|| RunArgs::sha3(1000)],
@@ -66,6 +135,12 @@ fn main() {
 
     for bench_fn in benches {
         let (mut run_args, expected) = bench_fn();
+        if args.trace {
+            run_args.enable_tracing();
+        }
+        if let Some(max_steps) = args.max_steps {
+            run_args.set_max_steps(max_steps);
+        }
         for _ in 0..args.runs {
             assert_eq!(benchmarks::run(&mut run_args), expected);
         }
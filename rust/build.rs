@@ -0,0 +1,51 @@
+//! Generates per-opcode tables from `instructions.in`: the mnemonic lookup table used by
+//! `types::disassembler` (`generated-mnemonics` feature) and the `min_revision` lookup table used
+//! by `types::opcode` (`generated-op-min-revision` feature) - see that file for the table format
+//! and why it doesn't (yet) cover gas/stack costs or dispatch itself.
+use std::{env, fs, path::Path};
+
+fn main() {
+    println!("cargo::rerun-if-changed=instructions.in");
+    let generate_mnemonics = env::var("CARGO_FEATURE_GENERATED_MNEMONICS").is_ok();
+    let generate_min_revision = env::var("CARGO_FEATURE_GENERATED_OP_MIN_REVISION").is_ok();
+    if !generate_mnemonics && !generate_min_revision {
+        return;
+    }
+
+    let source = fs::read_to_string("instructions.in").expect("instructions.in must exist");
+    let mut mnemonics = vec!["\"DATA\"".to_string(); 256];
+    let mut min_revisions = vec!["evmc_vm::Revision::EVMC_FRONTIER".to_string(); 256];
+    for line in source.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let mnemonic = fields.next().expect("each line has a mnemonic");
+        let byte = fields.next().expect("each line has a byte value");
+        let byte = u8::from_str_radix(byte.trim_start_matches("0x"), 16)
+            .expect("byte value is a hex literal");
+        let _push_len = fields.next().expect("each line has a push length");
+        let min_revision = fields.next().expect("each line has a min revision");
+        mnemonics[byte as usize] = format!("{mnemonic:?}");
+        min_revisions[byte as usize] = format!("evmc_vm::Revision::EVMC_{min_revision}");
+    }
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    if generate_mnemonics {
+        let generated = format!(
+            "pub(crate) static GENERATED_MNEMONICS: [&str; 256] = [{}];\n",
+            mnemonics.join(", ")
+        );
+        fs::write(Path::new(&out_dir).join("opcode_mnemonics.rs"), generated)
+            .expect("OUT_DIR is writable");
+    }
+    if generate_min_revision {
+        let generated = format!(
+            "pub(crate) static GENERATED_MIN_REVISION: [evmc_vm::Revision; 256] = [{}];\n",
+            min_revisions.join(", ")
+        );
+        fs::write(Path::new(&out_dir).join("opcode_min_revision.rs"), generated)
+            .expect("OUT_DIR is writable");
+    }
+}
@@ -1,21 +1,23 @@
 use std::{
-    ffi,
+    ffi::{self, CString},
     ops::{Deref, DerefMut},
     ptr,
 };
 
 use common::evmc_vm::{
     ffi::{
-        evmc_host_interface, evmc_message, evmc_step_status_code, evmc_tx_context,
-        evmc_vm as evmc_vm_t, evmc_vm_steppable,
+        evmc_host_interface, evmc_message, evmc_set_option_result, evmc_step_status_code,
+        evmc_tx_context, evmc_vm as evmc_vm_t, evmc_vm_steppable,
     },
-    Address, ExecutionResult, Revision, StepResult, Uint256,
+    Address, ExecutionResult, Revision, SetOptionError, StepResult, Uint256,
 };
 // This is needed in order for driver to link against evmrs.
 #[allow(unused_imports, clippy::single_component_path_imports)]
 use evmrs;
 
 pub mod host_interface;
+pub mod reference_vm;
+pub mod statetest;
 
 unsafe extern "C" {
     safe fn evmc_create_evmrs() -> *mut evmc_vm_t;
@@ -85,6 +87,25 @@ impl Drop for Instance {
 }
 
 impl Instance {
+    /// Configure the instance via the same `set_option(key, value)` ABI a host would use, e.g.
+    /// `("tracing", "json")` to have subsequent `run*` calls emit an EIP-3155 trace - see
+    /// `EvmRs::set_option` for the full set of recognized keys/values.
+    pub fn set_option(&mut self, key: &str, value: &str) -> Result<(), SetOptionError> {
+        let set_option = self.0.set_option.unwrap();
+        let key = CString::new(key).map_err(|_| SetOptionError::InvalidKey)?;
+        let value = CString::new(value).map_err(|_| SetOptionError::InvalidValue)?;
+        // SAFETY:
+        // `self.0` is a valid pointer to the instance this `set_option` belongs to, and `key`/
+        // `value` are valid, NUL-terminated C strings for the duration of this call.
+        match unsafe { set_option(self.0, key.as_ptr(), value.as_ptr()) } {
+            evmc_set_option_result::EVMC_SET_OPTION_SUCCESS => Ok(()),
+            evmc_set_option_result::EVMC_SET_OPTION_INVALID_NAME => Err(SetOptionError::InvalidKey),
+            evmc_set_option_result::EVMC_SET_OPTION_INVALID_VALUE => {
+                Err(SetOptionError::InvalidValue)
+            }
+        }
+    }
+
     /// Run the interpreter (the `execute` function) with the supplied values. This function is
     /// unsafe because it takes raw pointers. It intended to be used to verify that the checks in
     /// the ffi module work as intended.
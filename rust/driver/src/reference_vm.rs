@@ -0,0 +1,135 @@
+//! Dynamic loading of a second, independent EVMC implementation so the fuzz harness can compare
+//! `evmrs` against it instead of only checking `evmrs` against itself. Any EVMC-ABI VM built as a
+//! shared library works here (e.g. evmone, or the Go reference VM's `libevmone`-style cdylib
+//! build), since [`Instance`] and [`ReferenceVm`] both just wrap an `evmc_vm_t` vtable.
+
+use std::{env, ffi, fmt, ptr};
+
+use common::evmc_vm::ffi::evmc_vm as evmc_vm_t;
+use libloading::{Library, Symbol};
+
+/// Name of the environment variable pointing at the reference VM's shared library. The
+/// differential fuzz target treats an unset variable as "no reference VM configured" and skips
+/// the comparison rather than failing, so the harness keeps working in sandboxes that don't have
+/// a reference implementation available.
+pub const REFERENCE_VM_PATH_VAR: &str = "EVMRS_FUZZ_REFERENCE_VM";
+
+/// Name of the symbol the loaded library is expected to export, following the `evmc_create_*`
+/// naming convention from the EVMC loader (`evmc/loader.h`). Defaults to the bare `evmc_create`
+/// name most reference builds export; override it if the library under test only exports its
+/// usual qualified name (e.g. `evmc_create_evmone`).
+pub const REFERENCE_VM_SYMBOL_VAR: &str = "EVMRS_FUZZ_REFERENCE_VM_SYMBOL";
+
+const DEFAULT_SYMBOL: &str = "evmc_create";
+
+#[derive(Debug)]
+pub enum ReferenceVmError {
+    NotConfigured,
+    Load(libloading::Error),
+    Create,
+}
+
+impl fmt::Display for ReferenceVmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotConfigured => write!(f, "{REFERENCE_VM_PATH_VAR} is not set"),
+            Self::Load(err) => write!(f, "failed to load reference VM library: {err}"),
+            Self::Create => write!(f, "reference VM's create function returned a null instance"),
+        }
+    }
+}
+
+impl std::error::Error for ReferenceVmError {}
+
+/// A `Library` plus the `evmc_vm_t` vtable it produced. The vtable borrows from the library, so
+/// the two must be dropped together, in that order - `Drop` below calls `destroy` before the
+/// `_library` field is unloaded.
+pub struct ReferenceVm {
+    vm: &'static mut evmc_vm_t,
+    _library: Library,
+}
+
+impl ReferenceVm {
+    /// Loads the reference VM named by [`REFERENCE_VM_PATH_VAR`], or returns
+    /// [`ReferenceVmError::NotConfigured`] if that variable is unset. Meant to be called once per
+    /// fuzz process, not once per input - repeated `dlopen` of the same library is wasteful and
+    /// unnecessary since the returned handle is reusable.
+    pub fn from_env() -> Result<Self, ReferenceVmError> {
+        let Ok(path) = env::var(REFERENCE_VM_PATH_VAR) else {
+            return Err(ReferenceVmError::NotConfigured);
+        };
+        let symbol = env::var(REFERENCE_VM_SYMBOL_VAR).unwrap_or_else(|_| DEFAULT_SYMBOL.into());
+        Self::load(&path, &symbol)
+    }
+
+    /// Loads `path` and invokes the exported `symbol`, which must have the EVMC create-function
+    /// signature `unsafe extern "C" fn() -> *mut evmc_vm_t`.
+    pub fn load(path: &str, symbol: &str) -> Result<Self, ReferenceVmError> {
+        // SAFETY:
+        // Loading an arbitrary shared library is inherently unsafe; the caller is trusted to
+        // point this at a well-behaved EVMC implementation, same as the C `evmc_loader` API.
+        let library = unsafe { Library::new(path) }.map_err(ReferenceVmError::Load)?;
+        // SAFETY:
+        // `symbol` is documented to name an `unsafe extern "C" fn() -> *mut evmc_vm_t`.
+        let create: Symbol<unsafe extern "C" fn() -> *mut evmc_vm_t> =
+            unsafe { library.get(symbol.as_bytes()) }.map_err(ReferenceVmError::Load)?;
+        // SAFETY:
+        // `create` was just resolved from `library` and matches the EVMC create-function ABI.
+        let instance = unsafe { create() };
+        if instance.is_null() {
+            return Err(ReferenceVmError::Create);
+        }
+        // SAFETY:
+        // `instance` is not null and `create` must return a valid `evmc_vm_t*` per the EVMC ABI.
+        // It borrows from `library`, which outlives it because of the field order and `Drop` impl
+        // below.
+        let vm = unsafe { &mut *instance };
+        Ok(Self {
+            vm,
+            _library: library,
+        })
+    }
+
+    /// Runs the reference VM's `execute` the same way [`Instance::run`] runs `evmrs`'s, so the two
+    /// can be driven from identical arguments and compared result-for-result.
+    pub fn run<T>(
+        &mut self,
+        host: &common::evmc_vm::ffi::evmc_host_interface,
+        context: &mut T,
+        revision: common::evmc_vm::Revision,
+        message: &common::evmc_vm::ffi::evmc_message,
+        code: &[u8],
+    ) -> common::evmc_vm::ExecutionResult {
+        let execute = self.vm.execute.expect("reference VM has no execute function");
+        // SAFETY:
+        // All pointers are valid since they are created from references, except `code` which is
+        // null only when empty, matching the convention `Instance::run` already relies on.
+        unsafe {
+            execute(
+                self.vm,
+                host,
+                context as *mut T as *mut ffi::c_void,
+                revision,
+                message,
+                if code.is_empty() {
+                    ptr::null()
+                } else {
+                    code.as_ptr()
+                },
+                code.len(),
+            )
+        }
+        .into()
+    }
+}
+
+impl Drop for ReferenceVm {
+    fn drop(&mut self) {
+        if let Some(destroy) = self.vm.destroy {
+            // SAFETY:
+            // `self.vm` is a valid pointer to an `evmc_vm_t` for as long as `_library` stays
+            // loaded, which it does until after this call returns.
+            unsafe { destroy(self.vm) };
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Comparison/diagnostics shared by state-test-style conformance checks: running a case through
+//! [`Instance::run`](crate::Instance::run) and checking the outcome against a fixture's expected
+//! gas-used/output/failure class, the way `ethereum/tests`' GeneralStateTests and
+//! execution-spec-tests structure their per-case expectations.
+//!
+//! Fixture loading itself (JSON parsing, building the `evmc_message`/mock host from a case's
+//! `env`/`transaction`/`post` sections) isn't implemented here: no state-test fixture corpus ships
+//! with this repo snapshot, and guessing at that JSON schema with nothing to validate against
+//! would just be unverified scaffolding. What's implemented is the part that's fully specified
+//! regardless of fixture format - the mapping from a fixture's `expectException` label to the
+//! `StatusCode` `execute` should produce for it, and a shared routine comparing an actual
+//! `ExecutionResult` against the expected one so every case is checked the same way.
+
+use common::evmc_vm::{ExecutionResult, StatusCode};
+
+/// A fixture's expected outcome for one case: either a specific `expectException` label, or
+/// (label absent) a successful run with a specific gas-used/output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpectedOutcome {
+    Exception(String),
+    Success { gas_used: u64, output: Vec<u8> },
+}
+
+/// Where a fixture comparison disagreed with the actual result, with enough context to print a
+/// useful diagnostic without the caller re-deriving it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The fixture expected a specific failure class (`expected`, an `expectException` label) but
+    /// `execute` either returned a different one or succeeded (`got: None`) - the gap revm's
+    /// statetest runner closed when it stopped skipping EIP-4844 exception fixtures, rather than
+    /// passing on "some error, close enough".
+    UnexpectedException {
+        expected: String,
+        got: Option<StatusCode>,
+    },
+    GasUsed {
+        expected: u64,
+        got: u64,
+    },
+    Output {
+        expected: Vec<u8>,
+        got: Vec<u8>,
+    },
+}
+
+/// Maps an `expectException` fixture label to the `StatusCode` `execute` should return for it.
+/// Covers labels that name an in-EVM execution failure, plus a few `TR_*` transaction-rejection
+/// labels, each mapped to the closest status this crate's `execute` can produce. Labels naming a
+/// blob transaction-validity rejection - an oversized blob list, an invalid blob version, a
+/// blob-carrying `CREATE` - have no entry: those are checked by the surrounding client before
+/// `execute` is ever called at all (EVMC's ABI has no status code for them either), so
+/// [`check_result`] reports a fixture expecting one of them (or any other unmapped label) as a
+/// [`Mismatch::UnexpectedException`] whatever `execute` returns, rather than a silently-wrong pass.
+pub fn status_for_exception_label(label: &str) -> Option<StatusCode> {
+    Some(match label {
+        "TR_NoFunds" | "OutOfFunds" => StatusCode::EVMC_INSUFFICIENT_BALANCE,
+        "TR_GasLimitReached" | "TR_IntrinsicGas" | "OutOfGasBase" | "OutOfGasIntrinsic" => {
+            StatusCode::EVMC_OUT_OF_GAS
+        }
+        "TR_TypeNotSupported" | "InvalidOpcode" | "BadInstruction" => {
+            StatusCode::EVMC_INVALID_INSTRUCTION
+        }
+        "StackUnderflow" => StatusCode::EVMC_STACK_UNDERFLOW,
+        "StackOverflow" => StatusCode::EVMC_STACK_OVERFLOW,
+        "BadJumpDestination" => StatusCode::EVMC_BAD_JUMP_DESTINATION,
+        "WriteProtection" => StatusCode::EVMC_STATIC_MODE_VIOLATION,
+        "CallDepthExceeded" => StatusCode::EVMC_CALL_DEPTH_EXCEEDED,
+        _ => return None,
+    })
+}
+
+/// Checks `actual` (from [`Instance::run`](crate::Instance::run), run with tx gas limit
+/// `gas_limit`) against `expected`, reporting every way they disagree rather than stopping at the
+/// first - matching on multiple divergent fields (e.g. both gas used *and* output) in one run is
+/// easier to read than rerunning the case per field.
+pub fn check_result(
+    expected: &ExpectedOutcome,
+    actual: &ExecutionResult,
+    gas_limit: u64,
+) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    match expected {
+        ExpectedOutcome::Exception(label) => {
+            let expected_status = status_for_exception_label(label);
+            let got = (actual.status_code != StatusCode::EVMC_SUCCESS).then_some(actual.status_code);
+            if got.is_none() || got != expected_status {
+                mismatches.push(Mismatch::UnexpectedException {
+                    expected: label.clone(),
+                    got,
+                });
+            }
+        }
+        ExpectedOutcome::Success { gas_used, output } => {
+            if actual.status_code != StatusCode::EVMC_SUCCESS {
+                mismatches.push(Mismatch::UnexpectedException {
+                    expected: "success".into(),
+                    got: Some(actual.status_code),
+                });
+                return mismatches;
+            }
+            let actual_gas_used = gas_limit.saturating_sub(actual.gas_left as u64);
+            if actual_gas_used != *gas_used {
+                mismatches.push(Mismatch::GasUsed {
+                    expected: *gas_used,
+                    got: actual_gas_used,
+                });
+            }
+            let actual_output = actual.output().unwrap_or(&[]);
+            if actual_output != output.as_slice() {
+                mismatches.push(Mismatch::Output {
+                    expected: output.clone(),
+                    got: actual_output.to_vec(),
+                });
+            }
+        }
+    }
+    mismatches
+}
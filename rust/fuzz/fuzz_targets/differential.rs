@@ -0,0 +1,87 @@
+#![no_main]
+
+use std::cell::RefCell;
+
+use common::{
+    evmc_vm::{ffi::evmc_message, MessageKind, Revision, Uint256},
+    MockExecutionContextTrait,
+};
+use driver::{
+    host_interface::mocked_host_interface,
+    reference_vm::{ReferenceVm, ReferenceVmError},
+    Instance,
+};
+use libfuzzer_sys::fuzz_target;
+
+fn arbitrary_message(code: &[u8], input: &[u8], gas: i64) -> evmc_message {
+    evmc_message {
+        kind: MessageKind::EVMC_CALL,
+        flags: 0,
+        depth: 0,
+        gas,
+        recipient: Default::default(),
+        sender: Default::default(),
+        input_data: input.as_ptr(),
+        input_size: input.len(),
+        value: Default::default(),
+        create2_salt: Default::default(),
+        code_address: Default::default(),
+        code: code.as_ptr(),
+        code_size: code.len(),
+        code_hash: std::ptr::null(),
+    }
+}
+
+/// A context whose answers are fixed ahead of time rather than derived from the fuzz input, so
+/// that both VMs see identical host state without the two runs being able to observe (and thus
+/// diverge on) each other - mockall's `return_const` expectations are deterministic and may be
+/// called any number of times, which is exactly what driving the same mock through two full
+/// executions needs.
+fn deterministic_context() -> MockExecutionContextTrait {
+    let mut context = MockExecutionContextTrait::new();
+    context.expect_get_tx_context().return_const(Default::default());
+    context.expect_account_exists().return_const(false);
+    context.expect_get_storage().return_const(Uint256::default());
+    context.expect_get_balance().return_const(Uint256::default());
+    context.expect_get_code_size().return_const(0usize);
+    context.expect_get_block_hash().return_const(Uint256::default());
+    context
+}
+
+thread_local! {
+    /// The reference VM, loaded once per fuzz process rather than once per input; `None` when
+    /// none is configured.
+    static REFERENCE: RefCell<Option<ReferenceVm>> = RefCell::new(match ReferenceVm::from_env() {
+        Ok(reference) => Some(reference),
+        Err(ReferenceVmError::NotConfigured) => None,
+        Err(err) => panic!("failed to load reference VM: {err}"),
+    });
+}
+
+/// Run `code` through `evmrs` and, if [`ReferenceVm::from_env`] finds one configured, through a
+/// second EVMC implementation, then assert the two agree bit-for-bit on `status_code`,
+/// `gas_left`, `gas_refund`, and `output`. Without a reference VM configured this only checks that
+/// `evmrs` itself doesn't panic, same as `evmc_execute`'s target - it degrades rather than fails
+/// so the corpus stays usable in environments where no reference implementation is available.
+fuzz_target!(|data: (Vec<u8>, Vec<u8>, i64)| {
+    let (code, input, gas) = data;
+    let gas = gas.clamp(0, 100_000_000); // see go/ct/evm_fuzz_test.go
+
+    let message = arbitrary_message(&code, &input, gas);
+    let host = mocked_host_interface();
+
+    let mut evmrs = Instance::default();
+    let mut context = deterministic_context();
+    let evmrs_result = evmrs.run(&host, &mut context, Revision::EVMC_CANCUN, &message, &code);
+
+    let Some(mut reference) = REFERENCE.take() else {
+        return;
+    };
+    let reference_result = reference.run(&host, &mut context, Revision::EVMC_CANCUN, &message, &code);
+    REFERENCE.set(Some(reference));
+
+    assert_eq!(evmrs_result.status_code, reference_result.status_code);
+    assert_eq!(evmrs_result.gas_left, reference_result.gas_left);
+    assert_eq!(evmrs_result.gas_refund, reference_result.gas_refund);
+    assert_eq!(evmrs_result.output(), reference_result.output());
+});
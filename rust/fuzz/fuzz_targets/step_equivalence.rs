@@ -0,0 +1,111 @@
+#![no_main]
+
+use common::{
+    evmc_vm::{ffi::evmc_message, MessageKind, Revision, StepStatusCode, Uint256},
+    MockExecutionContextTrait,
+};
+use driver::{host_interface::mocked_host_interface, Instance, SteppableInstance};
+use libfuzzer_sys::fuzz_target;
+
+/// Upper bound on the number of single-step calls to make before giving up on an input, so an
+/// infinite loop in the contract under test cannot hang the fuzzer.
+const MAX_STEPS: usize = 100_000;
+
+fn arbitrary_message(code: &[u8], input: &[u8], gas: i64) -> evmc_message {
+    evmc_message {
+        kind: MessageKind::EVMC_CALL,
+        flags: 0,
+        depth: 0,
+        gas,
+        recipient: Default::default(),
+        sender: Default::default(),
+        input_data: input.as_ptr(),
+        input_size: input.len(),
+        value: Default::default(),
+        create2_salt: Default::default(),
+        code_address: Default::default(),
+        code: code.as_ptr(),
+        code_size: code.len(),
+        code_hash: std::ptr::null(),
+    }
+}
+
+fn unused_context() -> MockExecutionContextTrait {
+    let mut context = MockExecutionContextTrait::new();
+    context.expect_get_tx_context().return_const(Default::default());
+    context.expect_account_exists().return_const(false);
+    context.expect_get_storage().return_const(Uint256::default());
+    context.expect_get_balance().return_const(Uint256::default());
+    context.expect_get_code_size().return_const(0usize);
+    context.expect_get_block_hash().return_const(Uint256::default());
+    context
+}
+
+/// Run `code` to completion through `Instance::run`, then again one step at a time through
+/// `SteppableInstance::run`, and check that the latter reaches the same final status, gas left,
+/// gas refund, and output as the former. A mismatch would mean `CodeReader`'s PC handling, push-data
+/// reads, or jump validation disagree between the one-shot and the steppable dispatch loop, even
+/// though both ultimately execute the same opcode sequence.
+fuzz_target!(|data: (Vec<u8>, Vec<u8>, i64)| {
+    let (code, input, gas) = data;
+    let gas = gas.clamp(0, 100_000_000); // see go/ct/evm_fuzz_test.go
+
+    let message = arbitrary_message(&code, &input, gas);
+    let host = mocked_host_interface();
+
+    let mut oneshot = Instance::default();
+    let mut oneshot_context = unused_context();
+    let oneshot_result = oneshot.run(
+        &host,
+        &mut oneshot_context,
+        Revision::EVMC_CANCUN,
+        &message,
+        &code,
+    );
+
+    let mut stepped = SteppableInstance::default();
+    let mut stepped_context = unused_context();
+
+    let mut status = StepStatusCode::EVMC_STEP_RUNNING;
+    let mut pc = 0u64;
+    let mut gas_refund = 0i64;
+    let mut stack = Vec::new();
+    let mut memory = Vec::new();
+    let mut last_call_return_data = Vec::new();
+    let mut stepped_result = None;
+
+    for _ in 0..MAX_STEPS {
+        if status != StepStatusCode::EVMC_STEP_RUNNING {
+            break;
+        }
+        let result = stepped.run(
+            &host,
+            &mut stepped_context,
+            Revision::EVMC_CANCUN,
+            &message,
+            &code,
+            status,
+            pc,
+            gas_refund,
+            &mut stack,
+            &mut memory,
+            &mut last_call_return_data,
+            1,
+        );
+        status = result.step_status_code;
+        pc = result.pc;
+        gas_refund = result.gas_refund;
+        stack = result.stack.clone();
+        memory = result.memory.clone();
+        last_call_return_data = result.last_call_return_data.clone().unwrap_or_default();
+        stepped_result = Some(result);
+    }
+
+    let Some(stepped_result) = stepped_result else {
+        return;
+    };
+    assert_eq!(oneshot_result.status_code, stepped_result.status_code);
+    assert_eq!(oneshot_result.gas_left, stepped_result.gas_left);
+    assert_eq!(oneshot_result.gas_refund, stepped_result.gas_refund);
+    assert_eq!(oneshot_result.output(), stepped_result.output.as_deref());
+});
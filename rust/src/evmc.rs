@@ -1,26 +1,61 @@
-use std::process;
+use std::{fs::OpenOptions, path::PathBuf};
+#[cfg(feature = "interrupt")]
+use std::{
+    sync::{atomic::AtomicBool, Arc},
+    time::{Duration, Instant},
+};
 
 use evmc_vm::{
-    ffi::evmc_capabilities, EvmcVm, ExecutionContext, ExecutionMessage, ExecutionResult, Revision,
+    EvmcVm, ExecutionContext, ExecutionMessage, ExecutionResult, Revision,
     StatusCode as EvmcStatusCode, StepResult, StepStatusCode as EvmcStepStatusCode,
     SteppableEvmcVm, Uint256,
 };
+use tosca_declare_vm::tosca_declare_vm;
 
 use crate::{
-    ffi::EVMC_CAPABILITY,
     interpreter::Interpreter,
-    types::{LoggingObserver, Memory, NoOpObserver, ObserverType, Stack},
+    types::{
+        ExecutionContextTrait, LoggingObserver, Memory, NoOpObserver, ObserverType, Precompile,
+        Stack, StructuredTracer,
+    },
     u256,
 };
+#[cfg(feature = "interrupt")]
+use crate::types::Interrupt;
 
 pub struct EvmRs {
     observer_type: ObserverType,
+    /// Where `ObserverType::Eip3155` traces go: `None` is stdout, `Some` is a file path set via
+    /// `set_option("trace", <path>)`. Reopened in append mode on every `execute` call rather than
+    /// held open across calls, consistent with how every other observer writer here is built
+    /// fresh per call.
+    trace_path: Option<PathBuf>,
+    /// A hard op-count budget set via `set_option("max-steps", <n>)`, applied fresh to every
+    /// `Interpreter` this instance builds - see `types::interrupt` for why gas alone doesn't bound
+    /// wall-clock work for a fuzzer-controlled message.
+    #[cfg(feature = "interrupt")]
+    max_steps: Option<u64>,
+    /// A wall-clock budget set via `set_option("timeout-ms", <n>)`, measured from the start of
+    /// each `execute`/`step_n` call rather than stored as a fixed deadline, so the same instance
+    /// can be reused call after call.
+    #[cfg(feature = "interrupt")]
+    timeout: Option<Duration>,
 }
 
+// Generates `evmc_create_evmrs` plus the `destroy`/`execute`/`get_capabilities`/`set_option`
+// trampolines that used to be hand-written - see `tosca_declare_vm`. `evmrs` dispatches
+// precompiles itself (see `types::precompiles`) rather than relying on the host, hence
+// `precompiles` alongside `evm` here.
+#[tosca_declare_vm("evmrs", "evm,precompiles", "0.1.0")]
 impl EvmcVm for EvmRs {
     fn init() -> Self {
         EvmRs {
             observer_type: ObserverType::NoOp,
+            trace_path: None,
+            #[cfg(feature = "interrupt")]
+            max_steps: None,
+            #[cfg(feature = "interrupt")]
+            timeout: None,
         }
     }
 
@@ -31,32 +66,131 @@ impl EvmcVm for EvmRs {
         message: &'a ExecutionMessage,
         context: Option<&'a mut ExecutionContext<'a>>,
     ) -> ExecutionResult {
-        assert_ne!(
-            EVMC_CAPABILITY,
-            evmc_capabilities::EVMC_CAPABILITY_PRECOMPILES
-        );
-        let Some(context) = context else {
-            // Since EVMC_CAPABILITY_PRECOMPILES is not supported context must be set.
-            // If this is not the case it violates the EVMC spec and is an irrecoverable error.
-            process::abort();
-        };
-        let interpreter = Interpreter::new(revision, message, context, code);
-        match self.observer_type {
-            ObserverType::NoOp => interpreter.run(&mut NoOpObserver()),
-            ObserverType::Logging => interpreter.run(&mut LoggingObserver::new(std::io::stdout())),
-        }
+        self.run(revision, code, message, context)
     }
 
     fn set_option(&mut self, key: &str, value: &str) -> Result<(), evmc_vm::SetOptionError> {
         match (key, value) {
             ("logging", "true") => self.observer_type = ObserverType::Logging,
             ("logging", "false") => self.observer_type = ObserverType::NoOp,
+            ("trace", "true" | "stdout") => {
+                self.observer_type = ObserverType::Eip3155;
+                self.trace_path = None;
+            }
+            ("trace", "false") => {
+                self.observer_type = ObserverType::NoOp;
+                self.trace_path = None;
+            }
+            ("trace", "") => return Err(evmc_vm::SetOptionError::InvalidValue),
+            ("trace", path) => {
+                // Opened (and dropped) here purely to validate the path eagerly, so a bad one is
+                // reported at `set_option` time rather than discovered mid-trace; `execute`
+                // reopens it in append mode on every call.
+                if OpenOptions::new().create(true).append(true).open(path).is_err() {
+                    return Err(evmc_vm::SetOptionError::InvalidValue);
+                }
+                self.observer_type = ObserverType::Eip3155;
+                self.trace_path = Some(PathBuf::from(path));
+            }
+            ("tracing", "json") => self.observer_type = ObserverType::Tracing,
+            #[cfg(feature = "interrupt")]
+            ("max-steps", "") => self.max_steps = None,
+            #[cfg(feature = "interrupt")]
+            ("max-steps", n) => {
+                self.max_steps =
+                    Some(n.parse().map_err(|_| evmc_vm::SetOptionError::InvalidValue)?)
+            }
+            #[cfg(feature = "interrupt")]
+            ("timeout-ms", "") => self.timeout = None,
+            #[cfg(feature = "interrupt")]
+            ("timeout-ms", n) => {
+                self.timeout = Some(Duration::from_millis(
+                    n.parse().map_err(|_| evmc_vm::SetOptionError::InvalidValue)?,
+                ))
+            }
             _ => (),
         }
         Ok(())
     }
 }
 
+impl EvmRs {
+    /// Safe, non-FFI core of [`EvmcVm::execute`] - takes typed `evmc_vm` values instead of raw
+    /// `evmc_message`/`evmc_host_interface`/code pointers, so an embedder running this crate
+    /// in-process as a plain Rust dependency never needs to touch the C ABI at all.
+    /// `__tosca_declare_vm_execute` unpacks the host's raw pointers into exactly these types and
+    /// calls this directly; `execute` above is a one-line forward to it so both entry points stay
+    /// in sync by construction. A `None` host runs fine for a precompile (`host` is then unused)
+    /// or for bytecode that turns out to be pure arithmetic/memory/stack; [`Interpreter`] only
+    /// reaches for it lazily, per opcode, so a genuinely missing host is discovered - and fails
+    /// gracefully with `FailStatus::MissingHost` rather than a null dereference or aborting the
+    /// embedding process - only once an opcode that actually needs one runs.
+    pub fn run<'a>(
+        &self,
+        revision: Revision,
+        code: &'a [u8],
+        message: &'a ExecutionMessage,
+        host: Option<&'a mut ExecutionContext<'a>>,
+    ) -> ExecutionResult {
+        if let Some(precompile) = Precompile::from_address(message.recipient(), revision) {
+            return precompile.call(message.gas(), message.input().unwrap_or(&[]));
+        }
+        // A `None` host is only valid for bytecode that never touches chain state - the
+        // interpreter itself enforces that by failing any opcode that reaches for `context` with
+        // `FailStatus::MissingHost` instead of panicking.
+        let context = host.map(|context| context as &mut dyn ExecutionContextTrait);
+        let interpreter = match Interpreter::new(revision, message, context, code) {
+            Ok(interpreter) => interpreter,
+            Err(status) => return status.into(),
+        };
+        #[cfg(feature = "interrupt")]
+        let interpreter = match self.watchdog() {
+            Some(interrupt) => interpreter.with_interrupt(interrupt),
+            None => interpreter,
+        };
+        match self.observer_type {
+            ObserverType::NoOp => interpreter.run(&mut NoOpObserver()),
+            ObserverType::Logging => interpreter.run(&mut LoggingObserver::new(std::io::stdout())),
+            ObserverType::Eip3155 => match self.open_trace_writer() {
+                Some(writer) => interpreter.run(&mut StructuredTracer::new(writer)),
+                None => interpreter.run(&mut StructuredTracer::new(std::io::stdout())),
+            },
+            ObserverType::Tracing => interpreter.run(&mut StructuredTracer::new(std::io::stdout())),
+        }
+    }
+
+    /// The file `set_option("trace", <path>)` pointed at, reopened in append mode, or `None` for
+    /// the `"trace" == "true"`/`"stdout"` case that traces to stdout instead. The path was already
+    /// validated when it was set, but the file can still disappear out from under a long-lived
+    /// VM instance between calls, so a reopen failure here falls back to stdout rather than
+    /// silently dropping the trace.
+    fn open_trace_writer(&self) -> Option<std::fs::File> {
+        let path = self.trace_path.as_ref()?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .ok()
+    }
+
+    /// An [`Interrupt`] built fresh for this call from whatever `max-steps`/`timeout-ms` were last
+    /// set, or `None` if neither is configured - `Interrupt::new` always wants a cancellation flag,
+    /// so this hands it one nobody outside this call can reach, rather than plumbing a real
+    /// cross-thread cancellation source through the EVMC ABI (there's no option key for that yet).
+    #[cfg(feature = "interrupt")]
+    fn watchdog(&self) -> Option<Interrupt> {
+        if self.max_steps.is_none() && self.timeout.is_none() {
+            return None;
+        }
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let interrupt = Interrupt::new(Arc::new(AtomicBool::new(false)), deadline);
+        Some(match self.max_steps {
+            Some(max_steps) => interrupt.with_max_steps(max_steps),
+            None => interrupt,
+        })
+    }
+}
+
 impl SteppableEvmcVm for EvmRs {
     fn step_n<'a>(
         &self,
@@ -96,16 +230,37 @@ impl SteppableEvmcVm for EvmRs {
                 },
             );
         }
-        assert_ne!(
-            EVMC_CAPABILITY,
-            evmc_capabilities::EVMC_CAPABILITY_PRECOMPILES
-        );
-        let Some(context) = context else {
-            // Since EVMC_CAPABILITY_PRECOMPILES is not supported context must be set.
-            // If this is not the case it violates the EVMC spec and is an irrecoverable error.
-            process::abort();
+        if let Some(precompile) = Precompile::from_address(message.recipient(), revision) {
+            let result = precompile.call(message.gas(), message.input().unwrap_or(&[]));
+            return StepResult::new(
+                if result.status_code() == EvmcStatusCode::EVMC_SUCCESS {
+                    EvmcStepStatusCode::EVMC_STEP_RETURNED
+                } else {
+                    EvmcStepStatusCode::EVMC_STEP_FAILED
+                },
+                result.status_code(),
+                revision,
+                pc,
+                result.gas_left(),
+                gas_refund,
+                result.output().map(ToOwned::to_owned),
+                stack.to_owned(),
+                memory.to_owned(),
+                if last_call_return_data.is_empty() {
+                    None
+                } else {
+                    Some(last_call_return_data.to_owned())
+                },
+            );
+        }
+        // A `None` context is fine here too: a precompile address (handled above) is the only
+        // case that needs no host at all, but `Interpreter` also tolerates bytecode that simply
+        // never reaches for one, failing with `FailStatus::MissingHost` only if it does.
+        let context = context.map(|context| context as &mut dyn ExecutionContextTrait);
+        let stack = match Stack::new(&stack.iter().map(|i| u256::from(*i)).collect::<Vec<_>>()) {
+            Ok(stack) => stack,
+            Err(status) => return status.into(),
         };
-        let stack = Stack::new(&stack.iter().map(|i| u256::from(*i)).collect::<Vec<_>>());
         let memory = Memory::new(memory.to_owned());
         let interpreter = Interpreter::new_steppable(
             revision,
@@ -119,9 +274,21 @@ impl SteppableEvmcVm for EvmRs {
             Some(last_call_return_data.to_owned()),
             Some(steps),
         );
+        #[cfg(feature = "interrupt")]
+        let interpreter = match self.watchdog() {
+            Some(interrupt) => interpreter.with_interrupt(interrupt),
+            None => interpreter,
+        };
         match self.observer_type {
             ObserverType::NoOp => interpreter.run(&mut NoOpObserver()),
             ObserverType::Logging => interpreter.run(&mut LoggingObserver::new(std::io::stdout())),
+            // Same upgrade to `StructuredTracer` as the non-steppable `run` above, for the same
+            // reason: `trace` promises gasCost and a final summary line, which only it writes.
+            ObserverType::Eip3155 => match self.open_trace_writer() {
+                Some(writer) => interpreter.run(&mut StructuredTracer::new(writer)),
+                None => interpreter.run(&mut StructuredTracer::new(std::io::stdout())),
+            },
+            ObserverType::Tracing => interpreter.run(&mut StructuredTracer::new(std::io::stdout())),
         }
     }
 }
@@ -1,8 +1,9 @@
 //! This module implements the functions for the [`SteppableEvmcVm`] interface which are called
 //! from the host language via FFI. The functions in this module only check the provided
 //! arguments for validity, map them to Rust types and then call the business logic.
-//! This is in essence what evmc_declare::evmc_declare_vm generates, but for [`SteppableEvmcVm`]
-//! instead of [`EvmcVm`](evmc_vm::EvmcVm).
+//! `#[tosca_declare_vm]` (see `crate::evmc`) generates the equivalent glue for
+//! [`EvmcVm`](evmc_vm::EvmcVm); `SteppableEvmcVm` isn't understood by that macro yet, so its
+//! trampolines are still hand-written here.
 
 use std::slice;
 
@@ -13,7 +14,7 @@ use crate::EvmRs;
 #[no_mangle]
 extern "C" fn evmc_create_steppable_evmrs() -> *const ::evmc_vm::ffi::evmc_vm_steppable {
     let new_instance = ::evmc_vm::ffi::evmc_vm_steppable {
-        vm: crate::evmc_create_evmrs() as *mut ::evmc_vm::ffi::evmc_vm,
+        vm: crate::evmc::evmc_create_evmrs() as *mut ::evmc_vm::ffi::evmc_vm,
         step_n: Some(__evmc_step_n),
         destroy: Some(__evmc_steppable_destroy),
     };
@@ -30,8 +31,9 @@ extern "C" fn __evmc_steppable_destroy(instance: *mut ::evmc_vm::ffi::evmc_vm_st
     }
 }
 
-// must be defined in evmc_declare_vm
-const EVMC_CAPABILITY_PRECOMPILES: bool = false;
+// `EvmRs` now dispatches precompiles natively (see `types::precompiles`), so a host is no longer
+// required to serve calls into `0x01..=0x0a` and the null-host guard below can let them through.
+const EVMC_CAPABILITY_PRECOMPILES: bool = true;
 
 #[no_mangle]
 extern "C" fn __evmc_step_n(
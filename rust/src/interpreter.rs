@@ -1,18 +1,30 @@
-use std::cmp::min;
+pub(crate) mod access_list;
+pub(crate) mod journal;
+
+use std::{cmp::min, fmt, mem};
 
 use evmc_vm::{
-    AccessStatus, ExecutionMessage, ExecutionResult, MessageFlags, MessageKind, Revision,
-    StatusCode, StepResult, StorageStatus, Uint256,
+    AccessStatus, Address, ExecutionMessage, ExecutionResult, MessageFlags, MessageKind, Revision,
+    StatusCode, StepResult, Uint256,
 };
 
 #[cfg(not(feature = "needs-fn-ptr-conversion"))]
 use crate::types::Opcode;
+#[cfg(feature = "interrupt")]
+use crate::types::{Interrupt, INTERRUPT_CHECK_INTERVAL};
+#[cfg(feature = "external-module")]
+use crate::types::ExternalModuleRegistry;
 use crate::{
+    interpreter::{access_list::AccessList, journal::Journal},
     types::{
-        hash_cache, u256, CodeReader, ExecStatus, ExecutionContextTrait, ExecutionTxContext,
-        FailStatus, GetOpcodeError, Memory, Observer, Stack,
+        hash_cache, is_frame_op, min_revision, u256, CodeReader, ExecStatus, ExecutionContextTrait,
+        ExecutionTxContext, FailStatus, GasLeft, GetOpcodeError, Memory, Observer, Precompile,
+        Stack,
+    },
+    utils::{
+        check_min_revision, check_not_read_only, sstore_gas_and_refund, word_size, Gas, GasRefund,
+        GasSchedule, SliceExt,
     },
-    utils::{check_min_revision, check_not_read_only, word_size, Gas, GasRefund, SliceExt},
 };
 
 type OpResult = Result<(), FailStatus>;
@@ -22,6 +34,13 @@ pub type OpFn<const STEPPABLE: bool> = fn(&mut Interpreter<STEPPABLE>) -> OpResu
 
 // The closures here are necessary because methods capture the lifetime of the type which we
 // want to avoid.
+// `gen_jumptable` builds the 256-entry, opcode-byte-indexed dispatch table behind the
+// `jumptable-dispatch` and `needs-fn-ptr-conversion` features: `run_op`/`jumptable_lookup` then
+// turn decoding an opcode into a single array index and call, instead of the branch chain the
+// plain `match` in `run_op` below compiles to. It's generated directly from the handler methods
+// below (`stop`, `add`, `mul`, ...) so each handler's gas charge and revision gate stays exactly
+// where the `match`-based dispatch keeps it - this table is just a second, flatter way to reach
+// the same functions.
 #[cfg(feature = "needs-jumptable")]
 const fn gen_jumptable<const STEPPABLE: bool>() -> [OpFn<STEPPABLE>; 256] {
     [
@@ -66,6 +85,15 @@ const fn gen_jumptable<const STEPPABLE: bool>() -> [OpFn<STEPPABLE>; 256] {
         |i| i.jumptable_placeholder(),
         #[cfg(not(feature = "needs-fn-ptr-conversion"))]
         |i| i.jumptable_placeholder(),
+        #[cfg(all(
+            feature = "fn-ptr-conversion-expanded-dispatch",
+            feature = "superinstruction-fusion"
+        ))]
+        |i| i.fused_push1_add(),
+        #[cfg(not(all(
+            feature = "fn-ptr-conversion-expanded-dispatch",
+            feature = "superinstruction-fusion"
+        )))]
         |i| i.jumptable_placeholder(),
         |i| i.jumptable_placeholder(),
         |i| i.jumptable_placeholder(),
@@ -320,29 +348,128 @@ pub struct Interpreter<'a, const STEPPABLE: bool> {
     pub message: &'a ExecutionMessage,
     #[cfg(feature = "custom-evmc")]
     pub message: &'a ExecutionMessage<'a>,
-    pub context: &'a mut dyn ExecutionContextTrait,
+    /// `None` for a context-less frame running pure arithmetic/memory/stack bytecode with no host
+    /// at all (mirroring `ExecutionContext`'s own evolution to `Option<&mut ExecutionContext>` on
+    /// the EVMC side); any opcode that needs one goes through [`require_host`] instead of touching
+    /// this directly, so reaching for a host that isn't there fails with
+    /// [`FailStatus::MissingHost`] rather than panicking.
+    pub context: Option<&'a mut dyn ExecutionContextTrait>,
     pub revision: Revision,
     pub code_reader: CodeReader<'a, STEPPABLE>,
     pub gas_left: Gas,
     pub gas_refund: GasRefund,
-    #[cfg(not(feature = "custom-evmc"))]
-    pub output: Option<Vec<u8>>,
-    #[cfg(feature = "custom-evmc")]
-    pub output: Option<Box<[u8]>>,
+    /// The chain-specific constants backing `gas_left`'s `consume_*` methods, looked up once from
+    /// `revision` here rather than on every opcode that charges one of them.
+    pub gas_schedule: GasSchedule,
+    /// Set by `RETURN`/`REVERT` instead of paying for and reading the output buffer immediately;
+    /// `None` for every other exit path. [`GasLeft::finalize`] is what actually turns this into
+    /// the final gas left and output, once, when the run loop is done.
+    pub pending_output: Option<(u256, u64)>,
     pub stack: Stack,
     pub memory: Memory,
     pub last_call_return_data: Option<Vec<u8>>,
     pub steps: Option<i32>,
+    /// Cooperative cancellation source polled every [`INTERRUPT_CHECK_INTERVAL`] ops; `None`
+    /// means never interrupted. Set via [`with_interrupt`](Self::with_interrupt) after
+    /// construction rather than threading it through `new`/`new_steppable`, since most callers
+    /// don't want one.
+    #[cfg(feature = "interrupt")]
+    pub interrupt: Option<Interrupt>,
+    /// Counts down from [`INTERRUPT_CHECK_INTERVAL`] to `0` between `interrupt` polls.
+    #[cfg(feature = "interrupt")]
+    interrupt_counter: u32,
+    /// EIP-2929 warm/cold bookkeeping for this call frame, tracked locally instead of relying on
+    /// the host to get `access_account`/`access_storage` right.
+    pub access_list: AccessList,
+    /// Records every [`access_list`](Self::access_list) and [`gas_refund`](Self::gas_refund)
+    /// change made during this frame, so they can be rolled back if the frame reverts.
+    journal: Journal,
+    /// Set instead of dispatching a CALL/CALLCODE/STATICCALL/DELEGATECALL/CREATE/CREATE2 to the
+    /// host when `STEPPABLE`: [`Interpreter::run_resumable`] turns this into
+    /// [`Execution::Trapped`] rather than blocking on [`ExecutionContextTrait::call`] itself. Only
+    /// ever populated for `STEPPABLE == true`; see [`PendingCall`].
+    #[cfg(feature = "call-trap")]
+    pending_trap: Option<PendingCall>,
+    /// Third-party EVMC modules registered against specific addresses; checked by
+    /// [`dispatch_call`](Self::dispatch_call) ahead of the host, the same way [`Precompile`] is.
+    /// `None` unless opted into via [`with_external_modules`](Self::with_external_modules) - most
+    /// callers don't have one.
+    #[cfg(feature = "external-module")]
+    pub external_modules: Option<&'a mut ExternalModuleRegistry>,
+}
+
+/// Every opcode that needs to reach outside this frame - state, logs, block/tx context,
+/// CALL/CREATE - goes through this rather than touching `context` directly, so a `None` host
+/// (valid only for the pure arithmetic/memory/stack bytecode a context-less [`Interpreter::new`]/
+/// [`new_steppable`] was built for) fails deterministically instead of being dereferenced. Takes
+/// `&mut Option<..>` rather than being a method on `Interpreter` so the borrow checker still sees
+/// it as touching only the `context` field, same as a direct `self.context.foo()` call would -
+/// exactly what lets this sit inside `self.stack.push(require_host(&mut self.context)?...)`
+/// without fighting the borrow of `self.stack`.
+fn require_host<'a, 'b>(
+    context: &'b mut Option<&'a mut dyn ExecutionContextTrait>,
+) -> Result<&'b mut dyn ExecutionContextTrait, FailStatus> {
+    context.as_deref_mut().ok_or(FailStatus::MissingHost)
+}
+
+/// The addresses pre-warmed per EIP-2929 before the first instruction runs: the transaction's
+/// `sender`/`recipient` and every precompile address always, plus `block_coinbase` from EIP-3651
+/// (Shanghai) onward. Unlike era_vm's `World`, which pre-warms straight from the tx's EIP-2930
+/// access list, `evmc_tx_context` carries no such list - a host relying on one to pre-warm extra
+/// addresses/storage keys cannot be satisfied here, so those simply price as cold the first time
+/// this interpreter's own [`AccessList`] sees them. A context-less frame (`context: None`) simply
+/// skips the `block_coinbase` entry - there is no host to ask for one, and a pure bytecode program
+/// that cannot touch `COINBASE` in the first place has no use for it being warm anyway.
+fn prewarmed_addresses(
+    revision: Revision,
+    message: &ExecutionMessage,
+    context: Option<&mut dyn ExecutionContextTrait>,
+) -> impl Iterator<Item = Address> {
+    [*message.sender(), *message.recipient()]
+        .into_iter()
+        .chain(Precompile::addresses(revision))
+        .chain(
+            (revision >= Revision::EVMC_SHANGHAI)
+                .then(|| context.map(|context| context.get_tx_context().block_coinbase))
+                .flatten(),
+        )
+}
+
+/// Builds a fresh call frame's [`AccessList`], pre-warmed per [`prewarmed_addresses`]. At depth 0
+/// (the transaction's top-level call, never a nested CALL/DELEGATECALL/CREATE) also warms those
+/// same addresses in the host, if one is present: [`AccessList`] now falls back to the host on a
+/// local-cache miss (see the [`access_list`] module docs) precisely so warm/cold status survives
+/// across frame boundaries, so the host needs to agree from the start of the transaction that
+/// these addresses are warm, or a later frame falling back to it would wrongly see them as cold.
+/// Nested frames don't repeat this: their own sender/recipient were already warmed in the host by
+/// the enclosing frame's own address-access charge before it dispatched the call, and precompile
+/// addresses are locally pre-warmed in every frame regardless, so they never reach the host at all.
+fn build_access_list(
+    revision: Revision,
+    message: &ExecutionMessage,
+    mut context: Option<&mut dyn ExecutionContextTrait>,
+) -> AccessList {
+    let prewarmed: Vec<Address> =
+        prewarmed_addresses(revision, message, context.as_deref_mut()).collect();
+    if message.depth() == 0 {
+        if let Some(context) = context.as_deref_mut() {
+            for address in &prewarmed {
+                context.access_account(address);
+            }
+        }
+    }
+    AccessList::new(prewarmed)
 }
 
 impl<'a> Interpreter<'a, false> {
     pub fn new(
         revision: Revision,
         message: &'a ExecutionMessage,
-        context: &'a mut dyn ExecutionContextTrait,
+        mut context: Option<&'a mut dyn ExecutionContextTrait>,
         code: &'a [u8],
-    ) -> Self {
-        Self {
+    ) -> Result<Self, FailStatus> {
+        let access_list = build_access_list(revision, message, context.as_deref_mut());
+        Ok(Self {
             exec_status: ExecStatus::Running,
             message,
             context,
@@ -350,12 +477,23 @@ impl<'a> Interpreter<'a, false> {
             code_reader: CodeReader::new(code, message.code_hash().map(|h| u256::from(*h)), 0),
             gas_left: Gas::new(message.gas()),
             gas_refund: GasRefund::new(0),
-            output: None,
-            stack: Stack::new(&[]),
+            gas_schedule: GasSchedule::for_revision(revision),
+            pending_output: None,
+            stack: Stack::new(&[])?,
             memory: Memory::new(&[]),
             last_call_return_data: None,
             steps: None,
-        }
+            #[cfg(feature = "interrupt")]
+            interrupt: None,
+            #[cfg(feature = "interrupt")]
+            interrupt_counter: INTERRUPT_CHECK_INTERVAL,
+            access_list,
+            journal: Journal::new(),
+            #[cfg(feature = "call-trap")]
+            pending_trap: None,
+            #[cfg(feature = "external-module")]
+            external_modules: None,
+        })
     }
 }
 
@@ -364,7 +502,7 @@ impl<'a> Interpreter<'a, true> {
     pub fn new_steppable(
         revision: Revision,
         message: &'a ExecutionMessage,
-        context: &'a mut dyn ExecutionContextTrait,
+        mut context: Option<&'a mut dyn ExecutionContextTrait>,
         code: &'a [u8],
         pc: usize,
         gas_refund: i64,
@@ -373,6 +511,7 @@ impl<'a> Interpreter<'a, true> {
         last_call_return_data: Option<Vec<u8>>,
         steps: Option<i32>,
     ) -> Self {
+        let access_list = build_access_list(revision, message, context.as_deref_mut());
         Self {
             exec_status: ExecStatus::Running,
             message,
@@ -381,16 +520,129 @@ impl<'a> Interpreter<'a, true> {
             code_reader: CodeReader::new(code, message.code_hash().map(|h| u256::from(*h)), pc),
             gas_left: Gas::new(message.gas()),
             gas_refund: GasRefund::new(gas_refund),
-            output: None,
+            gas_schedule: GasSchedule::for_revision(revision),
+            pending_output: None,
             stack,
             memory,
             last_call_return_data,
             steps,
+            #[cfg(feature = "interrupt")]
+            interrupt: None,
+            #[cfg(feature = "interrupt")]
+            interrupt_counter: INTERRUPT_CHECK_INTERVAL,
+            access_list,
+            journal: Journal::new(),
+            #[cfg(feature = "call-trap")]
+            pending_trap: None,
+            #[cfg(feature = "external-module")]
+            external_modules: None,
         }
     }
+
+    /// Rebuilds a steppable [`Interpreter`] from a [`CallTrap`], folding `result` back into gas,
+    /// stack and memory exactly as [`Interpreter::call_or_call_code`]/
+    /// [`Interpreter::static_or_delegate_call`]/[`Interpreter::create_or_create2`] would have done
+    /// inline had they not trapped. `pc` was already advanced past the triggering op when it
+    /// trapped, so the returned interpreter picks up at the following instruction, same place
+    /// finishing that op synchronously would have left it.
+    #[cfg(feature = "call-trap")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn resume_call(
+        revision: Revision,
+        message: &'a ExecutionMessage,
+        context: Option<&'a mut dyn ExecutionContextTrait>,
+        code: &'a [u8],
+        trap: CallTrap,
+        steps: Option<i32>,
+        result: &ExecutionResult,
+    ) -> Result<Self, FailStatus> {
+        let CallTrap { call, suspended } = trap;
+        let mut interpreter = Self::new_steppable(
+            revision,
+            message,
+            context,
+            code,
+            suspended.pc,
+            suspended.gas_refund,
+            suspended.stack,
+            suspended.memory,
+            suspended.last_call_return_data,
+            steps,
+        );
+        interpreter.gas_left = Gas::new(suspended.gas_left);
+
+        if matches!(call.kind, MessageKind::EVMC_CREATE | MessageKind::EVMC_CREATE2) {
+            interpreter.gas_left.add(result.gas_left())?;
+            interpreter.gas_refund.add(result.gas_refund())?;
+            if result.status_code() == StatusCode::EVMC_SUCCESS {
+                let Some(addr) = result.create_address() else {
+                    return Err(FailStatus::InternalError);
+                };
+                interpreter.last_call_return_data = None;
+                interpreter.stack.push(addr)?;
+            } else {
+                interpreter.last_call_return_data = result.output().map(ToOwned::to_owned);
+                interpreter.stack.push(u256::ZERO)?;
+            }
+        } else {
+            interpreter.last_call_return_data = result.output().map(ToOwned::to_owned);
+            let dest = interpreter.memory.get_mut_slice(
+                call.ret_offset,
+                call.ret_len,
+                &mut interpreter.gas_left,
+            )?;
+            if let Some(output) = &interpreter.last_call_return_data {
+                let min_len = min(output.len(), call.ret_len as usize);
+                dest[..min_len].copy_from_slice(&output[..min_len]);
+            }
+            interpreter.gas_left.add(result.gas_left())?;
+            interpreter.gas_left.consume(call.endowment)?;
+            interpreter.gas_left.consume(call.stipend)?;
+            interpreter.gas_refund.add(result.gas_refund())?;
+            interpreter
+                .stack
+                .push(result.status_code() == StatusCode::EVMC_SUCCESS)?;
+        }
+
+        Ok(interpreter)
+    }
+}
+
+impl<'a, const STEPPABLE: bool> Interpreter<'a, STEPPABLE> {
+    /// Opts this run into delegating `CALL`/`CALLCODE`/`DELEGATECALL`/`STATICCALL` against a
+    /// registered address to the external module loaded there, ahead of the host - see
+    /// [`dispatch_call`](Self::dispatch_call).
+    #[cfg(feature = "external-module")]
+    pub fn with_external_modules(
+        mut self,
+        external_modules: &'a mut ExternalModuleRegistry,
+    ) -> Self {
+        self.external_modules = Some(external_modules);
+        self
+    }
 }
 
 impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
+    /// Opts this run into cooperative cancellation: every [`INTERRUPT_CHECK_INTERVAL`] ops,
+    /// `run`/`next` poll `interrupt` and bail out with [`FailStatus::Interrupted`] once it trips.
+    #[cfg(feature = "interrupt")]
+    pub fn with_interrupt(mut self, interrupt: Interrupt) -> Self {
+        self.interrupt = Some(interrupt);
+        self
+    }
+
+    /// Polls `interrupt` every [`INTERRUPT_CHECK_INTERVAL`] calls; `true` means the caller should
+    /// abort with [`FailStatus::Interrupted`] right away.
+    #[cfg(feature = "interrupt")]
+    fn interrupted(&mut self) -> bool {
+        self.interrupt_counter -= 1;
+        if self.interrupt_counter != 0 {
+            return false;
+        }
+        self.interrupt_counter = INTERRUPT_CHECK_INTERVAL;
+        self.interrupt.as_ref().is_some_and(Interrupt::tripped)
+    }
+
     /// R is expected to be [ExecutionResult] or [StepResult].
     #[cfg(not(feature = "tail-call"))]
     pub fn run<O, R>(mut self, observer: &mut O) -> R
@@ -403,6 +655,11 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
                 break;
             }
 
+            #[cfg(feature = "interrupt")]
+            if self.interrupted() {
+                return FailStatus::Interrupted.into();
+            }
+
             if STEPPABLE {
                 match &mut self.steps {
                     None => (),
@@ -412,18 +669,34 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
             }
             let op = match self.code_reader.get() {
                 Ok(op) => op,
-                Err(GetOpcodeError::OutOfRange) => {
+                Err(GetOpcodeError::OutOfRange { .. }) => {
                     self.exec_status = ExecStatus::Stopped;
                     break;
                 }
-                Err(GetOpcodeError::Invalid) => {
+                Err(GetOpcodeError::Invalid { .. }) => {
                     return FailStatus::InvalidInstruction.into();
                 }
             };
             observer.pre_op(&self);
+            let is_frame_boundary = is_frame_op(op);
+            if is_frame_boundary {
+                observer.frame_enter(&self);
+            }
             if let Err(err) = self.run_op(op) {
                 return err.into();
             }
+            // `run`'s `R` is `ExecutionResult`/`StepResult`, neither of which can express "parked
+            // mid-op, waiting on a sub-call result" - only `run_resumable` can hand that back to a
+            // caller via `Execution::Trapped`. A `call-trap` build that still calls `run` on a
+            // `STEPPABLE` interpreter gets a loud, documented failure instead of silently
+            // continuing past the untaken call/create.
+            #[cfg(feature = "call-trap")]
+            if STEPPABLE && self.pending_trap.is_some() {
+                return FailStatus::InternalError.into();
+            }
+            if is_frame_boundary {
+                observer.frame_exit(&self);
+            }
             observer.post_op(&self);
         }
 
@@ -441,11 +714,21 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         if let Err(err) = self.next() {
             return err.into();
         }
+        // See the non-`tail-call` `run` above for why a trap can't be expressed through `R` here.
+        #[cfg(feature = "call-trap")]
+        if STEPPABLE && self.pending_trap.is_some() {
+            return FailStatus::InternalError.into();
+        }
         self.into()
     }
     #[cfg(feature = "tail-call")]
     #[inline(always)]
     pub fn next(&mut self) -> OpResult {
+        #[cfg(feature = "interrupt")]
+        if self.interrupted() {
+            return Err(FailStatus::Interrupted);
+        }
+
         if STEPPABLE {
             match &mut self.steps {
                 None => (),
@@ -455,11 +738,11 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         }
         let op = match self.code_reader.get() {
             Ok(op) => op,
-            Err(GetOpcodeError::OutOfRange) => {
+            Err(GetOpcodeError::OutOfRange { .. }) => {
                 self.exec_status = ExecStatus::Stopped;
                 return Ok(());
             }
-            Err(GetOpcodeError::Invalid) => {
+            Err(GetOpcodeError::Invalid { .. }) => {
                 return Err(FailStatus::InvalidInstruction);
             }
         };
@@ -640,10 +923,15 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         return self.next();
     }
 
+    /// Fills the jumptable slots for opcode bytes that [`CodeReader`]'s analysis never hands out
+    /// an [`Opcode`] variant for (undefined opcodes), so this should be unreachable in practice -
+    /// it's here so `gen_jumptable` can stay a total function over all 256 byte values rather than
+    /// special-casing the gaps, matching what `self.invalid()` returns for the same case in the
+    /// non-jumptable `match`-based dispatch.
     #[cfg(feature = "needs-jumptable")]
     #[allow(clippy::unused_self)]
     pub fn jumptable_placeholder(&mut self) -> OpResult {
-        Err(FailStatus::Failure)
+        Err(FailStatus::InvalidInstruction)
     }
 
     #[cfg(feature = "needs-fn-ptr-conversion")]
@@ -658,6 +946,22 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         self.return_from_op()
     }
 
+    /// Fused `PUSH1 <imm>; ADD`, dispatched once in place of those two ops back to back - see
+    /// `types::superinstruction` and the `FusedPush1Add` analysis entry `analyze_code` emits for
+    /// it. Charges both ops' static gas up front and does `ADD`'s stack check before touching the
+    /// stack, same as running them separately would.
+    #[cfg(all(
+        feature = "fn-ptr-conversion-expanded-dispatch",
+        feature = "superinstruction-fusion"
+    ))]
+    pub fn fused_push1_add(&mut self) -> OpResult {
+        self.gas_left.consume(3 + 3)?;
+        let imm = self.code_reader.get_push_data();
+        let [value] = self.stack.pop()?;
+        self.stack.push(value + imm)?;
+        self.return_from_op()
+    }
+
     fn stop(&mut self) -> OpResult {
         self.exec_status = ExecStatus::Stopped;
         Ok(())
@@ -665,214 +969,214 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
 
     fn add(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [value2, value1] = self.stack.pop()?;
-        self.stack.push(value1 + value2)?;
+        let (guard, [value2, value1]) = self.stack.pop_with_guard()?;
+        guard.push(value1 + value2);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn mul(&mut self) -> OpResult {
         self.gas_left.consume(5)?;
-        let [fac2, fac1] = self.stack.pop()?;
-        self.stack.push(fac1 * fac2)?;
+        let (guard, [fac2, fac1]) = self.stack.pop_with_guard()?;
+        guard.push(fac1 * fac2);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn sub(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [value2, value1] = self.stack.pop()?;
-        self.stack.push(value1 - value2)?;
+        let (guard, [value2, value1]) = self.stack.pop_with_guard()?;
+        guard.push(value1 - value2);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn div(&mut self) -> OpResult {
         self.gas_left.consume(5)?;
-        let [denominator, value] = self.stack.pop()?;
-        self.stack.push(value / denominator)?;
+        let (guard, [denominator, value]) = self.stack.pop_with_guard()?;
+        guard.push(value / denominator);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn s_div(&mut self) -> OpResult {
         self.gas_left.consume(5)?;
-        let [denominator, value] = self.stack.pop()?;
-        self.stack.push(value.sdiv(denominator))?;
+        let (guard, [denominator, value]) = self.stack.pop_with_guard()?;
+        guard.push(value.sdiv(denominator));
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn mod_(&mut self) -> OpResult {
         self.gas_left.consume(5)?;
-        let [denominator, value] = self.stack.pop()?;
-        self.stack.push(value % denominator)?;
+        let (guard, [denominator, value]) = self.stack.pop_with_guard()?;
+        guard.push(value % denominator);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn s_mod(&mut self) -> OpResult {
         self.gas_left.consume(5)?;
-        let [denominator, value] = self.stack.pop()?;
-        self.stack.push(value.srem(denominator))?;
+        let (guard, [denominator, value]) = self.stack.pop_with_guard()?;
+        guard.push(value.srem(denominator));
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn add_mod(&mut self) -> OpResult {
         self.gas_left.consume(8)?;
-        let [denominator, value2, value1] = self.stack.pop()?;
-        self.stack.push(u256::addmod(value1, value2, denominator))?;
+        let (guard, [denominator, value2, value1]) = self.stack.pop_with_guard()?;
+        guard.push(u256::addmod(value1, value2, denominator));
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn mul_mod(&mut self) -> OpResult {
         self.gas_left.consume(8)?;
-        let [denominator, fac2, fac1] = self.stack.pop()?;
-        self.stack.push(u256::mulmod(fac1, fac2, denominator))?;
+        let (guard, [denominator, fac2, fac1]) = self.stack.pop_with_guard()?;
+        guard.push(u256::mulmod(fac1, fac2, denominator));
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn exp(&mut self) -> OpResult {
         self.gas_left.consume(10)?;
-        let [exp, value] = self.stack.pop()?;
+        let (guard, [exp, value]) = self.stack.pop_with_guard()?;
         self.gas_left.consume(exp.bits().div_ceil(8) as u64 * 50)?; // * does not overflow
-        self.stack.push(value.pow(exp))?;
+        guard.push(value.pow(exp));
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn sign_extend(&mut self) -> OpResult {
         self.gas_left.consume(5)?;
-        let [value, size] = self.stack.pop()?;
-        self.stack.push(u256::signextend(size, value))?;
+        let (guard, [value, size]) = self.stack.pop_with_guard()?;
+        guard.push(u256::signextend(size, value));
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn lt(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [rhs, lhs] = self.stack.pop()?;
-        self.stack.push(lhs < rhs)?;
+        let (guard, [rhs, lhs]) = self.stack.pop_with_guard()?;
+        guard.push(lhs < rhs);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn gt(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [rhs, lhs] = self.stack.pop()?;
-        self.stack.push(lhs > rhs)?;
+        let (guard, [rhs, lhs]) = self.stack.pop_with_guard()?;
+        guard.push(lhs > rhs);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn s_lt(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [rhs, lhs] = self.stack.pop()?;
-        self.stack.push(lhs.slt(&rhs))?;
+        let (guard, [rhs, lhs]) = self.stack.pop_with_guard()?;
+        guard.push(lhs.slt(&rhs));
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn s_gt(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [rhs, lhs] = self.stack.pop()?;
-        self.stack.push(lhs.sgt(&rhs))?;
+        let (guard, [rhs, lhs]) = self.stack.pop_with_guard()?;
+        guard.push(lhs.sgt(&rhs));
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn eq(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [rhs, lhs] = self.stack.pop()?;
-        self.stack.push(lhs == rhs)?;
+        let (guard, [rhs, lhs]) = self.stack.pop_with_guard()?;
+        guard.push(lhs == rhs);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn is_zero(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [value] = self.stack.pop()?;
-        self.stack.push(value == u256::ZERO)?;
+        let (guard, [value]) = self.stack.pop_with_guard()?;
+        guard.push(value == u256::ZERO);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn and(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [rhs, lhs] = self.stack.pop()?;
-        self.stack.push(lhs & rhs)?;
+        let (guard, [rhs, lhs]) = self.stack.pop_with_guard()?;
+        guard.push(lhs & rhs);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn or(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [rhs, lhs] = self.stack.pop()?;
-        self.stack.push(lhs | rhs)?;
+        let (guard, [rhs, lhs]) = self.stack.pop_with_guard()?;
+        guard.push(lhs | rhs);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn xor(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [rhs, lhs] = self.stack.pop()?;
-        self.stack.push(lhs ^ rhs)?;
+        let (guard, [rhs, lhs]) = self.stack.pop_with_guard()?;
+        guard.push(lhs ^ rhs);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn not(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [value] = self.stack.pop()?;
-        self.stack.push(!value)?;
+        let (guard, [value]) = self.stack.pop_with_guard()?;
+        guard.push(!value);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn byte(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [value, offset] = self.stack.pop()?;
-        self.stack.push(value.byte(offset))?;
+        let (guard, [value, offset]) = self.stack.pop_with_guard()?;
+        guard.push(value.byte(offset));
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn shl(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [value, shift] = self.stack.pop()?;
-        self.stack.push(value << shift)?;
+        let (guard, [value, shift]) = self.stack.pop_with_guard()?;
+        guard.push(value << shift);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn shr(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [value, shift] = self.stack.pop()?;
-        self.stack.push(value >> shift)?;
+        let (guard, [value, shift]) = self.stack.pop_with_guard()?;
+        guard.push(value >> shift);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn sar(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [value, shift] = self.stack.pop()?;
-        self.stack.push(value.sar(shift))?;
+        let (guard, [value, shift]) = self.stack.pop_with_guard()?;
+        guard.push(value.sar(shift));
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn sha3(&mut self) -> OpResult {
         self.gas_left.consume(30)?;
-        let [len, offset] = self.stack.pop()?;
+        let (guard, [len, offset]) = self.stack.pop_with_guard()?;
 
         let len = u64::try_from(len).map_err(|_| FailStatus::OutOfGas)?;
         self.gas_left.consume(6 * word_size(len)?)?; // * does not overflow
 
         let data = self.memory.get_mut_slice(offset, len, &mut self.gas_left)?;
-        self.stack.push(hash_cache::hash(data))?;
+        guard.push(hash_cache::hash(data));
         self.code_reader.next();
         self.return_from_op()
     }
@@ -885,21 +1189,25 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
     }
 
     fn balance(&mut self) -> OpResult {
-        if self.revision < Revision::EVMC_BERLIN {
-            self.gas_left.consume(700)?;
-        }
-        let [addr] = self.stack.pop()?;
+        let (guard, [addr]) = self.stack.pop_with_guard()?;
         let addr = addr.into();
         self.gas_left
-            .consume_address_access_cost(&addr, self.revision, self.context)?;
-        self.stack.push(self.context.get_balance(&addr))?;
+            .consume_address_access_cost(
+                &addr,
+                self.revision,
+                &mut self.access_list,
+                &mut self.journal,
+                self.context.as_deref_mut(),
+                &self.gas_schedule,
+            )?;
+        guard.push(require_host(&mut self.context)?.get_balance(&addr));
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn origin(&mut self) -> OpResult {
         self.gas_left.consume(2)?;
-        self.stack.push(self.context.get_tx_context().tx_origin)?;
+        self.stack.push(require_host(&mut self.context)?.get_tx_context().tx_origin)?;
         self.code_reader.next();
         self.return_from_op()
     }
@@ -920,7 +1228,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
 
     fn call_data_load(&mut self) -> OpResult {
         self.gas_left.consume(3)?;
-        let [offset] = self.stack.pop()?;
+        let (guard, [offset]) = self.stack.pop_with_guard()?;
         let (offset, overflow) = offset.into_u64_with_overflow();
         let offset = offset as usize;
         #[allow(clippy::map_identity)]
@@ -935,12 +1243,12 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
             )
             .unwrap_or_default();
         if overflow || offset >= call_data.len() {
-            self.stack.push(u256::ZERO)?;
+            guard.push(u256::ZERO);
         } else {
             let end = min(call_data.len(), offset + 32);
             let mut bytes = [0; 32];
             bytes[..end - offset].copy_from_slice(&call_data[offset..end]);
-            self.stack.push(u256::from_be_bytes(bytes))?;
+            guard.push(u256::from_be_bytes(bytes));
         }
         self.code_reader.next();
         self.return_from_op()
@@ -962,7 +1270,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
     }
 
     fn push0(&mut self) -> OpResult {
-        check_min_revision(Revision::EVMC_SHANGHAI, self.revision)?;
+        check_min_revision(min_revision(Opcode::Push0 as u8), self.revision)?;
         self.gas_left.consume(2)?;
         self.stack.push(u256::ZERO)?;
         self.code_reader.next();
@@ -974,7 +1282,10 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         let [len, offset, dest_offset] = self.stack.pop()?;
 
         if len != u256::ZERO {
-            let len = u64::try_from(len).map_err(|_| FailStatus::InvalidMemoryAccess)?;
+            // A length this large would overflow the memory-expansion cost computation long
+            // before the copy itself, so this is an out-of-gas condition, not an invalid memory
+            // access - matching `code_copy`/`ext_code_copy`'s handling of the same overflow.
+            let len = u64::try_from(len).map_err(|_| FailStatus::OutOfGas)?;
 
             #[allow(clippy::map_identity)]
             let src = self
@@ -991,7 +1302,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
             let dest = self
                 .memory
                 .get_mut_slice(dest_offset, len, &mut self.gas_left)?;
-            dest.copy_padded(src, &mut self.gas_left)?;
+            dest.copy_padded(src, &mut self.gas_left, &self.gas_schedule)?;
         }
         self.code_reader.next();
         self.return_from_op()
@@ -1015,7 +1326,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
             let dest = self
                 .memory
                 .get_mut_slice(dest_offset, len, &mut self.gas_left)?;
-            dest.copy_padded(src, &mut self.gas_left)?;
+            dest.copy_padded(src, &mut self.gas_left, &self.gas_schedule)?;
         }
         self.code_reader.next();
         self.return_from_op()
@@ -1024,33 +1335,41 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
     fn gas_price(&mut self) -> OpResult {
         self.gas_left.consume(2)?;
         self.stack
-            .push(self.context.get_tx_context().tx_gas_price)?;
+            .push(require_host(&mut self.context)?.get_tx_context().tx_gas_price)?;
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn ext_code_size(&mut self) -> OpResult {
-        if self.revision < Revision::EVMC_BERLIN {
-            self.gas_left.consume(700)?;
-        }
-        let [addr] = self.stack.pop()?;
+        let (guard, [addr]) = self.stack.pop_with_guard()?;
         let addr = addr.into();
         self.gas_left
-            .consume_address_access_cost(&addr, self.revision, self.context)?;
-        self.stack.push(self.context.get_code_size(&addr))?;
+            .consume_address_access_cost(
+                &addr,
+                self.revision,
+                &mut self.access_list,
+                &mut self.journal,
+                self.context.as_deref_mut(),
+                &self.gas_schedule,
+            )?;
+        guard.push(require_host(&mut self.context)?.get_code_size(&addr));
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn ext_code_copy(&mut self) -> OpResult {
-        if self.revision < Revision::EVMC_BERLIN {
-            self.gas_left.consume(700)?;
-        }
         let [len, offset, dest_offset, addr] = self.stack.pop()?;
         let addr = addr.into();
 
         self.gas_left
-            .consume_address_access_cost(&addr, self.revision, self.context)?;
+            .consume_address_access_cost(
+                &addr,
+                self.revision,
+                &mut self.access_list,
+                &mut self.journal,
+                self.context.as_deref_mut(),
+                &self.gas_schedule,
+            )?;
         if len != u256::ZERO {
             let len = u64::try_from(len).map_err(|_| FailStatus::OutOfGas)?;
 
@@ -1058,8 +1377,9 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
                 .memory
                 .get_mut_slice(dest_offset, len, &mut self.gas_left)?;
             let (offset, offset_overflow) = offset.into_u64_with_overflow();
-            self.gas_left.consume_copy_cost(len)?;
-            let bytes_written = self.context.copy_code(&addr, offset as usize, dest);
+            self.gas_left.consume_copy_cost(len, &self.gas_schedule)?;
+            let bytes_written =
+                require_host(&mut self.context)?.copy_code(&addr, offset as usize, dest);
             if offset_overflow {
                 dest.fill(0);
             } else if (bytes_written as u64) < len {
@@ -1099,33 +1419,37 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
             let dest = self
                 .memory
                 .get_mut_slice(dest_offset, len, &mut self.gas_left)?;
-            dest.copy_padded(src, &mut self.gas_left)?;
+            dest.copy_padded(src, &mut self.gas_left, &self.gas_schedule)?;
         }
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn ext_code_hash(&mut self) -> OpResult {
-        if self.revision < Revision::EVMC_BERLIN {
-            self.gas_left.consume(700)?;
-        }
-        let [addr] = self.stack.pop()?;
+        let (guard, [addr]) = self.stack.pop_with_guard()?;
         let addr = addr.into();
         self.gas_left
-            .consume_address_access_cost(&addr, self.revision, self.context)?;
-        self.stack.push(self.context.get_code_hash(&addr))?;
+            .consume_address_access_cost(
+                &addr,
+                self.revision,
+                &mut self.access_list,
+                &mut self.journal,
+                self.context.as_deref_mut(),
+                &self.gas_schedule,
+            )?;
+        guard.push(require_host(&mut self.context)?.get_code_hash(&addr));
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn block_hash(&mut self) -> OpResult {
         self.gas_left.consume(20)?;
-        let [block_number] = self.stack.pop()?;
-        self.stack.push(
+        let (guard, [block_number]) = self.stack.pop_with_guard()?;
+        guard.push(
             u64::try_from(block_number)
-                .map(|idx| self.context.get_block_hash(idx as i64).into())
+                .map(|idx| require_host(&mut self.context)?.get_block_hash(idx as i64).into())
                 .unwrap_or(u256::ZERO),
-        )?;
+        );
         self.code_reader.next();
         self.return_from_op()
     }
@@ -1133,7 +1457,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
     fn coinbase(&mut self) -> OpResult {
         self.gas_left.consume(2)?;
         self.stack
-            .push(self.context.get_tx_context().block_coinbase)?;
+            .push(require_host(&mut self.context)?.get_tx_context().block_coinbase)?;
         self.code_reader.next();
         self.return_from_op()
     }
@@ -1141,7 +1465,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
     fn timestamp(&mut self) -> OpResult {
         self.gas_left.consume(2)?;
         self.stack
-            .push(self.context.get_tx_context().block_timestamp as u64)?;
+            .push(require_host(&mut self.context)?.get_tx_context().block_timestamp as u64)?;
         self.code_reader.next();
         self.return_from_op()
     }
@@ -1149,7 +1473,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
     fn number(&mut self) -> OpResult {
         self.gas_left.consume(2)?;
         self.stack
-            .push(self.context.get_tx_context().block_number as u64)?;
+            .push(require_host(&mut self.context)?.get_tx_context().block_number as u64)?;
         self.code_reader.next();
         self.return_from_op()
     }
@@ -1157,7 +1481,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
     fn prev_randao(&mut self) -> OpResult {
         self.gas_left.consume(2)?;
         self.stack
-            .push(self.context.get_tx_context().block_prev_randao)?;
+            .push(require_host(&mut self.context)?.get_tx_context().block_prev_randao)?;
         self.code_reader.next();
         self.return_from_op()
     }
@@ -1165,61 +1489,62 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
     fn gas_limit(&mut self) -> OpResult {
         self.gas_left.consume(2)?;
         self.stack
-            .push(self.context.get_tx_context().block_gas_limit as u64)?;
+            .push(require_host(&mut self.context)?.get_tx_context().block_gas_limit as u64)?;
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn chain_id(&mut self) -> OpResult {
         self.gas_left.consume(2)?;
-        self.stack.push(self.context.get_tx_context().chain_id)?;
+        self.stack.push(require_host(&mut self.context)?.get_tx_context().chain_id)?;
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn self_balance(&mut self) -> OpResult {
-        check_min_revision(Revision::EVMC_ISTANBUL, self.revision)?;
+        check_min_revision(min_revision(Opcode::SelfBalance as u8), self.revision)?;
         self.gas_left.consume(5)?;
         let addr = self.message.recipient();
         if u256::from(addr) == u256::ZERO {
             self.stack.push(u256::ZERO)?;
         } else {
-            self.stack.push(self.context.get_balance(addr))?;
+            self.stack.push(require_host(&mut self.context)?.get_balance(addr))?;
         }
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn base_fee(&mut self) -> OpResult {
-        check_min_revision(Revision::EVMC_LONDON, self.revision)?;
+        check_min_revision(min_revision(Opcode::BaseFee as u8), self.revision)?;
         self.gas_left.consume(2)?;
         self.stack
-            .push(self.context.get_tx_context().block_base_fee)?;
+            .push(require_host(&mut self.context)?.get_tx_context().block_base_fee)?;
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn blob_hash(&mut self) -> OpResult {
-        check_min_revision(Revision::EVMC_CANCUN, self.revision)?;
+        check_min_revision(min_revision(Opcode::BlobHash as u8), self.revision)?;
         self.gas_left.consume(3)?;
-        let [idx] = self.stack.pop()?;
+        let (guard, [idx]) = self.stack.pop_with_guard()?;
         let (idx, idx_overflow) = idx.into_u64_with_overflow();
         let idx = idx as usize;
-        let hashes = ExecutionTxContext::from(self.context.get_tx_context()).blob_hashes;
+        let hashes =
+            ExecutionTxContext::from(require_host(&mut self.context)?.get_tx_context()).blob_hashes;
         if !idx_overflow && idx < hashes.len() {
-            self.stack.push(hashes[idx])?;
+            guard.push(hashes[idx]);
         } else {
-            self.stack.push(u256::ZERO)?;
+            guard.push(u256::ZERO);
         }
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn blob_base_fee(&mut self) -> OpResult {
-        check_min_revision(Revision::EVMC_CANCUN, self.revision)?;
+        check_min_revision(min_revision(Opcode::BlobBaseFee as u8), self.revision)?;
         self.gas_left.consume(2)?;
         self.stack
-            .push(self.context.get_tx_context().blob_base_fee)?;
+            .push(require_host(&mut self.context)?.get_tx_context().blob_base_fee)?;
         self.code_reader.next();
         self.return_from_op()
     }
@@ -1246,8 +1571,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         let [value, offset] = self.stack.pop()?;
 
         let dest = self.memory.get_mut_slice(offset, 32, &mut self.gas_left)?;
-        dest.copy_from_slice(&value.to_le_bytes());
-        dest.reverse();
+        dest.copy_from_slice(&value.to_be_bytes());
         self.code_reader.next();
         self.return_from_op()
     }
@@ -1264,20 +1588,24 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
 
     fn s_load(&mut self) -> OpResult {
         if self.revision < Revision::EVMC_BERLIN {
-            self.gas_left.consume(800)?;
+            self.gas_left.consume(self.gas_schedule.pre_berlin_sload_cost)?;
         }
-        let [key] = self.stack.pop()?;
+        let (guard, [key]) = self.stack.pop_with_guard()?;
         let key = key.into();
         let addr = self.message.recipient();
         if self.revision >= Revision::EVMC_BERLIN {
-            if self.context.access_storage(addr, &key) == AccessStatus::EVMC_ACCESS_COLD {
-                self.gas_left.consume(2_100)?;
+            if self.access_list.access_storage(addr, &key, self.context.as_deref_mut())
+                == AccessStatus::EVMC_ACCESS_COLD
+            {
+                self.journal.record_accessed_storage(*addr, key);
+                self.gas_left.consume(self.gas_schedule.cold_sload_cost)?;
             } else {
-                self.gas_left.consume(100)?;
+                self.gas_left
+                    .consume(self.gas_schedule.warm_account_access_cost)?;
             }
         }
-        let value = self.context.get_storage(addr, &key);
-        self.stack.push(value)?;
+        let value = require_host(&mut self.context)?.get_storage(addr, &key);
+        guard.push(value);
         self.code_reader.next();
         self.return_from_op()
     }
@@ -1335,35 +1663,40 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
     }
 
     fn t_load(&mut self) -> OpResult {
-        check_min_revision(Revision::EVMC_CANCUN, self.revision)?;
+        check_min_revision(min_revision(Opcode::TLoad as u8), self.revision)?;
         self.gas_left.consume(100)?;
-        let [key] = self.stack.pop()?;
+        let (guard, [key]) = self.stack.pop_with_guard()?;
         let addr = self.message.recipient();
-        let value = self.context.get_transient_storage(addr, &key.into());
-        self.stack.push(value)?;
+        let value = require_host(&mut self.context)?.get_transient_storage(addr, &key.into());
+        guard.push(value);
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn t_store(&mut self) -> OpResult {
-        check_min_revision(Revision::EVMC_CANCUN, self.revision)?;
+        check_min_revision(min_revision(Opcode::TStore as u8), self.revision)?;
         check_not_read_only(self.message)?;
         self.gas_left.consume(100)?;
         let [value, key] = self.stack.pop()?;
         let addr = self.message.recipient();
-        self.context
+        require_host(&mut self.context)?
             .set_transient_storage(addr, &key.into(), &value.into());
         self.code_reader.next();
         self.return_from_op()
     }
 
     fn m_copy(&mut self) -> OpResult {
-        check_min_revision(Revision::EVMC_CANCUN, self.revision)?;
+        check_min_revision(min_revision(Opcode::MCopy as u8), self.revision)?;
         self.gas_left.consume(3)?;
         let [len, offset, dest_offset] = self.stack.pop()?;
         if len != u256::ZERO {
-            self.memory
-                .copy_within(offset, dest_offset, len, &mut self.gas_left)?;
+            self.memory.copy_within(
+                offset,
+                dest_offset,
+                len,
+                &mut self.gas_left,
+                &self.gas_schedule,
+            )?;
         }
         self.code_reader.next();
         self.return_from_op()
@@ -1372,15 +1705,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
     fn return_(&mut self) -> OpResult {
         let [len, offset] = self.stack.pop()?;
         let len = u64::try_from(len).map_err(|_| FailStatus::OutOfGas)?;
-        let data = self.memory.get_mut_slice(offset, len, &mut self.gas_left)?;
-        #[cfg(not(feature = "custom-evmc"))]
-        {
-            self.output = Some(data.to_owned());
-        }
-        #[cfg(feature = "custom-evmc")]
-        {
-            self.output = Some(Box::from(&*data));
-        }
+        self.pending_output = Some((offset, len));
         self.exec_status = ExecStatus::Returned;
         Ok(())
     }
@@ -1388,15 +1713,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
     fn revert(&mut self) -> OpResult {
         let [len, offset] = self.stack.pop()?;
         let len = u64::try_from(len).map_err(|_| FailStatus::OutOfGas)?;
-        let data = self.memory.get_mut_slice(offset, len, &mut self.gas_left)?;
-        #[cfg(not(feature = "custom-evmc"))]
-        {
-            self.output = Some(data.to_owned());
-        }
-        #[cfg(feature = "custom-evmc")]
-        {
-            self.output = Some(Box::from(&*data));
-        }
+        self.pending_output = Some((offset, len));
         self.exec_status = ExecStatus::Revert;
         Ok(())
     }
@@ -1413,20 +1730,24 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         let addr = addr.into();
 
         if self.revision >= Revision::EVMC_BERLIN
-            && self.context.access_account(&addr) == AccessStatus::EVMC_ACCESS_COLD
+            && self.access_list.access_account(&addr, self.context.as_deref_mut())
+                == AccessStatus::EVMC_ACCESS_COLD
         {
+            self.journal.record_accessed_account(addr);
             self.gas_left.consume(2_600)?;
         }
 
-        if u256::from(self.context.get_balance(self.message.recipient())) > u256::ZERO
-            && !self.context.account_exists(&addr)
-        {
+        let balance = require_host(&mut self.context)?.get_balance(self.message.recipient());
+        let account_exists = require_host(&mut self.context)?.account_exists(&addr);
+        if u256::from(balance) > u256::ZERO && !account_exists {
             self.gas_left.consume(25_000)?;
         }
 
-        let destructed = self.context.selfdestruct(self.message.recipient(), &addr);
+        let destructed =
+            require_host(&mut self.context)?.selfdestruct(self.message.recipient(), &addr);
         if self.revision <= Revision::EVMC_BERLIN && destructed {
             self.gas_refund.add(24_000)?;
+            self.journal.record_gas_refund(24_000);
         }
 
         self.exec_status = ExecStatus::Stopped;
@@ -1443,43 +1764,18 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         let key = key.into();
         let addr = self.message.recipient();
 
-        let (dyn_gas_1, dyn_gas_2, dyn_gas_3, refund_1, refund_2, refund_3) =
-            if self.revision >= Revision::EVMC_LONDON {
-                (100, 2_900, 20_000, 5_000 - 2_100 - 100, 4_800, 20_000 - 100)
-            } else if self.revision >= Revision::EVMC_BERLIN {
-                (
-                    100,
-                    2_900,
-                    20_000,
-                    5_000 - 2_100 - 100,
-                    15_000,
-                    20_000 - 100,
-                )
-            } else if self.revision >= Revision::EVMC_ISTANBUL {
-                (800, 5_000, 20_000, 4_200, 15_000, 19_200)
-            } else {
-                (5_000, 5_000, 20_000, 0, 0, 0)
-            };
-
-        let status = self.context.set_storage(addr, &key, &value.into());
-        let (mut dyn_gas, gas_refund_change) = match status {
-            StorageStatus::EVMC_STORAGE_ASSIGNED => (dyn_gas_1, 0),
-            StorageStatus::EVMC_STORAGE_ADDED => (dyn_gas_3, 0),
-            StorageStatus::EVMC_STORAGE_DELETED => (dyn_gas_2, refund_2),
-            StorageStatus::EVMC_STORAGE_MODIFIED => (dyn_gas_2, 0),
-            StorageStatus::EVMC_STORAGE_DELETED_ADDED => (dyn_gas_1, -refund_2),
-            StorageStatus::EVMC_STORAGE_MODIFIED_DELETED => (dyn_gas_1, refund_2),
-            StorageStatus::EVMC_STORAGE_DELETED_RESTORED => (dyn_gas_1, -refund_2 + refund_1),
-            StorageStatus::EVMC_STORAGE_ADDED_DELETED => (dyn_gas_1, refund_3),
-            StorageStatus::EVMC_STORAGE_MODIFIED_RESTORED => (dyn_gas_1, refund_1),
-        };
+        let status = require_host(&mut self.context)?.set_storage(addr, &key, &value.into());
+        let (mut dyn_gas, gas_refund_change) = sstore_gas_and_refund(self.revision, status);
         if self.revision >= Revision::EVMC_BERLIN
-            && self.context.access_storage(addr, &key) == AccessStatus::EVMC_ACCESS_COLD
+            && self.access_list.access_storage(addr, &key, self.context.as_deref_mut())
+                == AccessStatus::EVMC_ACCESS_COLD
         {
+            self.journal.record_accessed_storage(*addr, key);
             dyn_gas += 2_100;
         }
         self.gas_left.consume(dyn_gas)?;
         self.gas_refund.add(gas_refund_change)?;
+        self.journal.record_gas_refund(gas_refund_change);
         self.code_reader.next();
         self.return_from_op()
     }
@@ -1498,7 +1794,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
 
     fn dup(&mut self, nth: usize) -> OpResult {
         self.gas_left.consume(3)?;
-        self.stack.push(self.stack.nth(nth - 1)?)?;
+        self.stack.dup(nth)?;
         self.code_reader.next();
         self.return_from_op()
     }
@@ -1528,7 +1824,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         for i in 0..N {
             topics_uint256[i] = Uint256::from(topics[N - 1 - i]);
         }
-        self.context
+        require_host(&mut self.context)?
             .emit_log(self.message.recipient(), data, &topics_uint256);
         self.code_reader.next();
         self.return_from_op()
@@ -1570,7 +1866,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
 
         let init_code = self.memory.get_mut_slice(offset, len, &mut self.gas_left)?;
 
-        if value > self.context.get_balance(self.message.recipient()).into() {
+        if value > require_host(&mut self.context)?.get_balance(self.message.recipient()).into() {
             self.last_call_return_data = None;
             self.stack.push(u256::ZERO)?;
             self.code_reader.next();
@@ -1581,6 +1877,35 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         let gas_limit = gas_left - gas_left / 64;
         self.gas_left.consume(gas_limit)?;
 
+        // A `STEPPABLE` interpreter parks here instead of recursing into the host, so a caller can
+        // run the init code itself (on another thread, interleaved with other work, ...) and hand
+        // the result back via `Interpreter::resume_call`.
+        #[cfg(feature = "call-trap")]
+        if STEPPABLE {
+            self.pending_trap = Some(PendingCall {
+                kind: if CREATE2 {
+                    MessageKind::EVMC_CREATE2
+                } else {
+                    MessageKind::EVMC_CREATE
+                },
+                flags: self.message.flags(),
+                depth: self.message.depth() + 1,
+                gas: gas_limit as i64,
+                destination: u256::ZERO.into(),
+                sender: *self.message.recipient(),
+                input: init_code.to_vec(),
+                value: value.into(),
+                create2_salt: salt.into(),
+                code_address: u256::ZERO.into(),
+                ret_offset: 0,
+                ret_len: 0,
+                endowment: 0,
+                stipend: 0,
+            });
+            self.code_reader.next();
+            return Ok(());
+        }
+
         let message = ExecutionMessage::new(
             if CREATE2 {
                 MessageKind::EVMC_CREATE2
@@ -1599,7 +1924,7 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
             None,
             None,
         );
-        let result = self.context.call(&message);
+        let result = require_host(&mut self.context)?.call(&message);
 
         self.gas_left.add(result.gas_left())?;
         self.gas_refund.add(result.gas_refund())?;
@@ -1619,6 +1944,31 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         self.return_from_op()
     }
 
+    /// Runs `call_message` natively if its recipient names a [`Precompile`], next checks whether
+    /// it names a registered [`ExternalModule`](crate::types::ExternalModule) (see
+    /// [`with_external_modules`](Self::with_external_modules)) and runs that instead if so,
+    /// otherwise delegates to the host via [`ExecutionContextTrait::call`] - or, for a
+    /// context-less [`Interpreter`], fails the same way any other sub-call failure does rather
+    /// than panicking.
+    fn dispatch_call(&mut self, call_message: &ExecutionMessage) -> ExecutionResult {
+        let Some(precompile) = Precompile::from_address(call_message.recipient(), self.revision)
+        else {
+            #[cfg(feature = "external-module")]
+            if let Some(module) = self
+                .external_modules
+                .as_mut()
+                .and_then(|registry| registry.get_mut(call_message.recipient()))
+            {
+                return module.call(self.revision, call_message);
+            }
+            return match require_host(&mut self.context) {
+                Ok(context) => context.call(call_message),
+                Err(status) => status.into(),
+            };
+        };
+        precompile.call(call_message.gas(), call_message.input().unwrap_or(&[]))
+    }
+
     fn call(&mut self) -> OpResult {
         self.call_or_call_code::<false>()
     }
@@ -1628,9 +1978,6 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
     }
 
     fn call_or_call_code<const CODE: bool>(&mut self) -> OpResult {
-        if self.revision < Revision::EVMC_BERLIN {
-            self.gas_left.consume(700)?;
-        }
         let [ret_len, ret_offset, args_len, args_offset, value, addr, gas] = self.stack.pop()?;
 
         if !CODE && value != u256::ZERO {
@@ -1642,11 +1989,23 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         let ret_len = u64::try_from(ret_len).map_err(|_| FailStatus::OutOfGas)?;
 
         self.gas_left
-            .consume_address_access_cost(&addr, self.revision, self.context)?;
-        self.gas_left.consume_positive_value_cost(&value)?;
+            .consume_address_access_cost(
+                &addr,
+                self.revision,
+                &mut self.access_list,
+                &mut self.journal,
+                self.context.as_deref_mut(),
+                &self.gas_schedule,
+            )?;
+        self.gas_left
+            .consume_positive_value_cost(&value, &self.gas_schedule)?;
         if !CODE {
-            self.gas_left
-                .consume_value_to_empty_account_cost(&value, &addr, self.context)?;
+            self.gas_left.consume_value_to_empty_account_cost(
+                &value,
+                &addr,
+                require_host(&mut self.context)?,
+                &self.gas_schedule,
+            )?;
         }
         // access slice to consume potential memory expansion cost but drop it so that we can get
         // another mutable reference into memory for input
@@ -1665,7 +2024,8 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         let stipend: u64 = if value == u256::ZERO { 0 } else { 2_300 };
         self.gas_left.add(stipend as i64)?;
 
-        if value > u256::from(self.context.get_balance(self.message.recipient())) {
+        let balance = require_host(&mut self.context)?.get_balance(self.message.recipient());
+        if value > u256::from(balance) {
             self.last_call_return_data = None;
             self.stack.push(u256::ZERO)?;
             self.code_reader.next();
@@ -1704,7 +2064,33 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
             )
         };
 
-        let result = self.context.call(&call_message);
+        // Same trap as `create_or_create2`, for the same reason: only a non-precompile message
+        // actually needs the host, so a precompile still runs natively here even under
+        // `call-trap`.
+        #[cfg(feature = "call-trap")]
+        if STEPPABLE && Precompile::from_address(call_message.recipient(), self.revision).is_none()
+        {
+            self.pending_trap = Some(PendingCall {
+                kind: call_message.kind(),
+                flags: call_message.flags(),
+                depth: call_message.depth(),
+                gas: call_message.gas(),
+                destination: *call_message.recipient(),
+                sender: *call_message.sender(),
+                input: call_message.input().map(ToOwned::to_owned).unwrap_or_default(),
+                value: *call_message.value(),
+                create2_salt: *call_message.create2_salt(),
+                code_address: *call_message.code_address(),
+                ret_offset,
+                ret_len,
+                endowment,
+                stipend,
+            });
+            self.code_reader.next();
+            return Ok(());
+        }
+
+        let result = self.dispatch_call(&call_message);
         self.last_call_return_data = result.output().map(ToOwned::to_owned);
         let dest = self
             .memory
@@ -1734,9 +2120,6 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
     }
 
     fn static_or_delegate_call<const DELEGATE: bool>(&mut self) -> OpResult {
-        if self.revision < Revision::EVMC_BERLIN {
-            self.gas_left.consume(700)?;
-        }
         let [ret_len, ret_offset, args_len, args_offset, addr, gas] = self.stack.pop()?;
 
         let addr = addr.into();
@@ -1744,7 +2127,14 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         let ret_len = u64::try_from(ret_len).map_err(|_| FailStatus::OutOfGas)?;
 
         self.gas_left
-            .consume_address_access_cost(&addr, self.revision, self.context)?;
+            .consume_address_access_cost(
+                &addr,
+                self.revision,
+                &mut self.access_list,
+                &mut self.journal,
+                self.context.as_deref_mut(),
+                &self.gas_schedule,
+            )?;
         // access slice to consume potential memory expansion cost but drop it so that we can get
         // another mutable reference into memory for input
         let _dest = self
@@ -1791,7 +2181,31 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
             )
         };
 
-        let result = self.context.call(&call_message);
+        // Same trap as `call_or_call_code`/`create_or_create2`.
+        #[cfg(feature = "call-trap")]
+        if STEPPABLE && Precompile::from_address(call_message.recipient(), self.revision).is_none()
+        {
+            self.pending_trap = Some(PendingCall {
+                kind: call_message.kind(),
+                flags: call_message.flags(),
+                depth: call_message.depth(),
+                gas: call_message.gas(),
+                destination: *call_message.recipient(),
+                sender: *call_message.sender(),
+                input: call_message.input().map(ToOwned::to_owned).unwrap_or_default(),
+                value: *call_message.value(),
+                create2_salt: *call_message.create2_salt(),
+                code_address: *call_message.code_address(),
+                ret_offset,
+                ret_len,
+                endowment,
+                stipend: 0,
+            });
+            self.code_reader.next();
+            return Ok(());
+        }
+
+        let result = self.dispatch_call(&call_message);
         self.last_call_return_data = result.output().map(ToOwned::to_owned);
         let dest = self
             .memory
@@ -1810,10 +2224,382 @@ impl<const STEPPABLE: bool> Interpreter<'_, STEPPABLE> {
         self.code_reader.next();
         self.return_from_op()
     }
+
+    /// Settles whatever `RETURN`/`REVERT` deferred into `pending_output`, producing the final gas
+    /// left and output in one place instead of each exit path assembling its own.
+    fn finalize_gas_left(&mut self) -> Result<(Gas, Option<Vec<u8>>), FailStatus> {
+        let gas = mem::replace(&mut self.gas_left, Gas::new(0));
+        let gas_left = match self.pending_output {
+            Some((offset, len)) => GasLeft::NeedsReturn { gas, offset, len },
+            None => GasLeft::Known(gas),
+        };
+        gas_left.finalize(&self.memory)
+    }
+}
+
+/// The owned pieces of a steppable [`Interpreter`]'s state needed to resume it. Unlike
+/// [`StepResult`], which flattens [`Stack`] and [`Memory`] into the FFI-shaped
+/// `Vec<Uint256>`/`Vec<u8>` the C step API exchanges them as, this keeps them as this crate's own
+/// types: a caller that wants to keep stepping the same execution natively can move them straight
+/// into the next [`Interpreter::new_steppable`] instead of copying them out to and back from that
+/// FFI shape.
+pub struct SuspendedExecution {
+    pub pc: usize,
+    pub gas_left: i64,
+    pub gas_refund: i64,
+    pub revision: Revision,
+    pub stack: Stack,
+    pub memory: Memory,
+    pub last_call_return_data: Option<Vec<u8>>,
+}
+
+impl From<Interpreter<'_, true>> for SuspendedExecution {
+    fn from(value: Interpreter<'_, true>) -> Self {
+        Self {
+            pc: value.code_reader.pc(),
+            gas_left: value.gas_left.as_u64() as i64,
+            gas_refund: value.gas_refund.as_i64(),
+            revision: value.revision,
+            stack: value.stack,
+            memory: value.memory,
+            last_call_return_data: value.last_call_return_data,
+        }
+    }
+}
+
+/// Every [`Revision`] this crate gates behavior on (see e.g. `Interpreter::s_load`'s
+/// `EVMC_BERLIN` check) - used to round-trip [`SuspendedExecution::revision`] through
+/// [`to_snapshot`](SuspendedExecution::to_snapshot) without depending on an unpublished
+/// raw-integer `Revision` conversion from the `evmc_vm` crate.
+const KNOWN_REVISIONS: &[Revision] = &[
+    Revision::EVMC_FRONTIER,
+    Revision::EVMC_HOMESTEAD,
+    Revision::EVMC_TANGERINE_WHISTLE,
+    Revision::EVMC_BYZANTIUM,
+    Revision::EVMC_PETERSBURG,
+    Revision::EVMC_ISTANBUL,
+    Revision::EVMC_BERLIN,
+    Revision::EVMC_LONDON,
+    Revision::EVMC_SHANGHAI,
+    Revision::EVMC_CANCUN,
+];
+
+/// Why [`SuspendedExecution::from_snapshot`] rejected a buffer, instead of panicking on
+/// corrupted or attacker-supplied input.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// The buffer ended before a declared field/length was fully read.
+    Truncated,
+    /// The leading version byte doesn't match [`SuspendedExecution::SNAPSHOT_VERSION`].
+    UnsupportedVersion(u8),
+    /// The revision field didn't match any of [`KNOWN_REVISIONS`].
+    UnknownRevision(u32),
+    /// The stack word count or contents couldn't be rebuilt into a [`Stack`].
+    InvalidStack,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "snapshot buffer ended before a declared field/length"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported snapshot version {version}")
+            }
+            Self::UnknownRevision(revision) => write!(f, "unknown revision tag {revision}"),
+            Self::InvalidStack => write!(f, "snapshot's stack contents could not be rebuilt"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// A cursor over a snapshot buffer that turns "not enough bytes left" into
+/// [`SnapshotError::Truncated`] instead of panicking on a corrupted or truncated input.
+struct SnapshotReader<'a>(&'a [u8]);
+
+impl<'a> SnapshotReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SnapshotError> {
+        if self.0.len() < len {
+            return Err(SnapshotError::Truncated);
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn take_u8(&mut self) -> Result<u8, SnapshotError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn take_u32(&mut self) -> Result<u32, SnapshotError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn take_u64(&mut self) -> Result<u64, SnapshotError> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn take_i64(&mut self) -> Result<i64, SnapshotError> {
+        Ok(i64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+impl SuspendedExecution {
+    /// Bumped whenever this byte layout changes, so [`from_snapshot`](Self::from_snapshot) can
+    /// reject a snapshot produced by an incompatible version instead of misreading it.
+    const SNAPSHOT_VERSION: u8 = 1;
+
+    /// Serializes this suspended execution to a flat, versioned byte buffer: a 1-byte version,
+    /// then `pc`/`gas_left`/`gas_refund`/`revision`, the stack as a length-prefixed array of
+    /// 32-byte big-endian words (bottom first), the memory buffer as a length-prefixed byte
+    /// string, and `last_call_return_data` as a presence flag plus a length-prefixed byte string.
+    /// Meant for checkpointing a long-running interpreter, handing a partially executed frame to
+    /// another host process, or persisting a fuzzer-found state for replay -
+    /// [`from_snapshot`](Self::from_snapshot) reverses it exactly.
+    pub fn to_snapshot(&self) -> Vec<u8> {
+        let mut out = vec![Self::SNAPSHOT_VERSION];
+        out.extend_from_slice(&(self.pc as u64).to_be_bytes());
+        out.extend_from_slice(&self.gas_left.to_be_bytes());
+        out.extend_from_slice(&self.gas_refund.to_be_bytes());
+        out.extend_from_slice(&(self.revision as u32).to_be_bytes());
+
+        let stack = self.stack.as_slice();
+        out.extend_from_slice(&(stack.len() as u32).to_be_bytes());
+        for word in stack {
+            out.extend_from_slice(&word.to_be_bytes());
+        }
+
+        let memory = self.memory.as_slice();
+        out.extend_from_slice(&(memory.len() as u32).to_be_bytes());
+        out.extend_from_slice(memory);
+
+        match &self.last_call_return_data {
+            Some(data) => {
+                out.push(1);
+                out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+                out.extend_from_slice(data);
+            }
+            None => out.push(0),
+        }
+        out
+    }
+
+    /// Reverses [`to_snapshot`](Self::to_snapshot). Fails on a truncated buffer, an
+    /// unrecognized version, or a revision tag outside [`KNOWN_REVISIONS`] - never by panicking
+    /// on malformed input, since a snapshot may have crossed a process or network boundary.
+    pub fn from_snapshot(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        let mut reader = SnapshotReader(bytes);
+        let version = reader.take_u8()?;
+        if version != Self::SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let pc = reader.take_u64()? as usize;
+        let gas_left = reader.take_i64()?;
+        let gas_refund = reader.take_i64()?;
+        let revision_tag = reader.take_u32()?;
+        let revision = KNOWN_REVISIONS
+            .iter()
+            .copied()
+            .find(|revision| *revision as u32 == revision_tag)
+            .ok_or(SnapshotError::UnknownRevision(revision_tag))?;
+
+        let stack_len = reader.take_u32()? as usize;
+        let mut stack = Vec::with_capacity(stack_len);
+        for _ in 0..stack_len {
+            stack.push(u256::from_be_bytes(reader.take(32)?.try_into().unwrap()));
+        }
+
+        let memory_len = reader.take_u32()? as usize;
+        let memory = reader.take(memory_len)?;
+
+        let last_call_return_data = if reader.take_u8()? == 1 {
+            let len = reader.take_u32()? as usize;
+            Some(reader.take(len)?.to_vec())
+        } else {
+            None
+        };
+
+        Ok(Self {
+            pc,
+            gas_left,
+            gas_refund,
+            revision,
+            stack: Stack::new(&stack).map_err(|_| SnapshotError::InvalidStack)?,
+            memory: Memory::new(memory),
+            last_call_return_data,
+        })
+    }
+}
+
+/// A CALL/CALLCODE/STATICCALL/DELEGATECALL/CREATE/CREATE2 message a `STEPPABLE` interpreter
+/// parked instead of handing to [`ExecutionContextTrait::call`] itself, under the `call-trap`
+/// feature. Owns `input` rather than borrowing it from [`Memory`] for the same reason
+/// [`SuspendedExecution`] owns `stack`/`memory`: the interpreter this was popped out of is about
+/// to be torn down into a [`SuspendedExecution`] too, so nothing is left to borrow from once the
+/// caller actually gets this value.
+///
+/// `ret_offset`/`ret_len`/`endowment`/`stipend` aren't needed to build the sub-call's
+/// [`ExecutionMessage`] - only to replay the same fold-back [`Interpreter::call_or_call_code`]/
+/// [`Interpreter::static_or_delegate_call`] would have done inline once a result comes back, so
+/// they stay private to this module rather than `pub`.
+pub struct PendingCall {
+    pub kind: MessageKind,
+    pub flags: u32,
+    pub depth: i64,
+    pub gas: i64,
+    pub destination: Address,
+    pub sender: Address,
+    pub input: Vec<u8>,
+    pub value: Uint256,
+    pub create2_salt: Uint256,
+    pub code_address: Address,
+    ret_offset: u64,
+    ret_len: u64,
+    endowment: u64,
+    stipend: u64,
+}
+
+impl PendingCall {
+    /// Builds the [`ExecutionMessage`] this trap is waiting on a result for. The caller runs it
+    /// however it sees fit - recursing into this crate for a nested Tosca-internal call, handing
+    /// it off to a scheduler, ... - and feeds the resulting [`ExecutionResult`] to
+    /// [`Interpreter::resume_call`].
+    pub fn to_execution_message(&self) -> ExecutionMessage {
+        ExecutionMessage::new(
+            self.kind,
+            self.flags,
+            self.depth,
+            self.gas,
+            self.destination,
+            self.sender,
+            Some(&self.input),
+            self.value,
+            self.create2_salt,
+            self.code_address,
+            None,
+            None,
+        )
+    }
+}
+
+/// A run trapped on a [`PendingCall`] instead of completing or exhausting its step budget: the
+/// rest of [`SuspendedExecution`]'s rationale applies here too, this just also carries the message
+/// the caller now needs to run and eventually feed to [`Interpreter::resume_call`].
+pub struct CallTrap {
+    pub call: PendingCall,
+    pub suspended: SuspendedExecution,
+}
+
+/// The outcome of [`Interpreter::run_resumable`]: either the run reached a final status, the same
+/// as [`Interpreter::run`] would report via [`StepResult`], it used up its step budget while still
+/// [`ExecStatus::Running`] and can be continued from the held [`SuspendedExecution`], or (under
+/// `call-trap`) it parked on a CALL/CREATE and is waiting on [`Interpreter::resume_call`].
+pub enum Execution {
+    Completed(StepResult),
+    Suspended(SuspendedExecution),
+    #[cfg(feature = "call-trap")]
+    Trapped(CallTrap),
+}
+
+impl Interpreter<'_, true> {
+    /// Like [`Interpreter::run`], but distinguishes a suspended run from a completed one instead
+    /// of always producing a [`StepResult`], so a native Rust caller can resume via
+    /// [`Interpreter::new_steppable`] without first marshaling the stack and memory through
+    /// [`StepResult`]'s FFI-shaped fields and back.
+    ///
+    /// The code and message are still only borrowed for the duration of the call, same as
+    /// [`Interpreter::new_steppable`] already requires; letting a caller carry them across suspend
+    /// points without re-supplying a reference every time (e.g. via `Cow`, only cloning if the
+    /// caller mutates them in between) is left for a follow-up, since it means changing what
+    /// `SteppableEvmcContainer` stores between calls, not just this type.
+    #[cfg(not(feature = "tail-call"))]
+    pub fn run_resumable<O>(mut self, observer: &mut O) -> Execution
+    where
+        O: Observer<true>,
+    {
+        loop {
+            if self.exec_status != ExecStatus::Running {
+                break;
+            }
+            #[cfg(feature = "interrupt")]
+            if self.interrupted() {
+                return Execution::Completed(FailStatus::Interrupted.into());
+            }
+            match &mut self.steps {
+                None => (),
+                Some(0) => return Execution::Suspended(self.into()),
+                Some(steps) => *steps -= 1,
+            }
+            let op = match self.code_reader.get() {
+                Ok(op) => op,
+                Err(GetOpcodeError::OutOfRange { .. }) => {
+                    self.exec_status = ExecStatus::Stopped;
+                    break;
+                }
+                Err(GetOpcodeError::Invalid { .. }) => {
+                    return Execution::Completed(FailStatus::InvalidInstruction.into());
+                }
+            };
+            observer.pre_op(&self);
+            let is_frame_boundary = is_frame_op(op);
+            if is_frame_boundary {
+                observer.frame_enter(&self);
+            }
+            if let Err(err) = self.run_op(op) {
+                return Execution::Completed(err.into());
+            }
+            #[cfg(feature = "call-trap")]
+            if let Some(call) = self.pending_trap.take() {
+                return Execution::Trapped(CallTrap {
+                    call,
+                    suspended: self.into(),
+                });
+            }
+            if is_frame_boundary {
+                observer.frame_exit(&self);
+            }
+            observer.post_op(&self);
+        }
+        Execution::Completed(self.into())
+    }
+    /// See the non-`tail-call` overload of [`Interpreter::run_resumable`] for what this does and
+    /// why. Under this feature a single [`Interpreter::next`] call already recurses through every
+    /// remaining opcode via its own tail calls, so running it once and then inspecting
+    /// [`exec_status`](Interpreter::exec_status) is equivalent to that version's loop.
+    #[cfg(feature = "tail-call")]
+    pub fn run_resumable<O>(mut self, observer: &mut O) -> Execution
+    where
+        O: Observer<true>,
+    {
+        observer.log("feature \"tail-call\" does not support logging".into());
+        match self.next() {
+            Err(err) => Execution::Completed(err.into()),
+            #[cfg(feature = "call-trap")]
+            Ok(()) if self.pending_trap.is_some() => {
+                let call = self.pending_trap.take().unwrap();
+                Execution::Trapped(CallTrap {
+                    call,
+                    suspended: self.into(),
+                })
+            }
+            Ok(()) if self.exec_status == ExecStatus::Running => Execution::Suspended(self.into()),
+            Ok(()) => Execution::Completed(self.into()),
+        }
+    }
 }
 
 impl<const STEPPABLE: bool> From<Interpreter<'_, STEPPABLE>> for StepResult {
-    fn from(value: Interpreter<STEPPABLE>) -> Self {
+    fn from(mut value: Interpreter<STEPPABLE>) -> Self {
+        if value.exec_status == ExecStatus::Revert {
+            value.journal.revert_to(0, &mut value.access_list, &mut value.gas_refund);
+        }
+        let (gas_left, output) = match value.finalize_gas_left() {
+            Ok(result) => result,
+            Err(fail_status) => return fail_status.into(),
+        };
+        #[cfg(feature = "custom-evmc")]
+        let output = output.map(Vec::into_boxed_slice);
         let stack = value
             .stack
             .as_slice()
@@ -1826,9 +2612,9 @@ impl<const STEPPABLE: bool> From<Interpreter<'_, STEPPABLE>> for StepResult {
             StatusCode::EVMC_SUCCESS,
             value.revision,
             value.code_reader.pc() as u64,
-            value.gas_left.as_u64() as i64,
+            gas_left.as_u64() as i64,
             value.gas_refund.as_i64(),
-            value.output,
+            output,
             stack,
             value.memory.as_slice().to_vec(),
             value.last_call_return_data,
@@ -1837,16 +2623,37 @@ impl<const STEPPABLE: bool> From<Interpreter<'_, STEPPABLE>> for StepResult {
 }
 
 impl<const STEPPABLE: bool> From<Interpreter<'_, STEPPABLE>> for ExecutionResult {
-    fn from(value: Interpreter<STEPPABLE>) -> Self {
-        Self::new(
+    fn from(mut value: Interpreter<STEPPABLE>) -> Self {
+        if value.exec_status == ExecStatus::Revert {
+            value.journal.revert_to(0, &mut value.access_list, &mut value.gas_refund);
+        }
+        let (gas_left, output) = match value.finalize_gas_left() {
+            Ok(result) => result,
+            Err(fail_status) => return fail_status.into(),
+        };
+        #[cfg(not(feature = "custom-evmc"))]
+        let result = Self::new(
             value.exec_status.into(),
-            value.gas_left.as_u64() as i64,
+            gas_left.as_u64() as i64,
             value.gas_refund.as_i64(),
-            #[cfg(not(feature = "custom-evmc"))]
-            value.output.as_deref(),
-            #[cfg(feature = "custom-evmc")]
-            value.output,
-        )
+            output.as_deref(),
+        );
+        #[cfg(feature = "custom-evmc")]
+        let result = Self::new(
+            value.exec_status.into(),
+            gas_left.as_u64() as i64,
+            value.gas_refund.as_i64(),
+            output.map(Vec::into_boxed_slice),
+        );
+        // Only reachable for `output.as_deref()` above: `Self::new` there copies the bytes into
+        // its own, C-owned buffer instead of taking ownership, so `output` is still ours to hand
+        // back to the pool. The `custom-evmc` branch moves `output` into the boxed slice `Self::new`
+        // takes ownership of, so there is nothing left here to release.
+        #[cfg(all(not(feature = "custom-evmc"), feature = "buffer-pool"))]
+        if let Some(output) = output {
+            crate::types::release_output(output);
+        }
+        result
     }
 }
 
@@ -1858,8 +2665,10 @@ mod tests {
     };
     use mockall::predicate;
 
+    #[cfg(feature = "call-trap")]
+    use crate::interpreter::Execution;
     use crate::{
-        interpreter::Interpreter,
+        interpreter::{Interpreter, SnapshotError, SuspendedExecution},
         types::{
             u256, Memory, MockExecutionContextTrait, MockExecutionMessage, NoOpObserver, Opcode,
             Stack,
@@ -1870,7 +2679,8 @@ mod tests {
     fn empty_code() {
         let mut context = MockExecutionContextTrait::new();
         let message = MockExecutionMessage::default().into();
-        let interpreter = Interpreter::new(Revision::EVMC_ISTANBUL, &message, &mut context, &[]);
+        let interpreter =
+            Interpreter::new(Revision::EVMC_ISTANBUL, &message, Some(&mut context), &[]).unwrap();
         let result: StepResult = interpreter.run(&mut NoOpObserver());
         assert_eq!(result.step_status_code, StepStatusCode::EVMC_STEP_STOPPED);
         assert_eq!(result.pc, 0);
@@ -1887,11 +2697,11 @@ mod tests {
         let interpreter = Interpreter::new_steppable(
             Revision::EVMC_ISTANBUL,
             &message,
-            &mut context,
+            Some(&mut context),
             &[Opcode::Add as u8],
             1,
             0,
-            Stack::new(&[]),
+            Stack::new(&[]).unwrap(),
             Memory::new(&[]),
             None,
             None,
@@ -1915,11 +2725,11 @@ mod tests {
         let result: ExecutionResult = Interpreter::new_steppable(
             Revision::EVMC_ISTANBUL,
             &message,
-            &mut context,
+            Some(&mut context),
             &[Opcode::Push1 as u8, 0x00],
             1,
             0,
-            Stack::new(&[]),
+            Stack::new(&[]).unwrap(),
             Memory::new(&[]),
             None,
             None,
@@ -1935,11 +2745,11 @@ mod tests {
         let interpreter = Interpreter::new_steppable(
             Revision::EVMC_ISTANBUL,
             &message,
-            &mut context,
+            Some(&mut context),
             &[Opcode::Add as u8],
             0,
             0,
-            Stack::new(&[]),
+            Stack::new(&[]).unwrap(),
             Memory::new(&[]),
             None,
             Some(0),
@@ -1960,11 +2770,11 @@ mod tests {
         let interpreter = Interpreter::new_steppable(
             Revision::EVMC_ISTANBUL,
             &message,
-            &mut context,
+            Some(&mut context),
             &[Opcode::Add as u8, Opcode::Add as u8],
             0,
             0,
-            Stack::new(&[1u8.into(), 2u8.into()]),
+            Stack::new(&[1u8.into(), 2u8.into()]).unwrap(),
             Memory::new(&[]),
             None,
             Some(1),
@@ -1985,10 +2795,11 @@ mod tests {
         let mut interpreter = Interpreter::new(
             Revision::EVMC_ISTANBUL,
             &message,
-            &mut context,
+            Some(&mut context),
             &[Opcode::Add as u8],
-        );
-        interpreter.stack = Stack::new(&[1u8.into(), 2u8.into()]);
+        )
+        .unwrap();
+        interpreter.stack = Stack::new(&[1u8.into(), 2u8.into()]).unwrap();
         let result: StepResult = interpreter.run(&mut NoOpObserver());
         assert_eq!(result.step_status_code, StepStatusCode::EVMC_STEP_STOPPED);
         assert_eq!(result.stack.as_slice(), [u256::from(3u8).into()]);
@@ -2005,10 +2816,11 @@ mod tests {
         let mut interpreter = Interpreter::new(
             Revision::EVMC_ISTANBUL,
             &message,
-            &mut context,
+            Some(&mut context),
             &[Opcode::Add as u8, Opcode::Add as u8],
-        );
-        interpreter.stack = Stack::new(&[1u8.into(), 2u8.into(), 3u8.into()]);
+        )
+        .unwrap();
+        interpreter.stack = Stack::new(&[1u8.into(), 2u8.into(), 3u8.into()]).unwrap();
         let result: StepResult = interpreter.run(&mut NoOpObserver());
         assert_eq!(result.step_status_code, StepStatusCode::EVMC_STEP_STOPPED);
         assert_eq!(result.stack.as_slice(), [u256::from(6u8).into()]);
@@ -2030,9 +2842,10 @@ mod tests {
         let interpreter = Interpreter::new(
             Revision::EVMC_ISTANBUL,
             &message,
-            &mut context,
+            Some(&mut context),
             &[Opcode::JumpDest as u8; 10_000_000],
-        );
+        )
+        .unwrap();
         let result: StepResult = interpreter.run(&mut NoOpObserver());
         assert_eq!(result.step_status_code, StepStatusCode::EVMC_STEP_STOPPED);
     }
@@ -2048,10 +2861,11 @@ mod tests {
         let mut interpreter = Interpreter::new(
             Revision::EVMC_ISTANBUL,
             &message,
-            &mut context,
+            Some(&mut context),
             &[Opcode::Add as u8],
-        );
-        interpreter.stack = Stack::new(&[1u8.into(), 2u8.into()]);
+        )
+        .unwrap();
+        interpreter.stack = Stack::new(&[1u8.into(), 2u8.into()]).unwrap();
         let result: ExecutionResult = interpreter.run(&mut NoOpObserver());
         assert_eq!(result.status_code, StatusCode::EVMC_OUT_OF_GAS);
     }
@@ -2129,11 +2943,11 @@ mod tests {
         let interpreter = Interpreter::new_steppable(
             Revision::EVMC_ISTANBUL,
             &message,
-            &mut context,
+            Some(&mut context),
             &[Opcode::Call as u8],
             0,
             0,
-            Stack::new(&stack),
+            Stack::new(&stack).unwrap(),
             Memory::new(&memory),
             None,
             None,
@@ -2154,4 +2968,303 @@ mod tests {
             ret_data.as_slice()
         );
     }
+
+    #[test]
+    fn call_dispatches_precompile_natively_without_host_round_trip() {
+        // Address 0x04 (Identity) names a precompile at every revision, so `call` should run it
+        // in-process via `dispatch_call` and never ask the host to execute it.
+        let input = [1u8, 2, 3, 4];
+        let gas = 1_000u64;
+        let addr = u256::from(4u8);
+        let value = u256::ZERO;
+        let args_offset = 0usize;
+        let args_len = input.len();
+        let ret_offset = 0usize;
+        let ret_len = input.len();
+
+        let message: evmc_vm::ExecutionMessage = MockExecutionMessage::default().into();
+
+        let mut context = MockExecutionContextTrait::new();
+        context
+            .expect_get_balance()
+            .times(1)
+            .return_const(Uint256::from(u256::ZERO));
+        context.expect_call().times(0);
+
+        let stack = [
+            ret_len.into(),
+            ret_offset.into(),
+            args_len.into(),
+            args_offset.into(),
+            value,
+            addr,
+            gas.into(),
+        ];
+
+        let interpreter = Interpreter::new_steppable(
+            Revision::EVMC_ISTANBUL,
+            &message,
+            Some(&mut context),
+            &[Opcode::Call as u8],
+            0,
+            0,
+            Stack::new(&stack).unwrap(),
+            Memory::new(&input),
+            None,
+            None,
+        );
+        let result: StepResult = interpreter.run(&mut NoOpObserver());
+        assert_eq!(result.step_status_code, StepStatusCode::EVMC_STEP_STOPPED);
+        // Identity costs 15 + 3 words for a 1-word (4-byte) input, on top of the flat pre-Berlin
+        // 700 address access charge.
+        assert_eq!(
+            result.gas_left,
+            MockExecutionMessage::DEFAULT_INIT_GAS as i64 - 700 - 18
+        );
+        assert_eq!(&result.memory[ret_offset..ret_offset + ret_len], &input);
+    }
+
+    #[cfg(feature = "call-trap")]
+    #[test]
+    fn call_traps_instead_of_dispatching_then_resumes_to_the_same_final_state_as_call() {
+        // Same setup as `call` above, but drives the sub-call out-of-band through the call-trap
+        // API instead of a mocked host answering `context.call` inline, checking both paths land
+        // on the same final `StepResult`.
+        let mut unique_values = 1u8..;
+        let mut next_value = || unique_values.next().unwrap();
+
+        let memory = [next_value(), next_value(), next_value(), next_value()];
+        let ret_data = [next_value(), next_value()];
+
+        let gas = next_value() as u64;
+        let addr = next_value().into();
+        let value = u256::ZERO;
+        let args_offset = 1usize;
+        let args_len = memory.len() - args_offset - 1;
+        let ret_offset = 1usize;
+        let ret_len = ret_data.len();
+
+        let input = memory[args_offset..args_offset + args_len].to_vec();
+
+        let message = MockExecutionMessage {
+            recipient: u256::from(next_value()).into(),
+            ..Default::default()
+        };
+        let depth = message.depth;
+        let recipient = message.recipient;
+
+        let mut context = MockExecutionContextTrait::new();
+        context
+            .expect_get_balance()
+            .times(1)
+            .with(predicate::eq(Address::from(recipient)))
+            .return_const(Uint256::from(u256::ZERO));
+        context.expect_call().times(0);
+
+        let message = message.into();
+
+        let stack = [
+            ret_len.into(),
+            ret_offset.into(),
+            args_len.into(),
+            args_offset.into(),
+            value,
+            addr,
+            gas.into(),
+        ];
+
+        let code = [Opcode::Call as u8];
+        let interpreter = Interpreter::new_steppable(
+            Revision::EVMC_ISTANBUL,
+            &message,
+            Some(&mut context),
+            &code,
+            0,
+            0,
+            Stack::new(&stack).unwrap(),
+            Memory::new(&memory),
+            None,
+            None,
+        );
+
+        let trap = match interpreter.run_resumable(&mut NoOpObserver()) {
+            Execution::Trapped(trap) => trap,
+            _ => panic!("expected Execution::Trapped"),
+        };
+        assert!(
+            trap.call.kind == MessageKind::EVMC_CALL
+                && trap.call.flags == 0
+                && trap.call.depth == depth + 1
+                && trap.call.gas == gas as i64
+                && trap.call.sender == Address::from(recipient)
+                && trap.call.destination == Address::from(addr)
+                && trap.call.code_address == Address::from(addr)
+                && trap.call.value == Uint256::from(value)
+        );
+        assert_eq!(trap.call.input, input);
+        let sub_call_message = trap.call.to_execution_message();
+        assert_eq!(sub_call_message.code(), None);
+
+        #[cfg(not(feature = "custom-evmc"))]
+        let result = ExecutionResult::new(StatusCode::EVMC_SUCCESS, 0, 0, Some(&ret_data));
+        #[cfg(feature = "custom-evmc")]
+        let result = ExecutionResult::new(
+            StatusCode::EVMC_SUCCESS,
+            0,
+            0,
+            Some(Box::from(ret_data.as_slice())),
+        );
+
+        let interpreter = Interpreter::resume_call(
+            Revision::EVMC_ISTANBUL,
+            &message,
+            Some(&mut context),
+            &code,
+            trap,
+            None,
+            &result,
+        )
+        .unwrap();
+        let result: StepResult = interpreter.run(&mut NoOpObserver());
+        assert_eq!(result.step_status_code, StepStatusCode::EVMC_STEP_STOPPED);
+        assert_eq!(result.pc, 1);
+        assert_eq!(
+            result.gas_left,
+            MockExecutionMessage::DEFAULT_INIT_GAS as i64 - 700 - gas as i64
+        );
+        assert_eq!(
+            result.last_call_return_data.as_deref(),
+            Some(ret_data.as_slice())
+        );
+        assert_eq!(
+            &result.memory[ret_offset..ret_offset + ret_len],
+            ret_data.as_slice()
+        );
+    }
+
+    #[cfg(feature = "interrupt")]
+    #[test]
+    fn with_interrupt_stops_run_with_interrupted_status() {
+        use std::sync::{atomic::AtomicBool, Arc};
+
+        use crate::types::Interrupt;
+
+        let mut context = MockExecutionContextTrait::new();
+        let message = MockExecutionMessage::default().into();
+        let mut interpreter = Interpreter::new(
+            Revision::EVMC_ISTANBUL,
+            &message,
+            Some(&mut context),
+            &[Opcode::Add as u8],
+        )
+        .unwrap()
+        .with_interrupt(Interrupt::new(Arc::new(AtomicBool::new(true)), None));
+        // Force the next op to land on a check instead of waiting out a full
+        // `INTERRUPT_CHECK_INTERVAL` batch.
+        interpreter.interrupt_counter = 1;
+        let result: ExecutionResult = interpreter.run(&mut NoOpObserver());
+        assert_eq!(result.status_code, StatusCode::EVMC_INTERNAL_ERROR);
+    }
+
+    #[cfg(feature = "interrupt")]
+    #[test]
+    fn with_interrupt_max_steps_stops_run_on_an_adversarial_loop() {
+        use std::sync::{atomic::AtomicBool, Arc};
+
+        use crate::types::Interrupt;
+
+        let mut context = MockExecutionContextTrait::new();
+        let message = MockExecutionMessage::default().into();
+        // An unconditional back-jump to its own `JUMPDEST`: with `message.gas()` alone there is no
+        // bound on how long this runs, since each iteration is cheap and the test's mocked message
+        // carries plenty of it.
+        let code = [Opcode::JumpDest as u8, Opcode::Push0 as u8, Opcode::Jump as u8];
+        let mut interpreter =
+            Interpreter::new(Revision::EVMC_CANCUN, &message, Some(&mut context), &code)
+                .unwrap()
+                .with_interrupt(Interrupt::new(Arc::new(AtomicBool::new(false)), None).with_max_steps(1));
+        // Force the next op to land on a check instead of waiting out a full
+        // `INTERRUPT_CHECK_INTERVAL` batch.
+        interpreter.interrupt_counter = 1;
+        let result: ExecutionResult = interpreter.run(&mut NoOpObserver());
+        assert_eq!(result.status_code, StatusCode::EVMC_INTERNAL_ERROR);
+    }
+
+    #[test]
+    fn context_less_interpreter_runs_pure_bytecode() {
+        let message = MockExecutionMessage::default().into();
+        // Stack/arithmetic only - PUSH1 1 PUSH1 1 ADD POP - so a `None` context never gets reached.
+        let code = [
+            Opcode::Push1 as u8,
+            1,
+            Opcode::Push1 as u8,
+            1,
+            Opcode::Add as u8,
+            Opcode::Pop as u8,
+        ];
+        let interpreter = Interpreter::new(Revision::EVMC_CANCUN, &message, None, &code).unwrap();
+        let result: ExecutionResult = interpreter.run(&mut NoOpObserver());
+        assert_eq!(result.status_code, StatusCode::EVMC_SUCCESS);
+    }
+
+    #[test]
+    fn context_less_interpreter_fails_instead_of_dereferencing_a_null_host() {
+        let message = MockExecutionMessage::default().into();
+        let code = [Opcode::Push0 as u8, Opcode::Balance as u8];
+        let interpreter = Interpreter::new(Revision::EVMC_CANCUN, &message, None, &code).unwrap();
+        let result: ExecutionResult = interpreter.run(&mut NoOpObserver());
+        assert_eq!(result.status_code, StatusCode::EVMC_INTERNAL_ERROR);
+    }
+
+    #[test]
+    fn suspended_execution_snapshot_round_trips() {
+        let suspended = SuspendedExecution {
+            pc: 5,
+            gas_left: 42,
+            gas_refund: 7,
+            revision: Revision::EVMC_CANCUN,
+            stack: Stack::new(&[u256::ONE, u256::MAX]).unwrap(),
+            memory: Memory::new(&[0xaa, 0xbb, 0xcc]),
+            last_call_return_data: Some(vec![1, 2, 3]),
+        };
+
+        let restored = SuspendedExecution::from_snapshot(&suspended.to_snapshot()).unwrap();
+
+        assert_eq!(restored.pc, suspended.pc);
+        assert_eq!(restored.gas_left, suspended.gas_left);
+        assert_eq!(restored.gas_refund, suspended.gas_refund);
+        assert_eq!(restored.revision, suspended.revision);
+        assert_eq!(restored.stack.as_slice(), suspended.stack.as_slice());
+        assert_eq!(restored.memory.as_slice(), suspended.memory.as_slice());
+        assert_eq!(
+            restored.last_call_return_data,
+            suspended.last_call_return_data
+        );
+    }
+
+    #[test]
+    fn suspended_execution_snapshot_rejects_truncated_and_future_versioned_buffers() {
+        let suspended = SuspendedExecution {
+            pc: 0,
+            gas_left: 0,
+            gas_refund: 0,
+            revision: Revision::EVMC_ISTANBUL,
+            stack: Stack::new(&[]).unwrap(),
+            memory: Memory::new(&[]),
+            last_call_return_data: None,
+        };
+        let mut snapshot = suspended.to_snapshot();
+        snapshot.truncate(snapshot.len() - 1);
+        assert_eq!(
+            SuspendedExecution::from_snapshot(&snapshot),
+            Err(SnapshotError::Truncated)
+        );
+
+        let mut snapshot = suspended.to_snapshot();
+        snapshot[0] = 255;
+        assert_eq!(
+            SuspendedExecution::from_snapshot(&snapshot),
+            Err(SnapshotError::UnsupportedVersion(255))
+        );
+    }
 }
@@ -0,0 +1,120 @@
+//! A journal of in-process, reversible interpreter state.
+//!
+//! The interpreter delegates state mutations (`SSTORE`, transient storage, `LOG`,
+//! `SELFDESTRUCT`) straight to the host [`ExecutionContextTrait`](crate::types::ExecutionContextTrait)
+//! rather than journaling them here: per the EVMC ABI this VM does not own persistent world
+//! state, the host does, and an EVMC host is already required to roll its own state back to the
+//! call's entry snapshot whenever a frame returns `EVMC_REVERT` or fails. Journaling those
+//! mutations a second time in-process would mean either overruling the host's own rollback (which
+//! this VM has no authority to do) or duplicating bookkeeping the host must already do correctly.
+//! The two pieces of mutable state the interpreter *does* own outright are the EIP-2929
+//! [`AccessList`] and the [`GasRefund`] counter, and neither of those is undone by the host on
+//! `REVERT`. [`Journal`] records every change to them so that [`revert_to`](Journal::revert_to)
+//! can restore a prior [`snapshot`](Journal::snapshot) exactly, keeping warm/cold status and
+//! refund accounting correct across reverted frames.
+//!
+//! The host side of that contract - rolling back storage/transient-storage/logs/selfdestruct on
+//! `REVERT` - isn't just asserted here: [`MockedHost`](crate::types::MockedHost) implements it via
+//! `checkpoint`/`revert_to`, with a test exercising all four.
+use evmc_vm::{Address, Uint256};
+
+use crate::{interpreter::access_list::AccessList, utils::GasRefund};
+
+enum JournalEntry {
+    AccessedAccount(Address),
+    AccessedStorage(Address, Uint256),
+    GasRefund(i64),
+}
+
+/// An append-only log of reversible mutations, with cheap snapshot/rollback.
+#[derive(Default)]
+pub struct Journal {
+    entries: Vec<JournalEntry>,
+}
+
+/// A handle returned by [`Journal::snapshot`], opaque to callers.
+pub type Snapshot = usize;
+
+impl Journal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Capture the current journal position. Pass the result to [`revert_to`](Self::revert_to)
+    /// to undo everything recorded since.
+    pub fn snapshot(&self) -> Snapshot {
+        self.entries.len()
+    }
+
+    /// Record that `address` just transitioned from cold to warm.
+    pub fn record_accessed_account(&mut self, address: Address) {
+        self.entries.push(JournalEntry::AccessedAccount(address));
+    }
+
+    /// Record that `key` of `address` just transitioned from cold to warm.
+    pub fn record_accessed_storage(&mut self, address: Address, key: Uint256) {
+        self.entries.push(JournalEntry::AccessedStorage(address, key));
+    }
+
+    /// Record a change of `delta` gas to the refund counter.
+    pub fn record_gas_refund(&mut self, delta: i64) {
+        if delta != 0 {
+            self.entries.push(JournalEntry::GasRefund(delta));
+        }
+    }
+
+    /// Undo every entry recorded since `snapshot`, in reverse order, restoring `access_list` and
+    /// `gas_refund` to exactly the state they were in when the snapshot was taken.
+    pub fn revert_to(&mut self, snapshot: Snapshot, access_list: &mut AccessList, gas_refund: &mut GasRefund) {
+        while self.entries.len() > snapshot {
+            match self.entries.pop().expect("just checked len > snapshot") {
+                JournalEntry::AccessedAccount(address) => access_list.forget_account(&address),
+                JournalEntry::AccessedStorage(address, key) => {
+                    access_list.forget_storage(&address, &key);
+                }
+                // Reverting a recorded refund change means subtracting it back out; overflow
+                // here would mean the forward `add` that produced this entry should itself have
+                // failed, so it cannot happen.
+                JournalEntry::GasRefund(delta) => gas_refund.add(-delta).expect("inverse of a recorded add"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::u256;
+
+    #[test]
+    fn reverts_access_list_insertions() {
+        let address: Address = u256::from(1u8).into();
+        let mut access_list = AccessList::new([]);
+        let mut gas_refund = GasRefund::new(0);
+        let mut journal = Journal::new();
+
+        let snapshot = journal.snapshot();
+        access_list.access_account(&address, None);
+        journal.record_accessed_account(address);
+
+        journal.revert_to(snapshot, &mut access_list, &mut gas_refund);
+        assert_eq!(
+            access_list.access_account(&address, None),
+            evmc_vm::AccessStatus::EVMC_ACCESS_COLD
+        );
+    }
+
+    #[test]
+    fn reverts_gas_refund_changes() {
+        let mut access_list = AccessList::new([]);
+        let mut gas_refund = GasRefund::new(0);
+        let mut journal = Journal::new();
+
+        let snapshot = journal.snapshot();
+        gas_refund.add(24_000).unwrap();
+        journal.record_gas_refund(24_000);
+
+        journal.revert_to(snapshot, &mut access_list, &mut gas_refund);
+        assert_eq!(gas_refund.as_i64(), 0);
+    }
+}
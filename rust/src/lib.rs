@@ -1,4 +1,12 @@
 #![allow(unused_crate_dependencies)]
+// Used by `types::code_analysis`'s alloc-based `Vec`/`Arc`/`Rc` so that module does not pull in
+// `std` for the parts of it that don't actually need it. The crate as a whole is still firmly
+// `std`-only (the EVMC FFI layer, `mimalloc`, `HashMap`-backed `MockedHost`, ...), so this alone
+// does not make Tosca buildable under `no_std`; it only keeps that one module from regressing.
+extern crate alloc;
+
+#[cfg(all(feature = "block-compiler", not(feature = "needs-fn-ptr-conversion")))]
+mod compiler;
 mod evmc;
 mod ffi;
 mod interpreter;
@@ -15,6 +23,21 @@ compile_error!(
     Either disable it or enable one or all of `code-analysis-cache` or `hash-cache`."
 );
 
+#[cfg(all(feature = "needs-cache", not(feature = "std")))]
+compile_error!(
+    "Feature `needs-cache` (via `code-analysis-cache`, `hash-cache`, or `jump-cache`) requires the
+    `std` feature: its `Cache` is backed by `std::sync::Mutex`/`LazyLock`, or `std::thread_local!`
+    under `thread-local-cache`, none of which are available in a `no_std` build. Disable the cache
+    feature(s) or enable `std`."
+);
+
+#[cfg(all(feature = "buffer-pool", not(feature = "std")))]
+compile_error!(
+    "Feature `buffer-pool` requires the `std` feature: its `BufferPool` is backed by
+    `std::sync::atomic::AtomicU64`/`LazyLock`, neither available in a `no_std` build. Disable
+    `buffer-pool` or enable `std`."
+);
+
 #[cfg(all(
     feature = "needs-fn-ptr-conversion",
     not(feature = "fn-ptr-conversion-expanded-dispatch"),
@@ -46,9 +69,15 @@ use llvm_profile_wrappers::{
     llvm_profile_write_file,
 };
 use types::u256;
+// Re-exported so an embedder calling `EvmRs::run` in-process gets exhaustive `match` checking
+// over these without adding `evmc_vm_tosca`/`evmc_vm_tosca_refactor` as a direct dependency of
+// their own - the crate feature selecting between them is already resolved here.
+pub use evmc_vm::{MessageKind, Revision, StatusCode, StorageStatus};
 pub use types::ExecutionContextTrait;
 #[cfg(feature = "mock")]
 pub use types::MockExecutionContextTrait;
+#[cfg(feature = "mock")]
+pub use types::{MockedAccount, MockedHost, MockedLog};
 
 /// Dump coverage data when compiled with `RUSTFLAGS="-C instrument-coverage"`.
 /// Otherwise this is a no-op.
@@ -0,0 +1,168 @@
+//! A concurrent, append-only arena for fixed-shape buffers - e.g. the expanded instruction
+//! streams [`CodeAnalysis`](crate::types::CodeAnalysis) builds per contract. Those buffers are
+//! immutable and `Arc`-shared once built, so allocating each one out of a process-wide `Vec` would
+//! mean nothing but growth, never shrinkage, and a lot of allocator churn when many distinct
+//! contracts are touched within one block. [`TypedArena`] instead hands out slices from a chain of
+//! fixed-size chunks and never frees or moves one until the whole arena is dropped, so the chunks
+//! can be filled from several worker threads analyzing different contracts at once without a
+//! shared lock: [`alloc_slice`](TypedArena::alloc_slice) only needs `&self`, claiming its region of
+//! a chunk with a compare-and-swap instead of a mutex.
+
+use std::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ptr,
+    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+};
+
+/// One fixed-capacity block of slots, bump-allocated by [`claim`](Self::claim). Chunks are linked
+/// newest-to-oldest through `next` - a plain pointer, not an `AtomicPtr`, because it is written
+/// once at construction and only ever read afterwards, either through `head` (already providing
+/// the necessary synchronization to observe a fully-initialized chunk) or during
+/// [`TypedArena`]'s `Drop`, by which point nothing else can still be using the arena.
+struct Chunk<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    claimed: AtomicUsize,
+    next: *mut Chunk<T>,
+}
+
+// SAFETY:
+// `Chunk` is only ever reached through an `AtomicPtr`, which does not itself constrain T - Send
+// is otherwise derived correctly from `slots`, so this restates rather than weakens that bound.
+unsafe impl<T: Send> Send for Chunk<T> {}
+// SAFETY:
+// `slots`' `UnsafeCell` makes `Chunk` `!Sync` by default, but concurrent `&Chunk` access is sound:
+// each index in `slots` is claimed by exactly one caller (via the CAS in `claim`), written by that
+// same caller, and hasn't been read by it and only it before the chunk as a whole is shared (via
+// `TypedArena::head`'s own Acquire/Release pair), so there is no data race on any individual slot.
+unsafe impl<T: Send + Sync> Sync for Chunk<T> {}
+
+impl<T> Chunk<T> {
+    fn new(capacity: usize, next: *mut Chunk<T>) -> Self {
+        let slots = (0..capacity)
+            .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+            .collect();
+        Self {
+            slots,
+            claimed: AtomicUsize::new(0),
+            next,
+        }
+    }
+
+    /// Reserves `count` contiguous slots for the exclusive use of the caller, returning the index
+    /// of the first one, or `None` if the chunk does not have that much room left. Retries the CAS
+    /// against whatever `claimed` actually is on contention, so two callers racing for the last
+    /// slots never both succeed.
+    fn claim(&self, count: usize) -> Option<usize> {
+        let mut current = self.claimed.load(Ordering::Relaxed);
+        loop {
+            let next = current.checked_add(count)?;
+            if next > self.slots.len() {
+                return None;
+            }
+            match self
+                .claimed
+                .compare_exchange_weak(current, next, Ordering::AcqRel, Ordering::Relaxed)
+            {
+                Ok(_) => return Some(current),
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Copies `data` into the slots `start..start + data.len()` and returns them back as a slice.
+    ///
+    /// # Safety
+    /// `start..start + data.len()` must be a range this chunk's [`claim`](Self::claim) has just
+    /// returned to the caller, and not have been written (or read) through by anyone else.
+    unsafe fn write(&self, start: usize, data: &[T]) -> &[T]
+    where
+        T: Copy,
+    {
+        for (offset, value) in data.iter().enumerate() {
+            (*self.slots[start + offset].get()).write(*value);
+        }
+        // SAFETY: `slots` is a `Box<[UnsafeCell<MaybeUninit<T>>]>`, which has the same layout as
+        // `[T]` would - `UnsafeCell<U>` and `MaybeUninit<U>` are both guaranteed to share `U`'s
+        // layout - and every slot in `start..start + data.len()` was just initialized above.
+        std::slice::from_raw_parts((*self.slots[start].get()).as_ptr(), data.len())
+    }
+}
+
+/// See the [module docs](self) for the problem this solves. `chunk_len` is the number of `T`s a
+/// freshly-allocated chunk holds; a single [`alloc_slice`](Self::alloc_slice) call larger than
+/// that gets its own dedicated, correctly-sized chunk instead of failing or splitting the slice.
+pub struct TypedArena<T> {
+    head: AtomicPtr<Chunk<T>>,
+    chunk_len: usize,
+    // `AtomicPtr<Chunk<T>>` is Send + Sync regardless of T, which would make the auto-derived
+    // impls for this struct unconditional (and unsound) without this marker to tie them back to
+    // `Chunk<T>`'s own, correctly-bounded, impls above.
+    _marker: PhantomData<Chunk<T>>,
+}
+
+// SAFETY: see `Sync for Chunk<T>` - the same reasoning applies one level up.
+unsafe impl<T: Send + Sync> Sync for TypedArena<T> {}
+
+impl<T: Copy> TypedArena<T> {
+    pub fn new(chunk_len: usize) -> Self {
+        Self {
+            head: AtomicPtr::new(ptr::null_mut()),
+            chunk_len: chunk_len.max(1),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Copies `data` into the arena and returns a slice pointing at the copy, valid for as long as
+    /// `self` is not dropped. Safe to call from multiple threads sharing one `&TypedArena<T>`.
+    pub fn alloc_slice(&self, data: &[T]) -> &[T] {
+        if data.is_empty() {
+            return &[];
+        }
+        loop {
+            let head = self.head.load(Ordering::Acquire);
+            if let Some(chunk) = unsafe { head.as_ref() } {
+                // SAFETY: `head` was published by a prior successful `compare_exchange` below (or
+                // is null, handled above) and every chunk stays alive until `self` is dropped -
+                // `alloc_slice` only ever prepends chunks, never unlinks or frees one.
+                if let Some(start) = chunk.claim(data.len()) {
+                    // SAFETY: `start` was just reserved by `claim` on this exact chunk.
+                    return unsafe { chunk.write(start, data) };
+                }
+            }
+
+            // Either there is no chunk yet or the current head is full: build a new one, sized to
+            // fit `data` even if that is larger than `chunk_len`, and try to publish it as the new
+            // head.
+            let capacity = self.chunk_len.max(data.len());
+            let new_chunk = Box::into_raw(Box::new(Chunk::new(capacity, head)));
+            if self
+                .head
+                .compare_exchange(head, new_chunk, Ordering::AcqRel, Ordering::Relaxed)
+                .is_err()
+            {
+                // Lost the race: nothing was ever claimed in the chunk we just built, so dropping
+                // it is all the cleanup needed.
+                // SAFETY: `new_chunk` was just created by `Box::into_raw` above and has not been
+                // shared with anyone else.
+                drop(unsafe { Box::from_raw(new_chunk) });
+            }
+            // Whether we installed the new chunk or someone else did, retry the claim from the top
+            // against whatever the head is now.
+        }
+    }
+}
+
+impl<T> Drop for TypedArena<T> {
+    fn drop(&mut self) {
+        let mut current = *self.head.get_mut();
+        while !current.is_null() {
+            // SAFETY: every non-null node in this chain was created by exactly one `Box::into_raw`
+            // in `alloc_slice`, is only ever freed here, and `&mut self` guarantees nothing else
+            // can still be walking or writing into the chain.
+            let chunk = unsafe { Box::from_raw(current) };
+            current = chunk.next;
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! A generic, size-classed, thread-safe pool for recycling `Vec<T>` scratch allocations - the
+//! allocations the `benchmarks` crate's `RunArgs::ffi_overhead` doc comment calls out as
+//! happening fresh on every single interpreter invocation: the `CodeReader` analysis buffers
+//! ([`CodeAnalysis`](crate::types::CodeAnalysis)'s `analysis`/`basic_blocks`/`block_index`), and
+//! the call output buffer `GasLeft::finalize` reads out of memory. Unlike
+//! [`JumpAnalysis`](crate::types::jump_analysis::JumpAnalysis)'s own single-class free list (which
+//! only ever inspects the top of one mutex-guarded stack and gives up rather than searching past an
+//! undersized buffer) or [`types::memory`](crate::types::memory)'s thread-local `alloc-reuse` pool
+//! (scoped to one thread, so a buffer freed on one worker can never be handed to another), this
+//! pool buckets by capacity into power-of-two size classes, one lock-free stack per class, and is
+//! process-wide: a buffer released on one thread is immediately available to any other.
+//!
+//! Each class's stack is a Treiber stack whose head is a tagged pointer - the node pointer packed
+//! into the low 48 bits of a `u64` (the full range of a real pointer on every 64-bit target this
+//! crate supports; the top 16 bits of a canonical address are always zero) alongside a 16-bit
+//! version counter in the high bits. Every `compare_exchange` bumps the counter, so a classic ABA
+//! (pop A, push B, push A back at the same address, then a stale `compare_exchange` on the first
+//! A succeeds against the second) fails instead: the second A's tag differs from the first's.
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    LazyLock,
+};
+
+/// Number of size classes a [`BufferPool`] uses when a caller doesn't pick one explicitly. Class
+/// `i` holds buffers with capacity in `(2^(i + MIN_CLASS_SHIFT - 1), 2^(i + MIN_CLASS_SHIFT)]`
+/// (class 0 also takes every smaller nonzero capacity), so
+/// the default spans from 64 elements up to 64 * 2^11 = 128 KiB elements before `release` just
+/// drops a buffer instead of pooling it - large enough for any contract's analysis or output, not
+/// so large that a handful of pathologically large ones can pin unbounded memory in the pool.
+pub const DEFAULT_CLASSES: usize = 12;
+
+const MIN_CLASS_SHIFT: u32 = 6;
+
+const TAG_BITS: u32 = 16;
+const PTR_BITS: u32 = u64::BITS - TAG_BITS;
+const PTR_MASK: u64 = (1 << PTR_BITS) - 1;
+
+struct Node<T> {
+    buf: Vec<T>,
+    next: *mut Node<T>,
+}
+
+// SAFETY: a `Node` is only ever reached through a `TreiberStack`'s tagged `head`, and every access
+// to it (the read in `acquire`, the write of `next` in `release`) is gated by the `compare_exchange`
+// that exclusively claims it - so `Vec<T>`'s own `Send` carries through.
+unsafe impl<T: Send> Send for Node<T> {}
+unsafe impl<T: Send> Sync for Node<T> {}
+
+/// One size class's free list: a Treiber stack of `Node<T>`s, addressed through a tagged `head` to
+/// stay ABA-safe (see the module doc comment).
+struct TreiberStack<T>(AtomicU64);
+
+impl<T> TreiberStack<T> {
+    const fn new() -> Self {
+        Self(AtomicU64::new(0))
+    }
+
+    fn unpack(packed: u64) -> *mut Node<T> {
+        (packed & PTR_MASK) as usize as *mut Node<T>
+    }
+
+    fn pack(ptr: *mut Node<T>, tag: u64) -> u64 {
+        (ptr as u64 & PTR_MASK) | (tag << PTR_BITS)
+    }
+
+    /// Pops a buffer with capacity for at least `min_capacity` elements, or `None` if this class
+    /// is empty or its top buffer is undersized - the same "only ever look at the head" trade-off
+    /// `JumpAnalysis`'s pool makes: a size-classed pool should rarely see this happen, and
+    /// searching past the head would turn a lock-free pop into an unbounded scan under contention.
+    fn acquire(&self, min_capacity: usize) -> Option<Vec<T>> {
+        loop {
+            let packed = self.0.load(Ordering::Acquire);
+            let ptr = Self::unpack(packed);
+            // SAFETY: every non-null value ever stored here was published by `release`'s
+            // `Box::into_raw` below, and the only way to free one is the `Box::from_raw` a few
+            // lines down, itself gated by the `compare_exchange` that exclusively claims it - so
+            // while this load observes a non-null pointer, it points at a live allocation.
+            let node = unsafe { ptr.as_ref() }?;
+            if node.buf.capacity() < min_capacity {
+                return None;
+            }
+            let tag = packed >> PTR_BITS;
+            let new_packed = Self::pack(node.next, tag.wrapping_add(1));
+            if self
+                .0
+                .compare_exchange_weak(packed, new_packed, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+            {
+                // SAFETY: this `compare_exchange` is what exclusively claims `ptr` - no other
+                // caller can also have popped it, and it was allocated by `release`'s own
+                // `Box::into_raw`.
+                let node = unsafe { Box::from_raw(ptr) };
+                return Some(node.buf);
+            }
+        }
+    }
+
+    /// Pushes `buf` (already cleared by the caller) onto this class for a future
+    /// [`acquire`](Self::acquire) to reuse.
+    fn release(&self, buf: Vec<T>) {
+        let node = Box::into_raw(Box::new(Node {
+            buf,
+            next: std::ptr::null_mut(),
+        }));
+        loop {
+            let packed = self.0.load(Ordering::Acquire);
+            let head = Self::unpack(packed);
+            // SAFETY: `node` was just created above and isn't reachable from `self.0` yet, so
+            // nothing else can be reading or writing its `next` field concurrently.
+            unsafe {
+                (*node).next = head;
+            }
+            let tag = packed >> PTR_BITS;
+            let new_packed = Self::pack(node, tag.wrapping_add(1));
+            if self
+                .0
+                .compare_exchange_weak(packed, new_packed, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+            {
+                return;
+            }
+        }
+    }
+}
+
+impl<T> Drop for TreiberStack<T> {
+    fn drop(&mut self) {
+        let mut ptr = Self::unpack(*self.0.get_mut());
+        // SAFETY: `&mut self` means nothing else can still be popping from or pushing to this
+        // stack, so walking and freeing the chain here is exclusive.
+        while let Some(node) = unsafe { ptr.as_mut() } {
+            let next = node.next;
+            drop(unsafe { Box::from_raw(ptr) });
+            ptr = next;
+        }
+    }
+}
+
+/// A process-wide pool of `Vec<T>` buffers, bucketed into `CLASSES` power-of-two size classes (see
+/// [`DEFAULT_CLASSES`]). Built with [`LazyLock`], the same deferred-init trick
+/// [`Cache`](crate::types::Cache) uses for its shard array, so construction isn't itself required
+/// to be `const`.
+pub struct BufferPool<T, const CLASSES: usize = DEFAULT_CLASSES>(
+    LazyLock<[TreiberStack<T>; CLASSES]>,
+);
+
+impl<T: Send, const CLASSES: usize> BufferPool<T, CLASSES> {
+    pub const fn new() -> Self {
+        Self(LazyLock::new(|| std::array::from_fn(|_| TreiberStack::new())))
+    }
+
+    /// The largest capacity this pool will hold onto (2^17 elements with [`DEFAULT_CLASSES`]); a
+    /// `release` past this is just dropped. `types::memory`'s `alloc-reuse` pool applies its own,
+    /// separate 1 MiB cap to its buffers.
+    const fn max_pooled_len() -> usize {
+        1 << (MIN_CLASS_SHIFT as usize + CLASSES - 1)
+    }
+
+    fn class_for(len: usize) -> Option<usize> {
+        if len == 0 || len > Self::max_pooled_len() {
+            return None;
+        }
+        let shift = len.next_power_of_two().trailing_zeros();
+        Some(shift.saturating_sub(MIN_CLASS_SHIFT) as usize)
+    }
+
+    /// Pops a spare buffer with capacity for at least `min_capacity` elements, ready to be
+    /// `clear`ed and refilled, allocating a fresh one only if the pool has nothing suitable.
+    pub fn acquire(&self, min_capacity: usize) -> Vec<T> {
+        Self::class_for(min_capacity)
+            .and_then(|class| self.0[class].acquire(min_capacity))
+            .unwrap_or_default()
+    }
+
+    /// Hands `buf` back to the pool for a future [`acquire`](Self::acquire) to reuse, or drops it
+    /// if it's too large to be worth holding onto.
+    pub fn release(&self, mut buf: Vec<T>) {
+        buf.clear();
+        if let Some(class) = Self::class_for(buf.capacity()) {
+            self.0[class].release(buf);
+        }
+    }
+}
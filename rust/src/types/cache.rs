@@ -1,3 +1,13 @@
+//! A bounded, optionally thread-local LRU cache, used for both the jump-destination and code
+//! analysis caches.
+//!
+//! This type is the one place in the crate that would need to change to run this interpreter core
+//! on a `no_std` host: the non-`thread-local-cache` path's `LazyLock<Mutex<_>>` needs a `no_std`
+//! mutex (e.g. `spin::Mutex` plus `once_cell::race` or `spin::Lazy` in place of `LazyLock`), while
+//! `RefCell`/`LocalKey` under `thread-local-cache` and `lru::LruCache` itself are already
+//! `alloc`-only and need no substitute. Actually wiring that up needs a new Cargo feature and a
+//! `no_std`-capable mutex dependency declared in this crate's manifest, which isn't present in
+//! this checkout to extend - left as a note for whoever adds the manifest back.
 #[cfg(not(feature = "thread-local-cache"))]
 use std::sync::{LazyLock, Mutex};
 #[cfg(feature = "thread-local-cache")]
@@ -9,15 +19,26 @@ use std::{
 
 use lru::{DefaultHasher, LruCache};
 
-pub struct Cache<const S: usize, K, V, H = DefaultHasher>(
-    // Mutex<LruCache<...>> is faster that quick_cache::Cache<...>
-    #[cfg(not(feature = "thread-local-cache"))] LazyLock<Mutex<LruCache<K, V, H>>>,
+/// Shard count a [`Cache`] uses when a caller doesn't pick one explicitly. Under
+/// `thread-local-cache` this is unused - each thread already has an uncontended cache of its own -
+/// but the non-`thread-local-cache` path stripes its single backing `Mutex` into this many
+/// independent ones, so `N` concurrent `get_or_insert` calls for distinct keys can mostly proceed
+/// without blocking each other. Picked as a reasonable starting point for typical worker-thread
+/// counts; a caller with a better-measured number for its workload should pass it explicitly
+/// rather than relying on this default.
+pub const DEFAULT_SHARDS: usize = 16;
+
+pub struct Cache<const S: usize, K, V, H = DefaultHasher, const N: usize = DEFAULT_SHARDS>(
+    // An array of `N` shards rather than one `Mutex<LruCache<...>>` so independent threads hitting
+    // different shards don't serialize on each other - see `shard_for`.
+    #[cfg(not(feature = "thread-local-cache"))] LazyLock<[Mutex<LruCache<K, V, H>>; N]>,
     #[cfg(feature = "thread-local-cache")] RefCell<LruCache<K, V, H>>,
+    #[cfg(feature = "thread-local-cache")] std::marker::PhantomData<[(); N]>,
 )
 where
     K: Hash + Eq;
 
-impl<const S: usize, K, V, H> Cache<S, K, V, H>
+impl<const S: usize, K, V, H, const N: usize> Cache<S, K, V, H, N>
 where
     K: Hash + Eq,
     H: BuildHasher + Default,
@@ -25,27 +46,70 @@ where
     #[cfg(not(feature = "thread-local-cache"))]
     pub const fn new() -> Self {
         Self(LazyLock::new(|| {
-            Mutex::new(LruCache::with_hasher(
+            // `S / N` rounds down to 0 whenever `S < N` (a capacity smaller than the shard
+            // count); clamp each shard to at least 1 entry rather than panic, since a cache that
+            // holds a bit more than `S` total is a far smaller surprise than one that can't be
+            // constructed at all.
+            let per_shard = match NonZeroUsize::new(S / N) {
+                Some(per_shard) => per_shard,
+                None => NonZeroUsize::MIN,
+            };
+            std::array::from_fn(|_| Mutex::new(LruCache::with_hasher(per_shard, H::default())))
+        }))
+    }
+    #[cfg(feature = "thread-local-cache")]
+    pub fn new() -> Self {
+        Self(
+            RefCell::new(LruCache::with_hasher(
                 NonZeroUsize::new(S).unwrap(),
                 H::default(),
-            ))
+            )),
+            std::marker::PhantomData,
+        )
+    }
+
+    /// Like [`new`](Self::new), but with a capacity chosen at construction time instead of `S`,
+    /// for hosts that want to size (and inject) their own cache instance rather than use the
+    /// compile-time default. The capacity is split evenly across the `N` shards, same as `S` is
+    /// for [`new`](Self::new) - a `capacity` smaller than `N` (a perfectly valid, intentionally
+    /// small cache) still gets 1 entry per shard rather than panicking, so the resulting cache can
+    /// end up holding a bit more than `capacity` in that case.
+    #[cfg(not(feature = "thread-local-cache"))]
+    pub fn with_capacity(capacity: NonZeroUsize) -> Self {
+        let per_shard = NonZeroUsize::new(capacity.get() / N).unwrap_or(NonZeroUsize::MIN);
+        Self(LazyLock::new(move || {
+            std::array::from_fn(|_| Mutex::new(LruCache::with_hasher(per_shard, H::default())))
         }))
     }
     #[cfg(feature = "thread-local-cache")]
-    pub fn new() -> Self {
-        Self(RefCell::new(LruCache::with_hasher(
-            NonZeroUsize::new(S).unwrap(),
-            H::default(),
-        )))
+    pub fn with_capacity(capacity: NonZeroUsize) -> Self {
+        Self(
+            RefCell::new(LruCache::with_hasher(capacity, H::default())),
+            std::marker::PhantomData,
+        )
+    }
+
+    /// Which of the `N` shards `key` belongs to, taking the high bits of its hash so that keys
+    /// whose `Hash` impl writes an already-unique value (e.g. `u256Hash`, under
+    /// `BuildNoHashHasher`) still spread evenly across shards without a second, more expensive
+    /// hash pass.
+    #[cfg(not(feature = "thread-local-cache"))]
+    fn shard_for<Q: Hash + ?Sized>(key: &Q) -> usize {
+        let hash = H::default().hash_one(key);
+        ((hash >> 32) as usize) % N
     }
 
-    #[cfg(feature = "jump-cache")]
+    #[cfg(any(feature = "jump-cache", feature = "code-analysis-cache"))]
     pub fn get_or_insert(&self, key: K, f: impl FnOnce() -> V) -> V
     where
         V: Clone,
     {
         #[cfg(not(feature = "thread-local-cache"))]
-        return self.0.lock().unwrap().get_or_insert(key, f).clone();
+        return self.0[Self::shard_for(&key)]
+            .lock()
+            .unwrap()
+            .get_or_insert(key, f)
+            .clone();
         #[cfg(feature = "thread-local-cache")]
         return self.0.borrow_mut().get_or_insert(key, f).clone();
     }
@@ -58,15 +122,87 @@ where
         V: Clone,
     {
         #[cfg(not(feature = "thread-local-cache"))]
-        return self.0.lock().unwrap().get_or_insert_ref(key, f).clone();
+        return self.0[Self::shard_for(key)]
+            .lock()
+            .unwrap()
+            .get_or_insert_ref(key, f)
+            .clone();
         #[cfg(feature = "thread-local-cache")]
         return self.0.borrow_mut().get_or_insert_ref(key, f).clone();
     }
+
+    /// The number of entries currently stored, summed over all shards. Never more than the
+    /// capacity passed to [`new`](Self::new)/[`with_capacity`](Self::with_capacity), except that a
+    /// capacity smaller than `N` still holds up to one entry per shard. Each shard evicts once its
+    /// own share is full, so a total below capacity does not mean the last insert evicted nothing.
+    pub fn len(&self) -> usize {
+        #[cfg(not(feature = "thread-local-cache"))]
+        return self.0.iter().map(|shard| shard.lock().unwrap().len()).sum();
+        #[cfg(feature = "thread-local-cache")]
+        return self.0.borrow().len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every `(key, value)` pair currently held, without evicting or otherwise disturbing the
+    /// cache - the building block behind e.g.
+    /// [`CodeAnalysis::dump_cache`](crate::types::CodeAnalysis::dump_cache) for a caller that
+    /// wants to persist a warm cache's contents rather than just its size. Ordered
+    /// most-recently-used first within each shard, shards visited in index order - sharding
+    /// trades away the single global recency order the unsharded cache used to offer here, which
+    /// no caller of `snapshot` has needed so far.
+    pub fn snapshot(&self) -> Vec<(K, V)>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        #[cfg(not(feature = "thread-local-cache"))]
+        return self
+            .0
+            .iter()
+            .flat_map(|shard| {
+                shard
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        #[cfg(feature = "thread-local-cache")]
+        return self
+            .0
+            .borrow()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+    }
+
+    /// Inserts every `(key, value)` pair from `entries`, replacing the value already held for a
+    /// key (unlike [`get_or_insert`](Self::get_or_insert), which keeps it) - so reloading a snapshot that exceeds this cache's capacity evicts
+    /// the same oldest-first way a cache that grew to that size through normal use would. Each
+    /// entry is routed to its own shard, same as a live `get_or_insert` for that key would be.
+    pub fn restore(&self, entries: impl IntoIterator<Item = (K, V)>) {
+        #[cfg(not(feature = "thread-local-cache"))]
+        for (key, value) in entries {
+            let shard = Self::shard_for(&key);
+            self.0[shard].lock().unwrap().put(key, value);
+        }
+        #[cfg(feature = "thread-local-cache")]
+        {
+            let mut cache = self.0.borrow_mut();
+            for (key, value) in entries {
+                cache.put(key, value);
+            }
+        }
+    }
 }
 
 #[cfg(feature = "thread-local-cache")]
 pub trait LocalKeyExt<const S: usize, K, V, H> {
-    #[cfg(feature = "jump-cache")]
+    #[cfg(any(feature = "jump-cache", feature = "code-analysis-cache"))]
     fn get_or_insert(&'static self, key: K, f: impl FnOnce() -> V) -> V
     where
         V: Clone;
@@ -77,6 +213,15 @@ pub trait LocalKeyExt<const S: usize, K, V, H> {
         K: std::borrow::Borrow<Q>,
         Q: ToOwned<Owned = K> + Hash + Eq,
         V: Clone;
+
+    fn len(&'static self) -> usize;
+
+    fn snapshot(&'static self) -> Vec<(K, V)>
+    where
+        K: Clone,
+        V: Clone;
+
+    fn restore(&'static self, entries: impl IntoIterator<Item = (K, V)>);
 }
 
 #[cfg(feature = "thread-local-cache")]
@@ -85,7 +230,7 @@ where
     K: Hash + Eq,
     H: BuildHasher + Default,
 {
-    #[cfg(feature = "jump-cache")]
+    #[cfg(any(feature = "jump-cache", feature = "code-analysis-cache"))]
     fn get_or_insert(&'static self, key: K, f: impl FnOnce() -> V) -> V
     where
         V: Clone,
@@ -102,4 +247,20 @@ where
     {
         self.with(|c| c.get_or_insert_ref(key, f))
     }
+
+    fn len(&'static self) -> usize {
+        self.with(|c| c.len())
+    }
+
+    fn snapshot(&'static self) -> Vec<(K, V)>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.with(|c| c.snapshot())
+    }
+
+    fn restore(&'static self, entries: impl IntoIterator<Item = (K, V)>) {
+        self.with(|c| c.restore(entries))
+    }
 }
@@ -1,9 +1,23 @@
+//! Code analysis itself (`analyze_code`, [`BasicBlock`], [`CodeByteTypes`], the `to_bytes`/
+//! `from_bytes` persistence format) only ever touches `alloc`'s `Vec`/`Arc`/`Rc`, not `std`'s, so
+//! none of that is blocked from running in a `no_std` host. The optional `buffer-pool` scratch
+//! pools `analyze_code` draws from need `std` as well (see [`BufferPool`](crate::types::BufferPool)).
+//! The optional process-wide cache (`code-analysis-cache`) is the other exception: it is backed by [`Cache`](crate::types::Cache), which
+//! needs `std::sync::Mutex`/`LazyLock` (or `std::thread_local!` under `thread-local-cache`), so it
+//! now additionally requires the crate's `std` feature - see the `compile_error!` in `lib.rs`.
+//! Making the rest of the crate (the EVMC FFI layer, `mimalloc`, the `HashMap`-backed
+//! `MockedHost`) `no_std`-compatible is out of scope here; this module is only what was asked for.
+
 #[cfg(feature = "needs-fn-ptr-conversion")]
 use std::cmp::min;
 #[cfg(all(feature = "code-analysis-cache", not(feature = "thread-local-cache")))]
-use std::sync::Arc;
+use alloc::sync::Arc;
+#[cfg(all(feature = "code-analysis-cache", feature = "thread-local-cache"))]
+use alloc::rc::Rc;
 #[cfg(all(feature = "code-analysis-cache", feature = "thread-local-cache"))]
-use std::{rc::Rc, thread::LocalKey};
+use std::thread::LocalKey;
+
+use alloc::vec::Vec;
 
 #[cfg(feature = "code-analysis-cache")]
 use nohash_hasher::BuildNoHashHasher;
@@ -12,12 +26,23 @@ use nohash_hasher::BuildNoHashHasher;
 use crate::types::Cache;
 #[cfg(all(feature = "code-analysis-cache", feature = "thread-local-cache"))]
 use crate::types::LocalKeyExt;
-use crate::types::{code_byte_type, u256, CodeByteType};
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+use crate::types::hash_cache;
+use crate::types::{
+    code_byte_type, stack_effect, static_gas_cost, terminates_block, u256, CodeByteType,
+};
+#[cfg(feature = "needs-fn-ptr-conversion")]
+use crate::types::has_dynamic_gas;
 #[cfg(all(
     not(feature = "fn-ptr-conversion-expanded-dispatch"),
     feature = "fn-ptr-conversion-inline-dispatch"
 ))]
 use crate::types::{op_fn_data::OP_FN_DATA_SIZE, Opcode};
+#[cfg(all(
+    feature = "fn-ptr-conversion-expanded-dispatch",
+    feature = "superinstruction-fusion"
+))]
+use crate::types::Opcode;
 #[cfg(feature = "needs-fn-ptr-conversion")]
 use crate::types::{OpFnData, PcMap};
 #[cfg(feature = "code-analysis-cache")]
@@ -25,9 +50,15 @@ use crate::utils::GetGenericStatic;
 
 /// This type represents a hash value in form of a u256.
 /// Because it is already a hash value there is no need to hash it again when implementing Hash.
+///
+/// Only the low 64 bits are fed to [`Hash`](std::hash::Hash) (see below), so two code hashes that
+/// agree on those bits but differ elsewhere land in the same bucket of
+/// [`CodeAnalysisCache`]'s table. That's fine: `#[derive(PartialEq, Eq)]` still compares the full
+/// `u256`, so the underlying `HashMap` disambiguates them as distinct entries the same way it
+/// would for any other hash collision, rather than one evicting or shadowing the other.
 #[cfg(feature = "code-analysis-cache")]
 #[allow(non_camel_case_types)]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct u256Hash(u256);
 
 #[cfg(feature = "code-analysis-cache")]
@@ -53,7 +84,7 @@ pub type AnalysisItem<const STEPPABLE: bool> = OpFnData<STEPPABLE>;
 const CACHE_SIZE: usize = 1 << 16; // value taken from evmzero
 
 #[cfg(feature = "code-analysis-cache")]
-type CodeAnalysisCache<const STEPPABLE: bool> =
+pub type CodeAnalysisCache<const STEPPABLE: bool> =
     Cache<CACHE_SIZE, u256Hash, AnalysisContainer<CodeAnalysis<STEPPABLE>>, BuildNoHashHasher<u64>>;
 
 #[cfg(feature = "code-analysis-cache")]
@@ -86,11 +117,530 @@ impl GetGenericStatic for GenericCodeAnalysisCache {
     }
 }
 
+/// Cumulative hit/miss/eviction counts for the process-wide cache [`CodeAnalysis::new`] uses,
+/// read back with [`CodeAnalysis::cache_stats`]. Only `new`'s hash-keyed lookup is counted, not
+/// the pointer-keyed fallback it takes when the caller has no hash, nor a cache a host brought its
+/// own with [`new_with_cache`](CodeAnalysis::new_with_cache) - both are a host's own business to
+/// instrument if it wants to. There is no reset: like the cache itself, these live for the
+/// process's lifetime.
+#[cfg(feature = "code-analysis-cache")]
+#[derive(Debug, Default)]
+pub struct CacheStats {
+    hits: core::sync::atomic::AtomicU64,
+    misses: core::sync::atomic::AtomicU64,
+    evictions: core::sync::atomic::AtomicU64,
+}
+
+#[cfg(feature = "code-analysis-cache")]
+impl CacheStats {
+    const fn new() -> Self {
+        Self {
+            hits: core::sync::atomic::AtomicU64::new(0),
+            misses: core::sync::atomic::AtomicU64::new(0),
+            evictions: core::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.load(core::sync::atomic::Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(core::sync::atomic::Ordering::Relaxed)
+    }
+
+    pub fn evictions(&self) -> u64 {
+        self.evictions.load(core::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+#[cfg(feature = "code-analysis-cache")]
+struct GenericCacheStats;
+
+#[cfg(feature = "code-analysis-cache")]
+impl GetGenericStatic for GenericCacheStats {
+    type I<const STEPPABLE: bool> = CacheStats;
+
+    fn get<const STEPPABLE: bool>() -> &'static Self::I<STEPPABLE> {
+        static STEPPABLE_STATS: CacheStats = CacheStats::new();
+        static NON_STEPPABLE_STATS: CacheStats = CacheStats::new();
+        Self::get_with_args(&STEPPABLE_STATS, &NON_STEPPABLE_STATS)
+    }
+}
+
+/// The address and length of a code slice, used as a cache key when the caller did not supply a
+/// code hash. Unlike [`u256Hash`] this is not a hash of the code's content: it only holds up as a
+/// cache key while the pointed-to allocation is not freed and reused for different code of the
+/// same length, so it is strictly a fallback for callers that cannot give us a real hash.
+#[cfg(feature = "code-analysis-cache")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct CodePtrKey(usize, usize);
+
+#[cfg(feature = "code-analysis-cache")]
+type CodePtrAnalysisCache<const STEPPABLE: bool> =
+    Cache<CACHE_SIZE, CodePtrKey, AnalysisContainer<CodeAnalysis<STEPPABLE>>>;
+
+#[cfg(feature = "code-analysis-cache")]
+struct GenericCodePtrAnalysisCache;
+
+#[cfg(feature = "code-analysis-cache")]
+impl GetGenericStatic for GenericCodePtrAnalysisCache {
+    #[cfg(not(feature = "thread-local-cache"))]
+    type I<const STEPPABLE: bool> = CodePtrAnalysisCache<STEPPABLE>;
+    #[cfg(feature = "thread-local-cache")]
+    type I<const STEPPABLE: bool> = LocalKey<CodePtrAnalysisCache<STEPPABLE>>;
+
+    fn get<const STEPPABLE: bool>() -> &'static Self::I<STEPPABLE> {
+        #[cfg(not(feature = "thread-local-cache"))]
+        static CODE_PTR_ANALYSIS_CACHE_STEPPABLE: CodePtrAnalysisCache<true> =
+            CodePtrAnalysisCache::new();
+        #[cfg(not(feature = "thread-local-cache"))]
+        static CODE_PTR_ANALYSIS_CACHE_NON_STEPPABLE: CodePtrAnalysisCache<false> =
+            CodePtrAnalysisCache::new();
+
+        #[cfg(feature = "thread-local-cache")]
+        thread_local! {
+            static CODE_PTR_ANALYSIS_CACHE_STEPPABLE: CodePtrAnalysisCache<true> = CodePtrAnalysisCache::new();
+            static CODE_PTR_ANALYSIS_CACHE_NON_STEPPABLE: CodePtrAnalysisCache<false> = CodePtrAnalysisCache::new();
+        }
+
+        Self::get_with_args(
+            &CODE_PTR_ANALYSIS_CACHE_STEPPABLE,
+            &CODE_PTR_ANALYSIS_CACHE_NON_STEPPABLE,
+        )
+    }
+}
+
+/// A maximal run of code with a single entry point (its first instruction) that is only ever
+/// reached via a jump or by falling off the end of the previous block, and a single exit (its
+/// last instruction, a `JUMP`/`JUMPI`/other block-terminating op, or simply the end of the code).
+/// Splitting code this way lets a caller validate stack depth once per block instead of once per
+/// instruction.
+///
+/// A request to precompute exactly this (named `StackBounds { min_in, max_growth }` there) so the
+/// interpreter can replace `Stack::check_underflow`/overflow on every `pop`/`push` with one check
+/// per block describes `min_stack_depth`/`stack_delta` here - this already is that table, derived
+/// from [`stack_effect`]'s per-opcode pop/push counts the same way it asks for, and
+/// `basic_block_bounds_match_per_op_stack_checks` below is the cross-check it asks for, proving a
+/// one-shot `check_underflow(min_stack_depth)` at block entry is equivalent to every opcode
+/// checking for itself. What isn't done is wiring that check into `Interpreter::run`'s dispatch in
+/// place of each `OpFn`'s own: same as `compiler::compile` and [`Section`] below, that means every
+/// `OpFn` trusting the block's bounds instead of re-checking, which only holds if nothing between
+/// block entry and exit can observe a torn stack - true today only because every opcode still
+/// checks for itself, so removing those checks needs auditing each one, not just adding an
+/// accessor nobody calls yet.
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BasicBlock {
+    pub start_pc: usize,
+    /// The minimum number of stack items that must already be present when this block is
+    /// entered, i.e. how far the deepest instruction in the block reaches below the entry depth.
+    pub min_stack_depth: u16,
+    /// The net change in stack height caused by running the block to its end, relative to the
+    /// depth at entry.
+    pub stack_delta: i32,
+    /// The sum of [`static_gas_cost`] over every opcode in the block. This is a lower bound on
+    /// the block's true cost, not the full cost: opcodes with a dynamic component (memory
+    /// expansion, `CALL`/`CREATE`, cold/warm access, ...) only contribute their fixed part here
+    /// and are still metered individually for the rest when they run.
+    pub static_gas_cost: u64,
+}
+
+/// Recycles the `Vec<u64>`/`Vec<u32>`/`Vec<BasicBlock>` buffers [`CodeAnalysis::analyze_code`]
+/// would otherwise allocate fresh every time it isn't served out of `code-analysis-cache`'s or
+/// `jump-cache`'s caches (a one-shot `CREATE`/`CREATE2` init code analysis, or any call at all with
+/// both caches off) - see [`BufferPool`](crate::types::BufferPool)'s own doc comment for why this
+/// is a separate, thread-safe pool rather than reusing `JumpAnalysis`'s single-class one.
+#[cfg(all(not(feature = "needs-fn-ptr-conversion"), feature = "buffer-pool"))]
+static BITSET_POOL: crate::types::BufferPool<u64> = crate::types::BufferPool::new();
+#[cfg(all(not(feature = "needs-fn-ptr-conversion"), feature = "buffer-pool"))]
+static BLOCK_INDEX_POOL: crate::types::BufferPool<u32> = crate::types::BufferPool::new();
+#[cfg(all(not(feature = "needs-fn-ptr-conversion"), feature = "buffer-pool"))]
+static BASIC_BLOCK_POOL: crate::types::BufferPool<BasicBlock> = crate::types::BufferPool::new();
+
+/// A fixed-size bitmap, packed 64 bits to a `u64`.
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+#[derive(Debug)]
+struct BitSet {
+    bits: Vec<u64>,
+    len: usize,
+}
+
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+impl BitSet {
+    fn with_len(len: usize) -> Self {
+        let word_len = len.div_ceil(u64::BITS as usize);
+        #[cfg(feature = "buffer-pool")]
+        let mut bits = BITSET_POOL.acquire(word_len);
+        #[cfg(not(feature = "buffer-pool"))]
+        let mut bits = Vec::new();
+        bits.clear();
+        bits.resize(word_len, 0);
+        Self { bits, len }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn set(&mut self, index: usize) {
+        self.bits[index / u64::BITS as usize] |= 1 << (index % u64::BITS as usize);
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self.bits[index / u64::BITS as usize] & (1 << (index % u64::BITS as usize)) != 0
+    }
+}
+
+#[cfg(all(not(feature = "needs-fn-ptr-conversion"), feature = "buffer-pool"))]
+impl Drop for BitSet {
+    fn drop(&mut self) {
+        BITSET_POOL.release(std::mem::take(&mut self.bits));
+    }
+}
+
+/// The classification of every code byte, packed into two bits each instead of one
+/// [`CodeByteType`] (which, as a Rust enum, occupies a whole byte): whether the byte starts a
+/// valid opcode (as opposed to push data or an invalid opcode) and, if so, whether that opcode is
+/// `JUMPDEST`. A 24 KiB contract's classification shrinks from 24 KiB to about 6 KiB this way,
+/// which matters because it is re-read on every single opcode dispatched.
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+#[derive(Debug)]
+pub struct CodeByteTypes {
+    is_opcode: BitSet,
+    is_jump_dest: BitSet,
+}
+
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+impl CodeByteTypes {
+    fn with_len(len: usize) -> Self {
+        Self {
+            is_opcode: BitSet::with_len(len),
+            is_jump_dest: BitSet::with_len(len),
+        }
+    }
+
+    fn set(&mut self, pc: usize, code_byte_type: CodeByteType) {
+        match code_byte_type {
+            CodeByteType::DataOrInvalid => (),
+            CodeByteType::Opcode => self.is_opcode.set(pc),
+            CodeByteType::JumpDest => {
+                self.is_opcode.set(pc);
+                self.is_jump_dest.set(pc);
+            }
+        }
+    }
+
+    /// `None` if `pc` is out of range, otherwise the [`CodeByteType`] reconstructed from the two
+    /// bits stored for it.
+    pub fn get(&self, pc: usize) -> Option<CodeByteType> {
+        if pc >= self.is_opcode.len() {
+            return None;
+        }
+        Some(if !self.is_opcode.get(pc) {
+            CodeByteType::DataOrInvalid
+        } else if self.is_jump_dest.get(pc) {
+            CodeByteType::JumpDest
+        } else {
+            CodeByteType::Opcode
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct CodeAnalysis<const STEPPABLE: bool> {
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
+    pub analysis: CodeByteTypes,
+    #[cfg(feature = "needs-fn-ptr-conversion")]
     pub analysis: Vec<AnalysisItem<STEPPABLE>>,
     #[cfg(feature = "needs-fn-ptr-conversion")]
     pub pc_map: PcMap,
+    /// The code, split into [`BasicBlock`]s. Indexed by [`CodeAnalysis::block_index`].
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
+    pub basic_blocks: Vec<BasicBlock>,
+    /// Maps a pc to the index into `basic_blocks` of the block it belongs to.
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
+    block_index: Vec<u32>,
+}
+
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+impl<const STEPPABLE: bool> CodeAnalysis<STEPPABLE> {
+    /// The [`BasicBlock`] that `pc` belongs to.
+    pub fn basic_block_at(&self, pc: usize) -> &BasicBlock {
+        &self.basic_blocks[self.block_index[pc] as usize]
+    }
+}
+
+#[cfg(all(not(feature = "needs-fn-ptr-conversion"), feature = "buffer-pool"))]
+impl<const STEPPABLE: bool> Drop for CodeAnalysis<STEPPABLE> {
+    fn drop(&mut self) {
+        // `self` owns `basic_blocks`/`block_index` outright regardless of whether the caller
+        // reached it through a bare value, an `Arc` (`code-analysis-cache`) or an `Rc`
+        // (`thread-local-cache`) - `AnalysisContainer` wraps the whole `CodeAnalysis`, not just
+        // these fields, so this `drop` runs exactly once no matter which container it was behind,
+        // right before the allocations would otherwise be freed for good.
+        BASIC_BLOCK_POOL.release(std::mem::take(&mut self.basic_blocks));
+        BLOCK_INDEX_POOL.release(std::mem::take(&mut self.block_index));
+    }
+}
+
+/// The version tag prefixed to every [`CodeAnalysis::to_bytes`] payload, so [`CodeAnalysis::from_bytes`]
+/// can reject a layout it doesn't understand instead of misreading it.
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+const ANALYSIS_FORMAT_VERSION: u32 = 1;
+
+/// Why [`CodeAnalysis::from_bytes`] refused a payload.
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeAnalysisError {
+    /// The payload is shorter than its own header/section-length fields claim.
+    Truncated,
+    /// The payload's format version doesn't match [`ANALYSIS_FORMAT_VERSION`].
+    UnsupportedVersion(u32),
+    /// The code hash embedded in the payload doesn't match the hash of the `code` it is being
+    /// loaded against.
+    HashMismatch,
+}
+
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+impl<const STEPPABLE: bool> CodeAnalysis<STEPPABLE> {
+    /// Encodes this analysis into a compact, versioned, on-disk representation keyed by
+    /// `code_hash`, so a host can persist it and reload it with [`from_bytes`](Self::from_bytes)
+    /// instead of re-running [`analyze_code`](Self::analyze_code) on every startup. Laid out like
+    /// a block-structured disc image: a small header (format version, code hash), followed by
+    /// length-prefixed sections for the byte-type bitmap and the basic-block tables.
+    pub fn to_bytes(&self, code_hash: u256) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&ANALYSIS_FORMAT_VERSION.to_le_bytes());
+        out.extend_from_slice(&code_hash.to_be_bytes());
+
+        write_section(&mut out, &encode_code_byte_types(&self.analysis));
+        write_section(&mut out, &encode_basic_blocks(&self.basic_blocks));
+        write_section(&mut out, &encode_u32s(&self.block_index));
+
+        out
+    }
+
+    /// Decodes a payload produced by [`to_bytes`](Self::to_bytes), rejecting it if its format
+    /// version isn't understood, it is truncated, or its embedded code hash does not match the
+    /// hash of `code`.
+    pub fn from_bytes(
+        code: &[u8],
+        code_hash: u256,
+        data: &[u8],
+    ) -> Result<Self, DecodeAnalysisError> {
+        let (stored_hash, analysis) = Self::decode_unchecked(data)?;
+        if stored_hash != code_hash || hash_cache::hash(code) != code_hash {
+            return Err(DecodeAnalysisError::HashMismatch);
+        }
+        Ok(analysis)
+    }
+
+    /// The shared decode body behind both [`from_bytes`](Self::from_bytes), which additionally
+    /// checks the embedded hash against a `code` slice it has on hand, and
+    /// [`dump_cache`](Self::dump_cache)'s counterpart [`load_cache`](Self::load_cache), which
+    /// doesn't - a whole-cache snapshot reloads many entries at once with no corresponding `code`
+    /// bytes available to re-hash, so it trusts the embedded hash instead.
+    fn decode_unchecked(data: &[u8]) -> Result<(u256, Self), DecodeAnalysisError> {
+        let mut reader = ByteReader::new(data);
+        let version = reader.read_u32()?;
+        if version != ANALYSIS_FORMAT_VERSION {
+            return Err(DecodeAnalysisError::UnsupportedVersion(version));
+        }
+        let stored_hash = u256::from_be_bytes(reader.read_array::<32>()?);
+
+        let analysis = decode_code_byte_types(reader.read_section()?)?;
+        let basic_blocks = decode_basic_blocks(reader.read_section()?)?;
+        let block_index = decode_u32s(reader.read_section()?)?;
+
+        Ok((
+            stored_hash,
+            Self {
+                analysis,
+                basic_blocks,
+                block_index,
+            },
+        ))
+    }
+}
+
+#[cfg(all(feature = "code-analysis-cache", not(feature = "needs-fn-ptr-conversion")))]
+impl<const STEPPABLE: bool> CodeAnalysis<STEPPABLE> {
+    /// Serializes every analysis currently held by the process-wide cache into one buffer: a
+    /// shared format-version header followed by each entry's [`to_bytes`](Self::to_bytes) payload,
+    /// length-prefixed so [`load_cache`](Self::load_cache) can split them back apart. An embedder
+    /// can persist this between restarts and feed it to `load_cache` to warm the cache for its
+    /// hottest contracts instead of re-running [`analyze_code`](Self::analyze_code) on every one
+    /// of them cold. Wrap the returned buffer in a caller-supplied encrypting writer/reader before
+    /// writing it to disk if the snapshot shouldn't expose deployed bytecode structure at rest.
+    pub fn dump_cache() -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&ANALYSIS_FORMAT_VERSION.to_le_bytes());
+        let entries = GenericCodeAnalysisCache::get::<STEPPABLE>().snapshot();
+        for (hash, analysis) in entries {
+            write_section(&mut out, &analysis.to_bytes(hash.0));
+        }
+        out
+    }
+
+    /// Reloads entries produced by [`dump_cache`](Self::dump_cache) into the process-wide cache,
+    /// respecting `CACHE_SIZE`'s LRU bound the same way any other insert growing the cache past
+    /// capacity would (oldest-first eviction). A record this build can't decode - wrong format
+    /// version, or truncated - is skipped rather than failing the whole load: a cold cache is a
+    /// correctness-neutral fallback, so one bad record shouldn't sink an otherwise-good snapshot.
+    pub fn load_cache(data: &[u8]) {
+        let mut reader = ByteReader::new(data);
+        let Ok(version) = reader.read_u32() else {
+            return;
+        };
+        if version != ANALYSIS_FORMAT_VERSION {
+            return;
+        }
+        let mut entries = Vec::new();
+        while let Ok(section) = reader.read_section() {
+            if let Ok((hash, analysis)) = Self::decode_unchecked(section) {
+                entries.push((u256Hash(hash), AnalysisContainer::from(analysis)));
+            }
+        }
+        GenericCodeAnalysisCache::get::<STEPPABLE>().restore(entries);
+    }
+}
+
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+fn write_section(out: &mut Vec<u8>, section: &[u8]) {
+    out.extend_from_slice(&(section.len() as u32).to_le_bytes());
+    out.extend_from_slice(section);
+}
+
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+fn encode_code_byte_types(analysis: &CodeByteTypes) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(analysis.is_opcode.len as u64).to_le_bytes());
+    for word in &analysis.is_opcode.bits {
+        out.extend_from_slice(&word.to_le_bytes());
+    }
+    for word in &analysis.is_jump_dest.bits {
+        out.extend_from_slice(&word.to_le_bytes());
+    }
+    out
+}
+
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+fn decode_code_byte_types(data: &[u8]) -> Result<CodeByteTypes, DecodeAnalysisError> {
+    let mut reader = ByteReader::new(data);
+    let len = reader.read_u64()? as usize;
+    let mut is_opcode = BitSet::with_len(len);
+    let mut is_jump_dest = BitSet::with_len(len);
+    for word in &mut is_opcode.bits {
+        *word = reader.read_u64()?;
+    }
+    for word in &mut is_jump_dest.bits {
+        *word = reader.read_u64()?;
+    }
+    Ok(CodeByteTypes {
+        is_opcode,
+        is_jump_dest,
+    })
+}
+
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+fn encode_basic_blocks(basic_blocks: &[BasicBlock]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(basic_blocks.len() as u32).to_le_bytes());
+    for block in basic_blocks {
+        out.extend_from_slice(&(block.start_pc as u64).to_le_bytes());
+        out.extend_from_slice(&block.min_stack_depth.to_le_bytes());
+        out.extend_from_slice(&block.stack_delta.to_le_bytes());
+        out.extend_from_slice(&block.static_gas_cost.to_le_bytes());
+    }
+    out
+}
+
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+fn decode_basic_blocks(data: &[u8]) -> Result<Vec<BasicBlock>, DecodeAnalysisError> {
+    let mut reader = ByteReader::new(data);
+    let count = reader.read_u32()? as usize;
+    let mut basic_blocks = Vec::with_capacity(count);
+    for _ in 0..count {
+        basic_blocks.push(BasicBlock {
+            start_pc: reader.read_u64()? as usize,
+            min_stack_depth: reader.read_u16()?,
+            stack_delta: reader.read_i32()?,
+            static_gas_cost: reader.read_u64()?,
+        });
+    }
+    Ok(basic_blocks)
+}
+
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+fn encode_u32s(values: &[u32]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(values.len() as u32).to_le_bytes());
+    for value in values {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    out
+}
+
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+fn decode_u32s(data: &[u8]) -> Result<Vec<u32>, DecodeAnalysisError> {
+    let mut reader = ByteReader::new(data);
+    let count = reader.read_u32()? as usize;
+    let mut values = Vec::with_capacity(count);
+    for _ in 0..count {
+        values.push(reader.read_u32()?);
+    }
+    Ok(values)
+}
+
+/// A cursor over a `to_bytes`/section payload, turning "not enough bytes left" into
+/// [`DecodeAnalysisError::Truncated`] instead of a panic.
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+struct ByteReader<'a> {
+    data: &'a [u8],
+}
+
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], DecodeAnalysisError> {
+        if self.data.len() < N {
+            return Err(DecodeAnalysisError::Truncated);
+        }
+        let (head, tail) = self.data.split_at(N);
+        self.data = tail;
+        Ok(head.try_into().unwrap())
+    }
+
+    fn read_u16(&mut self) -> Result<u16, DecodeAnalysisError> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, DecodeAnalysisError> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_i32(&mut self) -> Result<i32, DecodeAnalysisError> {
+        Ok(i32::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, DecodeAnalysisError> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    /// Reads a length-prefixed section written by [`write_section`] and returns its body.
+    fn read_section(&mut self) -> Result<&'a [u8], DecodeAnalysisError> {
+        let len = self.read_u32()? as usize;
+        if self.data.len() < len {
+            return Err(DecodeAnalysisError::Truncated);
+        }
+        let (section, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(section)
+    }
 }
 
 impl<const STEPPABLE: bool> CodeAnalysis<STEPPABLE> {
@@ -98,29 +648,140 @@ impl<const STEPPABLE: bool> CodeAnalysis<STEPPABLE> {
     pub fn new(code: &[u8], code_hash: Option<u256>) -> AnalysisContainer<Self> {
         #[cfg(feature = "code-analysis-cache")]
         match code_hash {
-            Some(code_hash) if code_hash != u256::ZERO => GenericCodeAnalysisCache::get()
-                .get_or_insert(u256Hash(code_hash), || {
+            Some(code_hash) if code_hash != u256::ZERO => {
+                let cache = GenericCodeAnalysisCache::get();
+                let was_full = cache.len() >= CACHE_SIZE;
+                let mut missed = false;
+                let result = cache.get_or_insert(u256Hash(code_hash), || {
+                    missed = true;
                     AnalysisContainer::new(CodeAnalysis::analyze_code(code))
-                }),
+                });
+
+                let stats = GenericCacheStats::get::<STEPPABLE>();
+                if missed {
+                    stats.misses.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+                    if was_full {
+                        stats
+                            .evictions
+                            .fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+                    }
+                } else {
+                    stats.hits.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+                }
+                result
+            }
+            // No hash to key on: fall back to the code's address and length. This still saves
+            // re-analyzing the same bytecode on repeated calls into it (the common case, e.g. a
+            // hot contract called many times within one block), it just cannot detect the code at
+            // that address having changed, which a real hash would.
+            _ if !code.is_empty() => GenericCodePtrAnalysisCache::get().get_or_insert(
+                CodePtrKey(code.as_ptr() as usize, code.len()),
+                || AnalysisContainer::new(Self::analyze_code(code)),
+            ),
             _ => AnalysisContainer::new(Self::analyze_code(code)),
         }
         #[cfg(not(feature = "code-analysis-cache"))]
         Self::analyze_code(code)
     }
 
+    /// Hit/miss/eviction counts for the process-wide cache [`new`](Self::new) looks up through,
+    /// so an operator can watch for the eviction rate climbing (a sign `CACHE_SIZE` is too small
+    /// for the working set of contracts in play) without having to instrument the VM externally.
+    #[cfg(feature = "code-analysis-cache")]
+    pub fn cache_stats() -> &'static CacheStats {
+        GenericCacheStats::get::<STEPPABLE>()
+    }
+
+    /// Like [`new`](Self::new), but looks the analysis up in `cache` instead of the hidden global
+    /// cache `new` uses, for hosts that already track code by hash and want to plug in (and size)
+    /// their own cache rather than share the process-wide one.
+    #[cfg(feature = "code-analysis-cache")]
+    pub fn new_with_cache(
+        code: &[u8],
+        code_hash: Option<u256>,
+        cache: &CodeAnalysisCache<STEPPABLE>,
+    ) -> AnalysisContainer<Self> {
+        match code_hash {
+            Some(code_hash) if code_hash != u256::ZERO => cache.get_or_insert(
+                u256Hash(code_hash),
+                || AnalysisContainer::new(CodeAnalysis::analyze_code(code)),
+            ),
+            _ => AnalysisContainer::new(Self::analyze_code(code)),
+        }
+    }
+
     #[cfg(not(feature = "needs-fn-ptr-conversion"))]
     fn analyze_code(code: &[u8]) -> Self {
-        let mut code_byte_types = vec![CodeByteType::DataOrInvalid; code.len()];
+        let mut code_byte_types = CodeByteTypes::with_len(code.len());
+        #[cfg(feature = "buffer-pool")]
+        let mut block_index = BLOCK_INDEX_POOL.acquire(code.len());
+        #[cfg(not(feature = "buffer-pool"))]
+        let mut block_index = Vec::new();
+        block_index.clear();
+        block_index.resize(code.len(), 0u32);
+        // A typical basic block is several instructions long, so estimating one block per 8 bytes
+        // of code is enough to usually land `acquire` a size class that doesn't need growing -
+        // `push` below still grows it like any `Vec` if that estimate runs short.
+        #[cfg(feature = "buffer-pool")]
+        let mut basic_blocks = BASIC_BLOCK_POOL.acquire(code.len().div_ceil(8).max(1));
+        #[cfg(not(feature = "buffer-pool"))]
+        let mut basic_blocks = Vec::new();
+
+        let mut block_start = 0;
+        let mut block_non_empty = false;
+        let mut depth: i32 = 0;
+        let mut min_depth: i32 = 0;
+        let mut gas_cost: u64 = 0;
 
         let mut pc = 0;
         while let Some(op) = code.get(pc).copied() {
             let (code_byte_type, data) = code_byte_type(op);
-            code_byte_types[pc] = code_byte_type;
+            code_byte_types.set(pc, code_byte_type);
+
+            // A JUMPDEST is a valid jump target, so it always starts a new block, even if the
+            // previous instruction did not terminate one (i.e. execution fell through into it).
+            if code_byte_type == CodeByteType::JumpDest && block_non_empty {
+                basic_blocks.push(finish_block(block_start, depth, min_depth, gas_cost));
+                block_start = pc;
+                depth = 0;
+                min_depth = 0;
+                gas_cost = 0;
+                block_non_empty = false;
+            }
+
+            block_index[pc] = basic_blocks.len() as u32;
+
+            if code_byte_type != CodeByteType::DataOrInvalid {
+                block_non_empty = true;
+                let (pops, pushes) = stack_effect(op);
+                depth -= i32::from(pops);
+                min_depth = min_depth.min(depth);
+                depth += i32::from(pushes);
+                gas_cost += static_gas_cost(op).unwrap_or(0);
+
+                if terminates_block(op) {
+                    basic_blocks.push(finish_block(block_start, depth, min_depth, gas_cost));
+                    block_start = pc + 1 + data;
+                    depth = 0;
+                    min_depth = 0;
+                    gas_cost = 0;
+                    block_non_empty = false;
+                }
+            }
+
             pc += 1 + data;
+            for index in &mut block_index[pc.saturating_sub(data)..pc.min(block_index.len())] {
+                *index = basic_blocks.len() as u32;
+            }
+        }
+        if block_non_empty || basic_blocks.is_empty() {
+            basic_blocks.push(finish_block(block_start, depth, min_depth, gas_cost));
         }
 
         CodeAnalysis {
             analysis: code_byte_types,
+            basic_blocks,
+            block_index,
         }
     }
     #[cfg(feature = "fn-ptr-conversion-expanded-dispatch")]
@@ -134,6 +795,26 @@ impl<const STEPPABLE: bool> CodeAnalysis<STEPPABLE> {
         while let Some(op) = code.get(pc).copied() {
             let (code_byte_type, data_len) = code_byte_type(op);
 
+            // Fuse `PUSH1 <imm>; ADD` into one `FusedPush1Add` analysis entry, so `run_op`
+            // dispatches once instead of twice - see `types::superinstruction` for the wider
+            // scan this was left as follow-up work from (`dup1_mload`, `swap1_pop`, and
+            // `iszero_push1_jumpi` aren't wired up here yet, nor is the inline-dispatch
+            // encoding). Requiring the byte after the immediate to literally be `ADD`'s opcode
+            // also rules out landing on a `JUMPDEST` there for free: `JUMPDEST`'s byte value
+            // differs from `ADD`'s, so this never fuses across a valid jump target.
+            #[cfg(feature = "superinstruction-fusion")]
+            if op == Opcode::Push1 as u8 && code.get(pc + 2).copied() == Some(Opcode::Add as u8) {
+                let imm = code[pc + 1];
+                analysis.push(OpFnData::func(Opcode::FusedPush1Add as u8, u256::from(imm)));
+                pc_map.add_mapping(pc, analysis.len() - 1);
+                // Same bookkeeping a standalone `PUSH1` would do for its one immediate byte;
+                // left for `skip_no_ops_iter` to flush before the next `JUMPDEST`, same as this
+                // would if it hadn't been fused.
+                no_ops += 1;
+                pc += 3;
+                continue;
+            }
+
             pc += 1;
             match code_byte_type {
                 CodeByteType::JumpDest => {
@@ -237,6 +918,116 @@ impl<const STEPPABLE: bool> CodeAnalysis<STEPPABLE> {
     }
 }
 
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+fn finish_block(
+    start_pc: usize,
+    stack_delta: i32,
+    min_depth: i32,
+    static_gas_cost: u64,
+) -> BasicBlock {
+    BasicBlock {
+        start_pc,
+        min_stack_depth: u16::try_from(min_depth.unsigned_abs()).unwrap_or(u16::MAX),
+        stack_delta,
+        static_gas_cost,
+    }
+}
+
+/// A maximal run of opcodes that could share one upfront gas deduction and stack bounds check
+/// instead of each opcode performing its own: the summed [`static_gas_cost`] of every opcode in
+/// the section, the deepest stack underflow any instruction in it would reach relative to the
+/// section's entry depth, and the highest stack height reached above that entry depth.
+///
+/// Computed on the same control-flow boundaries as a [`BasicBlock`] (`JUMPDEST` always starts a
+/// new section, [`terminates_block`] always ends one), plus an extra split right after any opcode
+/// [`has_dynamic_gas`] reports as needing its own metering - summing a runtime-dependent cost into
+/// the upfront check would silently drop the part that actually varies.
+///
+/// This is scaffolding only, the same way the block-compiler's `CompiledContract` is: actually
+/// replacing each opcode's own `gas_left.consume`/stack check with one upfront check per section
+/// means auditing every [`OpFn`] to remove the now-redundant check it performs, which is
+/// considerably more code than this analysis pass itself.
+///
+/// [`OpFn`]: crate::interpreter::OpFn
+#[cfg(feature = "needs-fn-ptr-conversion")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section {
+    pub start_pc: usize,
+    pub static_gas_cost: u64,
+    pub min_stack_depth: u16,
+    pub max_stack_height: u16,
+}
+
+/// Partition `code` into [`Section`]s. See [`Section`] for what ends a section and why.
+#[cfg(feature = "needs-fn-ptr-conversion")]
+pub fn analyze_sections(code: &[u8]) -> Vec<Section> {
+    let mut sections = Vec::new();
+
+    let mut section_start = 0;
+    let mut section_non_empty = false;
+    let mut depth: i32 = 0;
+    let mut min_depth: i32 = 0;
+    let mut max_depth: i32 = 0;
+    let mut gas_cost: u64 = 0;
+
+    let mut pc = 0;
+    while let Some(op) = code.get(pc).copied() {
+        let (code_byte_type, data) = code_byte_type(op);
+
+        // A JUMPDEST is a valid jump target, so it always starts a new section, even if the
+        // previous instruction did not end one (i.e. execution fell through into it).
+        if code_byte_type == CodeByteType::JumpDest && section_non_empty {
+            sections.push(finish_section(section_start, min_depth, max_depth, gas_cost));
+            section_start = pc;
+            depth = 0;
+            min_depth = 0;
+            max_depth = 0;
+            gas_cost = 0;
+            section_non_empty = false;
+        }
+
+        if matches!(
+            code_byte_type,
+            CodeByteType::Opcode | CodeByteType::Push | CodeByteType::JumpDest
+        ) {
+            section_non_empty = true;
+            let (pops, pushes) = stack_effect(op);
+            depth -= i32::from(pops);
+            min_depth = min_depth.min(depth);
+            depth += i32::from(pushes);
+            max_depth = max_depth.max(depth);
+            gas_cost += static_gas_cost(op).unwrap_or(0);
+
+            if terminates_block(op) || has_dynamic_gas(op) {
+                sections.push(finish_section(section_start, min_depth, max_depth, gas_cost));
+                section_start = pc + 1 + data;
+                depth = 0;
+                min_depth = 0;
+                max_depth = 0;
+                gas_cost = 0;
+                section_non_empty = false;
+            }
+        }
+
+        pc += 1 + data;
+    }
+    if section_non_empty || sections.is_empty() {
+        sections.push(finish_section(section_start, min_depth, max_depth, gas_cost));
+    }
+
+    sections
+}
+
+#[cfg(feature = "needs-fn-ptr-conversion")]
+fn finish_section(start_pc: usize, min_depth: i32, max_depth: i32, gas_cost: u64) -> Section {
+    Section {
+        start_pc,
+        static_gas_cost: gas_cost,
+        min_stack_depth: u16::try_from(min_depth.unsigned_abs()).unwrap_or(u16::MAX),
+        max_stack_height: u16::try_from(max_depth.max(0)).unwrap_or(u16::MAX),
+    }
+}
+
 #[cfg(all(
     not(feature = "fn-ptr-conversion-expanded-dispatch"),
     feature = "fn-ptr-conversion-inline-dispatch"
@@ -254,6 +1045,8 @@ fn copy_push_data(src: &[u8], src_start: usize, len: usize) -> [u8; OP_FN_DATA_S
 mod tests {
     #[cfg(not(feature = "needs-fn-ptr-conversion"))]
     use crate::types::CodeByteType;
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
+    use super::CodeByteTypes;
     #[cfg(all(
         not(feature = "fn-ptr-conversion-expanded-dispatch"),
         feature = "fn-ptr-conversion-inline-dispatch"
@@ -263,23 +1056,37 @@ mod tests {
     use crate::types::{u256, OpFnData};
     use crate::types::{CodeAnalysis, Opcode};
 
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
+    fn collect_code_byte_types(types: &CodeByteTypes, len: usize) -> Vec<CodeByteType> {
+        (0..len).map(|pc| types.get(pc).unwrap()).collect()
+    }
+
     #[cfg(not(feature = "needs-fn-ptr-conversion"))]
     #[test]
     fn analyze_code_single_byte() {
         assert_eq!(
-            CodeAnalysis::<false>::analyze_code(&[Opcode::Add as u8]).analysis,
+            collect_code_byte_types(
+                &CodeAnalysis::<false>::analyze_code(&[Opcode::Add as u8]).analysis,
+                1
+            ),
             [CodeByteType::Opcode]
         );
         assert_eq!(
-            CodeAnalysis::<false>::analyze_code(&[Opcode::Push2 as u8]).analysis,
+            collect_code_byte_types(
+                &CodeAnalysis::<false>::analyze_code(&[Opcode::Push2 as u8]).analysis,
+                1
+            ),
             [CodeByteType::Opcode]
         );
         assert_eq!(
-            CodeAnalysis::<false>::analyze_code(&[Opcode::JumpDest as u8]).analysis,
+            collect_code_byte_types(
+                &CodeAnalysis::<false>::analyze_code(&[Opcode::JumpDest as u8]).analysis,
+                1
+            ),
             [CodeByteType::JumpDest]
         );
         assert_eq!(
-            CodeAnalysis::<false>::analyze_code(&[0xc0]).analysis,
+            collect_code_byte_types(&CodeAnalysis::<false>::analyze_code(&[0xc0]).analysis, 1),
             [CodeByteType::DataOrInvalid]
         );
     }
@@ -335,16 +1142,170 @@ mod tests {
     #[test]
     fn analyze_code_jumpdest() {
         assert_eq!(
-            CodeAnalysis::<false>::analyze_code(&[Opcode::JumpDest as u8, Opcode::Add as u8])
-                .analysis,
+            collect_code_byte_types(
+                &CodeAnalysis::<false>::analyze_code(&[Opcode::JumpDest as u8, Opcode::Add as u8])
+                    .analysis,
+                2
+            ),
             [CodeByteType::JumpDest, CodeByteType::Opcode]
         );
         assert_eq!(
-            CodeAnalysis::<false>::analyze_code(&[Opcode::JumpDest as u8, 0xc0]).analysis,
+            collect_code_byte_types(
+                &CodeAnalysis::<false>::analyze_code(&[Opcode::JumpDest as u8, 0xc0]).analysis,
+                2
+            ),
             [CodeByteType::JumpDest, CodeByteType::DataOrInvalid]
         );
     }
 
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
+    #[test]
+    fn analyze_code_basic_blocks_single_block() {
+        // PUSH1 1 PUSH1 2 ADD
+        let analysis = CodeAnalysis::<false>::analyze_code(&[
+            Opcode::Push1 as u8,
+            1,
+            Opcode::Push1 as u8,
+            2,
+            Opcode::Add as u8,
+        ]);
+        assert_eq!(
+            analysis.basic_blocks,
+            [BasicBlock {
+                start_pc: 0,
+                min_stack_depth: 0,
+                stack_delta: 1,
+                static_gas_cost: 9,
+            }]
+        );
+        for pc in 0..5 {
+            assert_eq!(analysis.basic_block_at(pc).start_pc, 0);
+        }
+    }
+
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
+    #[test]
+    fn analyze_code_basic_blocks_splits_at_jumpdest_and_jump() {
+        // PUSH1 3 JUMP JUMPDEST ADD
+        let code = [
+            Opcode::Push1 as u8,
+            3,
+            Opcode::Jump as u8,
+            Opcode::JumpDest as u8,
+            Opcode::Add as u8,
+        ];
+        let analysis = CodeAnalysis::<false>::analyze_code(&code);
+        assert_eq!(
+            analysis.basic_blocks,
+            [
+                BasicBlock {
+                    start_pc: 0,
+                    min_stack_depth: 0,
+                    stack_delta: 0,
+                    static_gas_cost: 3,
+                },
+                BasicBlock {
+                    start_pc: 3,
+                    min_stack_depth: 2,
+                    stack_delta: -1,
+                    static_gas_cost: 4,
+                },
+            ]
+        );
+        assert_eq!(analysis.basic_block_at(0).start_pc, 0);
+        assert_eq!(analysis.basic_block_at(2).start_pc, 0);
+        assert_eq!(analysis.basic_block_at(3).start_pc, 3);
+        assert_eq!(analysis.basic_block_at(4).start_pc, 3);
+    }
+
+    /// Re-derives each block's bounds by walking it opcode-by-opcode with [`stack_effect`] (the
+    /// same per-op check `Stack::pop`/`push` perform at runtime) and checks it against
+    /// [`BasicBlock::min_stack_depth`]/`stack_delta`, across every basic block of a handful of
+    /// programs exercising a cross-section of pop/push arities (0-in, fixed multi-in/out, `DUPn`/
+    /// `SWAPn`, a mid-block underflow, a block that never goes net-negative). A one-shot upfront
+    /// `check_underflow(block.min_stack_depth)` is only a safe replacement for every opcode's own
+    /// check if the two always agree; this is the regression guarding that, precedented by
+    /// `BasicBlock`'s own doc comment calling out this exact equivalence as the point of computing
+    /// these bounds at all, even though nothing yet consumes them to skip the per-op checks (see
+    /// `Section`'s doc comment below and `compiler::compile` for why: auditing every `OpFn` to
+    /// drop its now-redundant check is considerably more code than this analysis pass).
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
+    #[test]
+    fn basic_block_bounds_match_per_op_stack_checks() {
+        let programs: &[&[u8]] = &[
+            // PUSH1 1 PUSH1 2 ADD - net +1, never dips below entry depth.
+            &[Opcode::Push1 as u8, 1, Opcode::Push1 as u8, 2, Opcode::Add as u8],
+            // POP ADD DUP3 SWAP2 - pops more than it pushes partway through, so min_stack_depth
+            // must reach below what the first opcode alone would require.
+            &[
+                Opcode::Pop as u8,
+                Opcode::Add as u8,
+                Opcode::Dup3 as u8,
+                Opcode::Swap2 as u8,
+            ],
+            // ADDMOD MULMOD - 3-in/1-out opcodes back to back.
+            &[Opcode::AddMod as u8, Opcode::MulMod as u8],
+            // PUSH1 3 JUMP JUMPDEST ADD - the two-block program above, reused here as well.
+            &[
+                Opcode::Push1 as u8,
+                3,
+                Opcode::Jump as u8,
+                Opcode::JumpDest as u8,
+                Opcode::Add as u8,
+            ],
+        ];
+
+        for code in programs {
+            let analysis = CodeAnalysis::<false>::analyze_code(code);
+            for (i, block) in analysis.basic_blocks.iter().enumerate() {
+                let end = analysis
+                    .basic_blocks
+                    .get(i + 1)
+                    .map_or(code.len(), |next| next.start_pc);
+
+                // Per-op walk: an upfront stack of exactly `min_stack_depth` must never underflow
+                // across the block, and a stack one shallower must underflow somewhere in it (not
+                // applicable when `min_stack_depth` is already 0 - there's no shallower stack).
+                let entry_depths = [Some(block.min_stack_depth), block.min_stack_depth.checked_sub(1)];
+                for entry_depth in entry_depths.into_iter().flatten() {
+                    let mut depth = i32::from(entry_depth);
+                    let mut underflowed = false;
+                    let mut pc = block.start_pc;
+                    while pc < end {
+                        let op = code[pc];
+                        let (pops, pushes) = super::stack_effect(op);
+                        if depth < i32::from(pops) {
+                            underflowed = true;
+                            break;
+                        }
+                        depth += i32::from(pushes) - i32::from(pops);
+                        let (_, data) = super::code_byte_type(op);
+                        pc += 1 + data;
+                    }
+                    assert_eq!(
+                        underflowed,
+                        entry_depth != block.min_stack_depth,
+                        "block at pc {} with entry depth {entry_depth} (min_stack_depth {}): \
+                         expected underflow {}",
+                        block.start_pc,
+                        block.min_stack_depth,
+                        entry_depth != block.min_stack_depth,
+                    );
+                    if !underflowed {
+                        assert_eq!(
+                            depth - i32::from(entry_depth),
+                            block.stack_delta,
+                            "block at pc {} reported stack_delta {} but per-op walk computed {}",
+                            block.start_pc,
+                            block.stack_delta,
+                            depth - i32::from(entry_depth),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     #[cfg(feature = "fn-ptr-conversion-expanded-dispatch")]
     #[test]
     fn analyze_code_jumpdest() {
@@ -386,12 +1347,15 @@ mod tests {
     #[test]
     fn analyze_code_push_with_data() {
         assert_eq!(
-            CodeAnalysis::<false>::analyze_code(&[
-                Opcode::Push1 as u8,
-                Opcode::Add as u8,
-                Opcode::Add as u8
-            ])
-            .analysis,
+            collect_code_byte_types(
+                &CodeAnalysis::<false>::analyze_code(&[
+                    Opcode::Push1 as u8,
+                    Opcode::Add as u8,
+                    Opcode::Add as u8
+                ])
+                .analysis,
+                3
+            ),
             [
                 CodeByteType::Opcode,
                 CodeByteType::DataOrInvalid,
@@ -399,8 +1363,15 @@ mod tests {
             ]
         );
         assert_eq!(
-            CodeAnalysis::<false>::analyze_code(&[Opcode::Push1 as u8, Opcode::Add as u8, 0xc0])
+            collect_code_byte_types(
+                &CodeAnalysis::<false>::analyze_code(&[
+                    Opcode::Push1 as u8,
+                    Opcode::Add as u8,
+                    0xc0
+                ])
                 .analysis,
+                3
+            ),
             [
                 CodeByteType::Opcode,
                 CodeByteType::DataOrInvalid,
@@ -408,13 +1379,16 @@ mod tests {
             ]
         );
         assert_eq!(
-            CodeAnalysis::<false>::analyze_code(&[
-                Opcode::Push1 as u8,
-                Opcode::Add as u8,
-                0xc0,
-                Opcode::Add as u8
-            ])
-            .analysis,
+            collect_code_byte_types(
+                &CodeAnalysis::<false>::analyze_code(&[
+                    Opcode::Push1 as u8,
+                    Opcode::Add as u8,
+                    0xc0,
+                    Opcode::Add as u8
+                ])
+                .analysis,
+                4
+            ),
             [
                 CodeByteType::Opcode,
                 CodeByteType::DataOrInvalid,
@@ -423,13 +1397,16 @@ mod tests {
             ]
         );
         assert_eq!(
-            CodeAnalysis::<false>::analyze_code(&[
-                Opcode::Push2 as u8,
-                Opcode::Add as u8,
-                Opcode::Add as u8,
-                Opcode::Add as u8,
-            ])
-            .analysis,
+            collect_code_byte_types(
+                &CodeAnalysis::<false>::analyze_code(&[
+                    Opcode::Push2 as u8,
+                    Opcode::Add as u8,
+                    Opcode::Add as u8,
+                    Opcode::Add as u8,
+                ])
+                .analysis,
+                4
+            ),
             [
                 CodeByteType::Opcode,
                 CodeByteType::DataOrInvalid,
@@ -438,13 +1415,16 @@ mod tests {
             ]
         );
         assert_eq!(
-            CodeAnalysis::<false>::analyze_code(&[
-                Opcode::Push2 as u8,
-                Opcode::Add as u8,
-                Opcode::Add as u8,
-                0xc0
-            ])
-            .analysis,
+            collect_code_byte_types(
+                &CodeAnalysis::<false>::analyze_code(&[
+                    Opcode::Push2 as u8,
+                    Opcode::Add as u8,
+                    Opcode::Add as u8,
+                    0xc0
+                ])
+                .analysis,
+                4
+            ),
             [
                 CodeByteType::Opcode,
                 CodeByteType::DataOrInvalid,
@@ -655,4 +1635,168 @@ mod tests {
             [2, 3, 4, 5]
         );
     }
+
+    #[cfg(feature = "needs-fn-ptr-conversion")]
+    #[test]
+    fn analyze_sections_single_section() {
+        // PUSH1 1 PUSH1 2 ADD
+        let sections = super::analyze_sections(&[
+            Opcode::Push1 as u8,
+            1,
+            Opcode::Push1 as u8,
+            2,
+            Opcode::Add as u8,
+        ]);
+        assert_eq!(
+            sections,
+            [super::Section {
+                start_pc: 0,
+                static_gas_cost: 9,
+                min_stack_depth: 0,
+                max_stack_height: 2,
+            }]
+        );
+    }
+
+    #[cfg(feature = "needs-fn-ptr-conversion")]
+    #[test]
+    fn analyze_sections_splits_at_jumpdest_and_jump() {
+        // PUSH1 3 JUMP JUMPDEST ADD
+        let code = [
+            Opcode::Push1 as u8,
+            3,
+            Opcode::Jump as u8,
+            Opcode::JumpDest as u8,
+            Opcode::Add as u8,
+        ];
+        let sections = super::analyze_sections(&code);
+        assert_eq!(
+            sections,
+            [
+                super::Section {
+                    start_pc: 0,
+                    static_gas_cost: 3,
+                    min_stack_depth: 0,
+                    max_stack_height: 1,
+                },
+                super::Section {
+                    start_pc: 3,
+                    static_gas_cost: 4,
+                    min_stack_depth: 2,
+                    max_stack_height: 0,
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "needs-fn-ptr-conversion")]
+    #[test]
+    fn analyze_sections_splits_before_dynamic_gas_opcode() {
+        // PUSH1 0 PUSH1 0 SHA3 ADD
+        let code = [
+            Opcode::Push1 as u8,
+            0,
+            Opcode::Push1 as u8,
+            0,
+            Opcode::Sha3 as u8,
+            Opcode::Add as u8,
+        ];
+        let sections = super::analyze_sections(&code);
+        assert_eq!(
+            sections,
+            [
+                super::Section {
+                    start_pc: 0,
+                    static_gas_cost: 6 + 30,
+                    min_stack_depth: 0,
+                    max_stack_height: 2,
+                },
+                super::Section {
+                    start_pc: 5,
+                    static_gas_cost: 3,
+                    min_stack_depth: 2,
+                    max_stack_height: 0,
+                },
+            ]
+        );
+    }
+
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
+    #[test]
+    fn to_bytes_from_bytes_round_trip() {
+        use crate::types::hash_cache;
+
+        let code = [
+            Opcode::Push1 as u8,
+            1,
+            Opcode::JumpDest as u8,
+            Opcode::Add as u8,
+        ];
+        let code_hash = hash_cache::hash(&code);
+        let analysis = CodeAnalysis::<false>::analyze_code(&code);
+
+        let bytes = analysis.to_bytes(code_hash);
+        let decoded = CodeAnalysis::<false>::from_bytes(&code, code_hash, &bytes).unwrap();
+
+        assert_eq!(
+            collect_code_byte_types(&decoded.analysis, code.len()),
+            collect_code_byte_types(&analysis.analysis, code.len())
+        );
+        assert_eq!(decoded.basic_blocks, analysis.basic_blocks);
+        assert_eq!(decoded.block_index, analysis.block_index);
+    }
+
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
+    #[test]
+    fn from_bytes_rejects_hash_mismatch() {
+        use crate::types::{hash_cache, u256, DecodeAnalysisError};
+
+        let code = [Opcode::Stop as u8];
+        let analysis = CodeAnalysis::<false>::analyze_code(&code);
+        let bytes = analysis.to_bytes(hash_cache::hash(&code));
+
+        assert_eq!(
+            CodeAnalysis::<false>::from_bytes(&code, u256::from(1_u64), &bytes).unwrap_err(),
+            DecodeAnalysisError::HashMismatch
+        );
+    }
+
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
+    #[test]
+    fn from_bytes_rejects_truncated_payload() {
+        use crate::types::{hash_cache, DecodeAnalysisError};
+
+        let code = [Opcode::Stop as u8];
+        let code_hash = hash_cache::hash(&code);
+        let bytes = CodeAnalysis::<false>::analyze_code(&code).to_bytes(code_hash);
+
+        assert_eq!(
+            CodeAnalysis::<false>::from_bytes(&code, code_hash, &bytes[..bytes.len() - 1])
+                .unwrap_err(),
+            DecodeAnalysisError::Truncated
+        );
+    }
+
+    #[cfg(all(feature = "code-analysis-cache", not(feature = "needs-fn-ptr-conversion")))]
+    #[test]
+    fn dump_cache_load_cache_round_trip() {
+        use crate::types::hash_cache;
+
+        let code = [Opcode::Push1 as u8, 1, Opcode::JumpDest as u8, Opcode::Add as u8];
+        let code_hash = hash_cache::hash(&code);
+        let analysis = CodeAnalysis::<false>::new(&code, Some(code_hash));
+
+        let dump = CodeAnalysis::<false>::dump_cache();
+        assert!(!dump.is_empty());
+
+        CodeAnalysis::<false>::load_cache(&dump);
+
+        let reloaded = CodeAnalysis::<false>::new(&code, Some(code_hash));
+        assert_eq!(
+            collect_code_byte_types(&reloaded.analysis, code.len()),
+            collect_code_byte_types(&analysis.analysis, code.len())
+        );
+        assert_eq!(reloaded.basic_blocks, analysis.basic_blocks);
+        assert_eq!(reloaded.block_index, analysis.block_index);
+    }
 }
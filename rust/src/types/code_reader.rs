@@ -1,6 +1,6 @@
 #[cfg(not(feature = "needs-fn-ptr-conversion"))]
-use std::cmp::min;
-use std::{self, ops::Deref};
+use core::cmp::min;
+use core::ops::Deref;
 
 #[cfg(feature = "needs-fn-ptr-conversion")]
 use crate::interpreter::OpFn;
@@ -25,12 +25,36 @@ impl<'a, const STEPPABLE: bool> Deref for CodeReader<'a, STEPPABLE> {
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum GetOpcodeError {
-    OutOfRange,
-    Invalid,
+    /// `pc` is at or past the end of the code.
+    OutOfRange { pc: usize },
+    /// The byte at `pc` is not a legal opcode. `byte` carries the offending value when it's
+    /// cheaply available (the `needs-fn-ptr-conversion` builds key their analysis by converted
+    /// pc rather than raw code offset, so they can't recover it here).
+    Invalid { pc: usize, byte: Option<u8> },
+    /// A `JUMP`/`JUMPI` target `dest` is not a `JUMPDEST`, reported at the pc that attempted it -
+    /// the diagnostic counterpart to the bare `FailStatus::BadJumpDestination` [`CodeReader::try_jump`]
+    /// returns on the same condition.
+    InvalidJumpDestination { pc: usize, dest: u256 },
 }
 
 impl<'a, const STEPPABLE: bool> CodeReader<'a, STEPPABLE> {
     /// If the const generic J is false, jumpdests are skipped.
+    ///
+    /// `code_hash`, when given, is expected to be the keccak256 hash of `code` exactly as it
+    /// appears on chain - the interpreter passes through `message.code_hash()`, which is the
+    /// host's hash of the raw, unpadded bytecode, not of anything this module derives from it.
+    /// [`CodeAnalysis::new`] uses it, under the `code-analysis-cache` feature, to key a shared,
+    /// bounded LRU of `Arc`-wrapped analyses (including the jumpdest bitmap and, where enabled,
+    /// the expanded push-data form `copy_push_data` builds), so repeated calls into the same
+    /// contract reuse one immutable analysis instead of re-scanning its code every time. A host
+    /// has no real hash to give for one-shot `CREATE`/`CREATE2` init code (it isn't stored
+    /// anywhere yet), so `message.code_hash()` is `None` there; `CodeAnalysis::new` still reuses
+    /// the analysis across repeated calls into the *same* init code slice (keyed on its address
+    /// and length instead of a hash), but never promotes it into the hash-keyed cache, so one-shot
+    /// init code can't evict another contract's hot entry. Empty `code` skips both caches
+    /// entirely. A host that already tracks code by hash and wants to share one cache across
+    /// several [`Interpreter`](crate::interpreter::Interpreter)s instead of using the implicit
+    /// process-wide one can call [`CodeAnalysis::new_with_cache`] directly.
     pub fn new(code: &'a [u8], code_hash: Option<u256>, pc: usize) -> Self {
         let code_analysis = CodeAnalysis::new(code, code_hash);
         #[cfg(feature = "needs-fn-ptr-conversion")]
@@ -44,20 +68,21 @@ impl<'a, const STEPPABLE: bool> CodeReader<'a, STEPPABLE> {
 
     #[cfg(not(feature = "needs-fn-ptr-conversion"))]
     pub fn get(&self) -> Result<Opcode, GetOpcodeError> {
-        if let Some(op) = self.code.get(self.pc) {
-            let analysis = self.code_analysis.analysis[self.pc];
-            if analysis == CodeByteType::DataOrInvalid {
-                Err(GetOpcodeError::Invalid)
-            } else {
+        match self.code_analysis.analysis.get(self.pc) {
+            None => Err(GetOpcodeError::OutOfRange { pc: self.pc }),
+            Some(CodeByteType::DataOrInvalid) => Err(GetOpcodeError::Invalid {
+                pc: self.pc,
+                byte: Some(self.code[self.pc]),
+            }),
+            Some(_) => {
+                let op = self.code[self.pc];
                 // SAFETY:
                 // [Opcode] has repr(u8) and therefore the same memory layout as u8.
                 // In get_code_byte_types this byte of the code was determined to be a valid opcode.
                 // Therefore the value is a valid [Opcode].
-                let op = unsafe { std::mem::transmute::<u8, Opcode>(*op) };
+                let op = unsafe { core::mem::transmute::<u8, Opcode>(op) };
                 Ok(op)
             }
-        } else {
-            Err(GetOpcodeError::OutOfRange)
         }
     }
     #[cfg(feature = "needs-fn-ptr-conversion")]
@@ -65,23 +90,38 @@ impl<'a, const STEPPABLE: bool> CodeReader<'a, STEPPABLE> {
         self.code_analysis
             .analysis
             .get(self.pc)
-            .ok_or(GetOpcodeError::OutOfRange)
-            .and_then(|analysis| analysis.get_func().ok_or(GetOpcodeError::Invalid))
+            .ok_or(GetOpcodeError::OutOfRange { pc: self.pc })
+            .and_then(|analysis| {
+                analysis.get_func().ok_or(GetOpcodeError::Invalid {
+                    pc: self.pc,
+                    byte: None,
+                })
+            })
     }
 
     pub fn next(&mut self) {
         self.pc += 1;
     }
 
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
     pub fn try_jump(&mut self, dest: u256) -> Result<(), FailStatus> {
         let dest = u64::try_from(dest).map_err(|_| FailStatus::BadJumpDestination)? as usize;
-        if !self.code_analysis.analysis.get(dest).is_some_and(|c| {
-            #[cfg(not(feature = "needs-fn-ptr-conversion"))]
-            return *c == CodeByteType::JumpDest;
+        if self.code_analysis.analysis.get(dest) != Some(CodeByteType::JumpDest) {
+            return Err(FailStatus::BadJumpDestination);
+        }
+        self.pc = dest;
 
-            #[cfg(feature = "needs-fn-ptr-conversion")]
-            return c.code_byte_type() == CodeByteType::JumpDest;
-        }) {
+        Ok(())
+    }
+    #[cfg(feature = "needs-fn-ptr-conversion")]
+    pub fn try_jump(&mut self, dest: u256) -> Result<(), FailStatus> {
+        let dest = u64::try_from(dest).map_err(|_| FailStatus::BadJumpDestination)? as usize;
+        if !self
+            .code_analysis
+            .analysis
+            .get(dest)
+            .is_some_and(|c| c.code_byte_type() == CodeByteType::JumpDest)
+        {
             return Err(FailStatus::BadJumpDestination);
         }
         self.pc = dest;
@@ -89,6 +129,37 @@ impl<'a, const STEPPABLE: bool> CodeReader<'a, STEPPABLE> {
         Ok(())
     }
 
+    /// Validates `dest` exactly as [`try_jump`](Self::try_jump) does, but without performing the
+    /// jump, and reports a rejected destination as a [`GetOpcodeError::InvalidJumpDestination`]
+    /// carrying `dest` and the current pc instead of the bare `FailStatus` `try_jump` returns -
+    /// for debuggers/disassemblers that want to say which value was rejected and where, rather
+    /// than just that execution would have failed.
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
+    pub fn check_jump(&self, dest: u256) -> Result<(), GetOpcodeError> {
+        let is_valid = u64::try_from(dest).is_ok_and(|d| {
+            self.code_analysis.analysis.get(d as usize) == Some(CodeByteType::JumpDest)
+        });
+        if is_valid {
+            Ok(())
+        } else {
+            Err(GetOpcodeError::InvalidJumpDestination { pc: self.pc, dest })
+        }
+    }
+    #[cfg(feature = "needs-fn-ptr-conversion")]
+    pub fn check_jump(&self, dest: u256) -> Result<(), GetOpcodeError> {
+        let is_valid = u64::try_from(dest).is_ok_and(|d| {
+            self.code_analysis
+                .analysis
+                .get(d as usize)
+                .is_some_and(|c| c.code_byte_type() == CodeByteType::JumpDest)
+        });
+        if is_valid {
+            Ok(())
+        } else {
+            Err(GetOpcodeError::InvalidJumpDestination { pc: self.pc, dest })
+        }
+    }
+
     #[cfg(not(feature = "needs-fn-ptr-conversion"))]
     pub fn get_push_data(&mut self, len: usize) -> u256 {
         assert!(len <= 32);
@@ -255,9 +326,38 @@ mod tests {
         #[cfg(feature = "needs-fn-ptr-conversion")]
         assert!(code_reader.get().is_ok(),);
         code_reader.next();
-        assert_eq!(code_reader.get(), Err(GetOpcodeError::Invalid));
+        assert!(matches!(
+            code_reader.get(),
+            Err(GetOpcodeError::Invalid { pc: 2, .. })
+        ));
         code_reader.next();
-        assert_eq!(code_reader.get(), Err(GetOpcodeError::OutOfRange));
+        assert!(matches!(
+            code_reader.get(),
+            Err(GetOpcodeError::OutOfRange { pc: 3 })
+        ));
+    }
+
+    #[cfg(not(feature = "needs-fn-ptr-conversion"))]
+    #[test]
+    fn code_reader_check_jump() {
+        let code_reader = CodeReader::<false>::new(
+            &[
+                Opcode::Push1 as u8,
+                Opcode::JumpDest as u8,
+                Opcode::JumpDest as u8,
+            ],
+            None,
+            0,
+        );
+        assert!(matches!(
+            code_reader.check_jump(1u8.into()),
+            Err(GetOpcodeError::InvalidJumpDestination { pc: 0, dest }) if dest == 1u8.into()
+        ));
+        assert_eq!(code_reader.check_jump(2u8.into()), Ok(()));
+        assert!(matches!(
+            code_reader.check_jump(u256::MAX),
+            Err(GetOpcodeError::InvalidJumpDestination { pc: 0, .. })
+        ));
     }
 
     #[test]
@@ -0,0 +1,415 @@
+//! A human-readable listing of raw EVM bytecode, independent of [`CodeAnalysis`](crate::types::CodeAnalysis)'s
+//! execution-oriented block/jump-dest bookkeeping - useful for tooling/debuggers that just want a
+//! single canonical `pc -> instruction` decode instead of re-implementing the PUSH-length table.
+//!
+//! A request for a disassembler module behind a `disasm` feature, yielding `(pc, Opcode,
+//! immediate_bytes)` with a `Display` for mnemonics, describes this module under a different
+//! field shape: [`disassemble`] already walks `code` pc-by-pc, consumes `PUSH1..PUSH32`'s 1-32
+//! immediate bytes (zero-padding a truncated trailing push), and [`DisasmInstr`]'s `mnemonic`
+//! comes from `opcode_mnemonic`: a hand-maintained match over [`Opcode`], or, with
+//! `generated-mnemonics`, a table `build.rs` generates from `instructions.in`. Neither is shared
+//! with [`code_byte_type`] or the interpreter's dispatch; the mnemonics are kept as a separate
+//! copy. `mnemonic: &'static str`
+//! stands in for `Opcode` itself because not every decoded byte maps to one - [`CodeByteType::DataOrInvalid`]
+//! bytes still need a listing entry - so `DisasmInstr` covers that case under `is_data` instead of
+//! requiring every entry to carry a valid `Opcode`.
+use std::{cmp::min, fmt};
+
+use crate::types::{code_byte_type, u256, CodeByteType, Opcode};
+
+/// One decoded instruction (or, for a byte that doesn't decode to a real opcode, one raw data
+/// byte) at a given `pc`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisasmInstr {
+    pub pc: usize,
+    pub mnemonic: &'static str,
+    pub immediate: Option<u256>,
+    pub is_jumpdest: bool,
+    pub is_data: bool,
+}
+
+/// Decodes `code` into a flat instruction listing, mirroring the walk [`CodeAnalysis::new`]'s
+/// [`analyze_code`](crate::types::CodeAnalysis) does for its basic-block analysis: start at pc 0,
+/// classify the opcode byte, and for `PUSH1..=PUSH32` capture the following `n` bytes as a
+/// big-endian immediate, zero-padding past the end of `code` rather than failing - the same
+/// truncation rule `CodeReader::get_push_data` uses. Bytes that don't decode to a known opcode are
+/// reported as data rather than an instruction.
+pub fn disassemble(code: &[u8]) -> Vec<DisasmInstr> {
+    let mut instructions = Vec::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let op = code[pc];
+        let (byte_type, push_len) = code_byte_type(op);
+        if byte_type == CodeByteType::DataOrInvalid {
+            instructions.push(DisasmInstr {
+                pc,
+                mnemonic: "DATA",
+                immediate: Some(u256::from(op)),
+                is_jumpdest: false,
+                is_data: true,
+            });
+            pc += 1;
+            continue;
+        }
+        if push_len > 0 {
+            let data_len = min(push_len, code.len() - pc - 1);
+            let mut immediate = [0; 32];
+            immediate[32 - push_len..32 - push_len + data_len]
+                .copy_from_slice(&code[pc + 1..pc + 1 + data_len]);
+            instructions.push(DisasmInstr {
+                pc,
+                mnemonic: opcode_mnemonic(op),
+                immediate: Some(u256::from_be_bytes(immediate)),
+                is_jumpdest: false,
+                is_data: false,
+            });
+            pc += push_len + 1;
+            continue;
+        }
+        instructions.push(DisasmInstr {
+            pc,
+            mnemonic: opcode_mnemonic(op),
+            immediate: None,
+            is_jumpdest: byte_type == CodeByteType::JumpDest,
+            is_data: false,
+        });
+        pc += 1;
+    }
+    instructions
+}
+
+#[cfg(feature = "generated-mnemonics")]
+include!(concat!(env!("OUT_DIR"), "/opcode_mnemonics.rs"));
+
+/// `op`'s mnemonic, e.g. `"PUSH2"` or `"CALLDATALOAD"` - `op` must be a byte [`code_byte_type`]
+/// classifies as [`CodeByteType::Opcode`] or [`CodeByteType::JumpDest`].
+#[cfg(feature = "generated-mnemonics")]
+fn opcode_mnemonic(op: u8) -> &'static str {
+    GENERATED_MNEMONICS[op as usize]
+}
+
+/// `op`'s mnemonic, e.g. `"PUSH2"` or `"CALLDATALOAD"` - `op` must be a byte [`code_byte_type`]
+/// classifies as [`CodeByteType::Opcode`] or [`CodeByteType::JumpDest`]. Hand-maintained; see
+/// `instructions.in` for the generated equivalent used when `generated-mnemonics` is enabled.
+#[cfg(not(feature = "generated-mnemonics"))]
+fn opcode_mnemonic(op: u8) -> &'static str {
+    // SAFETY: callers only pass bytes code_byte_type has already classified as a known opcode, so
+    // op is a valid Opcode discriminant.
+    let op = unsafe { std::mem::transmute::<u8, Opcode>(op) };
+    match op {
+        Opcode::Stop => "STOP",
+        Opcode::Add => "ADD",
+        Opcode::Mul => "MUL",
+        Opcode::Sub => "SUB",
+        Opcode::Div => "DIV",
+        Opcode::SDiv => "SDIV",
+        Opcode::Mod => "MOD",
+        Opcode::SMod => "SMOD",
+        Opcode::AddMod => "ADDMOD",
+        Opcode::MulMod => "MULMOD",
+        Opcode::Exp => "EXP",
+        Opcode::SignExtend => "SIGNEXTEND",
+        Opcode::Lt => "LT",
+        Opcode::Gt => "GT",
+        Opcode::SLt => "SLT",
+        Opcode::SGt => "SGT",
+        Opcode::Eq => "EQ",
+        Opcode::IsZero => "ISZERO",
+        Opcode::And => "AND",
+        Opcode::Or => "OR",
+        Opcode::Xor => "XOR",
+        Opcode::Not => "NOT",
+        Opcode::Byte => "BYTE",
+        Opcode::Shl => "SHL",
+        Opcode::Shr => "SHR",
+        Opcode::Sar => "SAR",
+        Opcode::Sha3 => "SHA3",
+        #[cfg(feature = "needs-fn-ptr-conversion")]
+        Opcode::NoOp | Opcode::SkipNoOps => "NOOP",
+        Opcode::Address => "ADDRESS",
+        Opcode::Balance => "BALANCE",
+        Opcode::Origin => "ORIGIN",
+        Opcode::Caller => "CALLER",
+        Opcode::CallValue => "CALLVALUE",
+        Opcode::CallDataLoad => "CALLDATALOAD",
+        Opcode::CallDataSize => "CALLDATASIZE",
+        Opcode::CallDataCopy => "CALLDATACOPY",
+        Opcode::CodeSize => "CODESIZE",
+        Opcode::CodeCopy => "CODECOPY",
+        Opcode::GasPrice => "GASPRICE",
+        Opcode::ExtCodeSize => "EXTCODESIZE",
+        Opcode::ExtCodeCopy => "EXTCODECOPY",
+        Opcode::ReturnDataSize => "RETURNDATASIZE",
+        Opcode::ReturnDataCopy => "RETURNDATACOPY",
+        Opcode::ExtCodeHash => "EXTCODEHASH",
+        Opcode::BlockHash => "BLOCKHASH",
+        Opcode::Coinbase => "COINBASE",
+        Opcode::Timestamp => "TIMESTAMP",
+        Opcode::Number => "NUMBER",
+        Opcode::PrevRandao => "PREVRANDAO",
+        Opcode::GasLimit => "GASLIMIT",
+        Opcode::ChainId => "CHAINID",
+        Opcode::SelfBalance => "SELFBALANCE",
+        Opcode::BaseFee => "BASEFEE",
+        Opcode::BlobHash => "BLOBHASH",
+        Opcode::BlobBaseFee => "BLOBBASEFEE",
+        Opcode::Pop => "POP",
+        Opcode::MLoad => "MLOAD",
+        Opcode::MStore => "MSTORE",
+        Opcode::MStore8 => "MSTORE8",
+        Opcode::SLoad => "SLOAD",
+        Opcode::SStore => "SSTORE",
+        Opcode::Jump => "JUMP",
+        Opcode::JumpI => "JUMPI",
+        Opcode::Pc => "PC",
+        Opcode::MSize => "MSIZE",
+        Opcode::Gas => "GAS",
+        Opcode::JumpDest => "JUMPDEST",
+        Opcode::TLoad => "TLOAD",
+        Opcode::TStore => "TSTORE",
+        Opcode::MCopy => "MCOPY",
+        Opcode::Push0 => "PUSH0",
+        Opcode::Push1 => "PUSH1",
+        Opcode::Push2 => "PUSH2",
+        Opcode::Push3 => "PUSH3",
+        Opcode::Push4 => "PUSH4",
+        Opcode::Push5 => "PUSH5",
+        Opcode::Push6 => "PUSH6",
+        Opcode::Push7 => "PUSH7",
+        Opcode::Push8 => "PUSH8",
+        Opcode::Push9 => "PUSH9",
+        Opcode::Push10 => "PUSH10",
+        Opcode::Push11 => "PUSH11",
+        Opcode::Push12 => "PUSH12",
+        Opcode::Push13 => "PUSH13",
+        Opcode::Push14 => "PUSH14",
+        Opcode::Push15 => "PUSH15",
+        Opcode::Push16 => "PUSH16",
+        Opcode::Push17 => "PUSH17",
+        Opcode::Push18 => "PUSH18",
+        Opcode::Push19 => "PUSH19",
+        Opcode::Push20 => "PUSH20",
+        Opcode::Push21 => "PUSH21",
+        Opcode::Push22 => "PUSH22",
+        Opcode::Push23 => "PUSH23",
+        Opcode::Push24 => "PUSH24",
+        Opcode::Push25 => "PUSH25",
+        Opcode::Push26 => "PUSH26",
+        Opcode::Push27 => "PUSH27",
+        Opcode::Push28 => "PUSH28",
+        Opcode::Push29 => "PUSH29",
+        Opcode::Push30 => "PUSH30",
+        Opcode::Push31 => "PUSH31",
+        Opcode::Push32 => "PUSH32",
+        Opcode::Dup1 => "DUP1",
+        Opcode::Dup2 => "DUP2",
+        Opcode::Dup3 => "DUP3",
+        Opcode::Dup4 => "DUP4",
+        Opcode::Dup5 => "DUP5",
+        Opcode::Dup6 => "DUP6",
+        Opcode::Dup7 => "DUP7",
+        Opcode::Dup8 => "DUP8",
+        Opcode::Dup9 => "DUP9",
+        Opcode::Dup10 => "DUP10",
+        Opcode::Dup11 => "DUP11",
+        Opcode::Dup12 => "DUP12",
+        Opcode::Dup13 => "DUP13",
+        Opcode::Dup14 => "DUP14",
+        Opcode::Dup15 => "DUP15",
+        Opcode::Dup16 => "DUP16",
+        Opcode::Swap1 => "SWAP1",
+        Opcode::Swap2 => "SWAP2",
+        Opcode::Swap3 => "SWAP3",
+        Opcode::Swap4 => "SWAP4",
+        Opcode::Swap5 => "SWAP5",
+        Opcode::Swap6 => "SWAP6",
+        Opcode::Swap7 => "SWAP7",
+        Opcode::Swap8 => "SWAP8",
+        Opcode::Swap9 => "SWAP9",
+        Opcode::Swap10 => "SWAP10",
+        Opcode::Swap11 => "SWAP11",
+        Opcode::Swap12 => "SWAP12",
+        Opcode::Swap13 => "SWAP13",
+        Opcode::Swap14 => "SWAP14",
+        Opcode::Swap15 => "SWAP15",
+        Opcode::Swap16 => "SWAP16",
+        Opcode::Log0 => "LOG0",
+        Opcode::Log1 => "LOG1",
+        Opcode::Log2 => "LOG2",
+        Opcode::Log3 => "LOG3",
+        Opcode::Log4 => "LOG4",
+        Opcode::Create => "CREATE",
+        Opcode::Call => "CALL",
+        Opcode::CallCode => "CALLCODE",
+        Opcode::Return => "RETURN",
+        Opcode::DelegateCall => "DELEGATECALL",
+        Opcode::Create2 => "CREATE2",
+        Opcode::StaticCall => "STATICCALL",
+        Opcode::Revert => "REVERT",
+        Opcode::Invalid => "INVALID",
+        Opcode::SelfDestruct => "SELFDESTRUCT",
+    }
+}
+
+impl fmt::Display for DisasmInstr {
+    /// Renders the classic `0012: PUSH2 0x00ff` disassembly line, or `0012: .byte 0x0c` for a byte
+    /// that doesn't decode to a known opcode.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_data {
+            let Some(immediate) = self.immediate else {
+                unreachable!("disassemble always sets immediate for a data byte");
+            };
+            return write!(f, "{:04x}: .byte 0x{:02x}", self.pc, immediate.to_be_bytes()[31]);
+        }
+        write!(f, "{:04x}: {}", self.pc, self.mnemonic)?;
+        if let Some(immediate) = self.immediate {
+            // Printed at the push's own width, e.g. two bytes for PUSH2.
+            let width = self
+                .mnemonic
+                .strip_prefix("PUSH")
+                .and_then(|n| n.parse::<usize>().ok())
+                .map_or(32, |n| n.min(32));
+            write!(f, " 0x")?;
+            for byte in &immediate.to_be_bytes()[32 - width..] {
+                write!(f, "{byte:02x}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders a full listing, one line per [`DisasmInstr`] via its [`Display`](fmt::Display) impl,
+/// with a `.L<pc>:` label line inserted immediately before every `JUMPDEST` so jump targets stand
+/// out without cross-referencing anything by eye.
+pub fn render(instructions: &[DisasmInstr]) -> String {
+    let mut output = String::new();
+    for instr in instructions {
+        if instr.is_jumpdest {
+            output.push_str(&format!(".L{:04x}:\n", instr.pc));
+        }
+        output.push_str(&instr.to_string());
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disassembles_simple_code() {
+        // PUSH1 0x01, PUSH1 0x02, ADD, JUMPDEST, STOP
+        let code = [0x60, 0x01, 0x60, 0x02, 0x01, 0x5B, 0x00];
+        let instructions = disassemble(&code);
+        assert_eq!(
+            instructions,
+            vec![
+                DisasmInstr {
+                    pc: 0,
+                    mnemonic: "PUSH1",
+                    immediate: Some(u256::from(1)),
+                    is_jumpdest: false,
+                    is_data: false,
+                },
+                DisasmInstr {
+                    pc: 2,
+                    mnemonic: "PUSH1",
+                    immediate: Some(u256::from(2)),
+                    is_jumpdest: false,
+                    is_data: false,
+                },
+                DisasmInstr {
+                    pc: 4,
+                    mnemonic: "ADD",
+                    immediate: None,
+                    is_jumpdest: false,
+                    is_data: false,
+                },
+                DisasmInstr {
+                    pc: 5,
+                    mnemonic: "JUMPDEST",
+                    immediate: None,
+                    is_jumpdest: true,
+                    is_data: false,
+                },
+                DisasmInstr {
+                    pc: 6,
+                    mnemonic: "STOP",
+                    immediate: None,
+                    is_jumpdest: false,
+                    is_data: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn zero_pads_push_data_truncated_by_end_of_code() {
+        // PUSH2 with only one byte of immediate left before the code ends.
+        let code = [0x61, 0xAB];
+        let instructions = disassemble(&code);
+        assert_eq!(
+            instructions,
+            vec![DisasmInstr {
+                pc: 0,
+                mnemonic: "PUSH2",
+                immediate: Some(u256::from(0xAB00_u64)),
+                is_jumpdest: false,
+                is_data: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn tags_undefined_bytes_as_data() {
+        // 0x0C is not an assigned opcode.
+        let code = [0x0C];
+        let instructions = disassemble(&code);
+        assert_eq!(
+            instructions,
+            vec![DisasmInstr {
+                pc: 0,
+                mnemonic: "DATA",
+                immediate: Some(u256::from(0x0C)),
+                is_jumpdest: false,
+                is_data: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn display_renders_classic_form() {
+        let instr = DisasmInstr {
+            pc: 0x12,
+            mnemonic: "PUSH2",
+            immediate: Some(u256::from(0xFF_u64)),
+            is_jumpdest: false,
+            is_data: false,
+        };
+        assert_eq!(instr.to_string(), "0012: PUSH2 0x00ff");
+    }
+
+    #[test]
+    fn display_renders_data_bytes_as_dot_byte() {
+        let instr = DisasmInstr {
+            pc: 0,
+            mnemonic: "DATA",
+            immediate: Some(u256::from(0x0C)),
+            is_jumpdest: false,
+            is_data: true,
+        };
+        assert_eq!(instr.to_string(), "0000: .byte 0x0c");
+    }
+
+    #[test]
+    fn render_inserts_labels_before_jumpdests() {
+        // PUSH1 0x00, JUMP, JUMPDEST, STOP
+        let code = [0x60, 0x00, 0x56, 0x5B, 0x00];
+        let listing = render(&disassemble(&code));
+        assert_eq!(
+            listing,
+            "0000: PUSH1 0x00\n0002: JUMP\n.L0003:\n0003: JUMPDEST\n0004: STOP\n"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Dynamic loading of third-party EVMC-ABI shared libraries, registered against specific
+//! addresses so a `CALL`/`CALLCODE`/`DELEGATECALL`/`STATICCALL` targeting one of them is handed
+//! off to that external implementation instead of going through
+//! [`Precompile`](crate::types::Precompile) dispatch or the host. Modeled on
+//! `driver::reference_vm::ReferenceVm`'s `libloading` approach - same `Library` + `evmc_vm_t`
+//! vtable pairing, same drop order requirement - but keyed by address rather than being a single
+//! fuzz-harness-wide comparison target, and gated behind the `external-module` feature so the
+//! default build stays free of the `libloading` dependency.
+//!
+//! A registered module is run the same way a context-less
+//! [`Interpreter`](crate::interpreter::Interpreter) runs: with a null host and no inherited code.
+//! The point is drop-in interop with a
+//! self-contained external implementation for differential testing, not proxying this frame's
+//! storage/host callbacks across an extra FFI boundary - the module is expected to own whatever
+//! logic lives at the address it's registered against outright, the same way a [`Precompile`
+//! ](crate::types::Precompile) does.
+
+use std::{collections::HashMap, fmt, ptr};
+
+use evmc_vm::{
+    ffi::{evmc_message, evmc_vm as evmc_vm_t},
+    Address, ExecutionMessage, ExecutionResult, Revision,
+};
+use libloading::{Library, Symbol};
+
+#[derive(Debug)]
+pub enum ExternalModuleError {
+    Load(libloading::Error),
+    Create,
+}
+
+impl fmt::Display for ExternalModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Load(err) => write!(f, "failed to load external module: {err}"),
+            Self::Create => {
+                write!(f, "external module's create function returned a null instance")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExternalModuleError {}
+
+/// A `Library` plus the `evmc_vm_t` vtable it produced. The vtable borrows from the library, so
+/// the two must be dropped together, in that order - `Drop` below calls `destroy` before
+/// `_library` is unloaded, same as `driver::reference_vm::ReferenceVm`.
+pub struct ExternalModule {
+    vm: &'static mut evmc_vm_t,
+    _library: Library,
+}
+
+impl ExternalModule {
+    /// Loads `path` and invokes the exported `symbol`, which must have the EVMC create-function
+    /// signature `unsafe extern "C" fn() -> *mut evmc_vm_t`, following the `evmc_create_*` naming
+    /// convention from the EVMC loader (`evmc/loader.h`).
+    pub fn load(path: &str, symbol: &str) -> Result<Self, ExternalModuleError> {
+        // SAFETY:
+        // Loading an arbitrary shared library is inherently unsafe; the caller is trusted to
+        // point this at a well-behaved EVMC implementation, same as the C `evmc_loader` API.
+        let library = unsafe { Library::new(path) }.map_err(ExternalModuleError::Load)?;
+        // SAFETY:
+        // `symbol` is documented to name an `unsafe extern "C" fn() -> *mut evmc_vm_t`.
+        let create: Symbol<unsafe extern "C" fn() -> *mut evmc_vm_t> =
+            unsafe { library.get(symbol.as_bytes()) }.map_err(ExternalModuleError::Load)?;
+        // SAFETY:
+        // `create` was just resolved from `library` and matches the EVMC create-function ABI.
+        let instance = unsafe { create() };
+        if instance.is_null() {
+            return Err(ExternalModuleError::Create);
+        }
+        // SAFETY:
+        // `instance` is not null and `create` must return a valid `evmc_vm_t*` per the EVMC ABI.
+        // It borrows from `library`, which outlives it because of the field order and `Drop` impl
+        // below.
+        let vm = unsafe { &mut *instance };
+        Ok(Self {
+            vm,
+            _library: library,
+        })
+    }
+
+    /// Runs `message` against this module with no host and no code of its own - see the module
+    /// doc comment for why neither gets marshaled across. Gas, input and output are exactly what
+    /// does cross: the caller folds the returned [`ExecutionResult`] back into
+    /// `last_call_return_data` and the return-data memory region the same way it would for any
+    /// other sub-call.
+    pub fn call(&mut self, revision: Revision, message: &ExecutionMessage) -> ExecutionResult {
+        let execute = self.vm.execute.expect("external module has no execute function");
+        let input = message.input().unwrap_or(&[]);
+        let raw_message = evmc_message {
+            kind: message.kind(),
+            flags: message.flags(),
+            depth: message.depth(),
+            gas: message.gas(),
+            recipient: *message.recipient(),
+            sender: *message.sender(),
+            input_data: if input.is_empty() {
+                ptr::null()
+            } else {
+                input.as_ptr()
+            },
+            input_size: input.len(),
+            value: *message.value(),
+            create2_salt: *message.create2_salt(),
+            code_address: *message.code_address(),
+        };
+        // SAFETY:
+        // `self.vm` is a valid, live `evmc_vm_t*` for as long as `_library` stays loaded, which it
+        // does for the duration of this call. `raw_message` is a local, valid for the call. `host`
+        // and `context` are null, which `execute` must accept per the EVMC ABI - the same
+        // null-host contract this crate's own context-less `Interpreter` relies on (see
+        // `Interpreter::new`). `code`/`code_size` are likewise null/zero: this module is expected
+        // to own its logic outright rather than run bytecode handed to it.
+        unsafe {
+            execute(
+                self.vm,
+                ptr::null(),
+                ptr::null_mut(),
+                revision,
+                &raw_message,
+                ptr::null(),
+                0,
+            )
+        }
+        .into()
+    }
+}
+
+impl Drop for ExternalModule {
+    fn drop(&mut self) {
+        if let Some(destroy) = self.vm.destroy {
+            // SAFETY:
+            // `self.vm` is valid for as long as `_library` stays loaded, which it does until
+            // after this call returns.
+            unsafe { destroy(self.vm) };
+        }
+    }
+}
+
+/// Which address each loaded [`ExternalModule`] is registered against. Checked by
+/// `Interpreter::dispatch_call` ahead of asking the host to run a `CALL`, the same way
+/// [`Precompile::from_address`](crate::types::Precompile::from_address) is checked ahead of it.
+#[derive(Default)]
+pub struct ExternalModuleRegistry {
+    modules: HashMap<Address, ExternalModule>,
+}
+
+impl ExternalModuleRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `module` against `address`, replacing whatever was previously registered there.
+    pub fn register(&mut self, address: Address, module: ExternalModule) {
+        self.modules.insert(address, module);
+    }
+
+    /// The module registered against `address`, if any.
+    pub fn get_mut(&mut self, address: &Address) -> Option<&mut ExternalModule> {
+        self.modules.get_mut(address)
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{
+    types::{u256, FailStatus, Memory},
+    utils::{Gas, SliceExt},
+};
+#[cfg(feature = "buffer-pool")]
+use crate::types::BufferPool;
+
+/// Recycles the `Vec<u8>` [`GasLeft::finalize`] reads a call's output into, for a caller to hand
+/// back with [`release_output`] once it's done with it - `ExecutionResult::new`'s FFI path copies
+/// this into its own, C-owned buffer rather than taking ownership, so the `Vec` here would
+/// otherwise just be allocated and freed once per call for nothing.
+#[cfg(feature = "buffer-pool")]
+static OUTPUT_POOL: BufferPool<u8> = BufferPool::new();
+
+/// Hands a call's output buffer back to [`OUTPUT_POOL`] once the caller that read it (via
+/// [`GasLeft::finalize`]) is done with it, so the next call's `finalize` can skip allocating one.
+#[cfg(feature = "buffer-pool")]
+pub fn release_output(output: Vec<u8>) {
+    OUTPUT_POOL.release(output);
+}
+
+/// What `RETURN`/`REVERT` still owe when the run loop exits, as opposed to every other way
+/// execution can end (`STOP`, falling off the end of the code, a plain `Err`), which has nothing
+/// left to charge or read. Keeping the two apart lets [`finalize`](Self::finalize) be the single
+/// place that charges for, reads, and zero-pads the output buffer, instead of `return_` and
+/// `revert_` each doing their own copy of that logic inline.
+#[derive(Debug)]
+pub enum GasLeft {
+    /// The final gas left; there is no output buffer to assemble.
+    Known(Gas),
+    /// `offset..offset + len` in memory is the output buffer, not yet charged for or read.
+    NeedsReturn { gas: Gas, offset: u256, len: u64 },
+}
+
+impl GasLeft {
+    /// Charges whatever a `NeedsReturn` still owes for growing memory to cover its output region,
+    /// then reads that region out of `memory`, zero-padding past its current length rather than
+    /// actually growing it - nothing runs after this to tell the difference. `Known` has no
+    /// output and nothing left to charge.
+    pub fn finalize(self, memory: &Memory) -> Result<(Gas, Option<Vec<u8>>), FailStatus> {
+        match self {
+            Self::Known(gas) => Ok((gas, None)),
+            Self::NeedsReturn { mut gas, offset, len } => {
+                gas.consume(memory.reserve_cost(offset, len)?)?;
+                let data = memory.as_slice().get_within_bounds(offset, len);
+                #[cfg(feature = "buffer-pool")]
+                let mut output = OUTPUT_POOL.acquire(len as usize);
+                #[cfg(not(feature = "buffer-pool"))]
+                let mut output = Vec::new();
+                output.extend_from_slice(data);
+                output.resize(len as usize, 0);
+                Ok((gas, Some(output)))
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::types::FailStatus;
+
+/// Which gas-accounting computation ran out of gas, distinct from the single
+/// [`FailStatus::OutOfGas`] bucket that is all the host ever sees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutOfGasReason {
+    /// The 9000 gas surcharge for sending a non-zero value (`CALL`, `CALLCODE`).
+    PositiveValueTransfer,
+    /// The 25000 gas surcharge for sending value to an account that does not yet exist.
+    ValueToEmptyAccount,
+    /// EIP-2929 cold/warm account access.
+    AddressAccess,
+    /// The per-word cost of copying memory (`CALLDATACOPY`, `CODECOPY`, ...).
+    Copy,
+    /// The quadratic cost of growing memory to a new high-water mark (`MSTORE`, `MLOAD`, `CALL`,
+    /// `RETURN`, ...).
+    MemoryExpansion,
+    /// The refund counter itself overflowed `i64`.
+    RefundOverflow,
+}
+
+/// A finer-grained account of why execution halted than [`FailStatus`], which only exposes the
+/// coarse bucket a failure maps to in the EVMC ABI. A test harness that needs to assert *why* a
+/// transaction failed (e.g. distinguishing the cause of an `EVMC_OUT_OF_GAS`) can match on this
+/// instead of re-deriving the reason from scratch.
+///
+/// This currently only distinguishes gas-accounting failures, the causes named in the request
+/// that motivated it (`Gas::consume_address_access_cost` and its siblings); every other failure
+/// still carries just the [`FailStatus`] it already had. Threading the same precision through the
+/// rest of the interpreter's opcode handlers and all the way out to [`ExecutionResult`] would mean
+/// changing `OpResult` from `Result<(), FailStatus>` to `Result<(), HaltReason>` crate-wide, which
+/// is a much larger, riskier change than gas-accounting alone; this is the first slice of that
+/// work, not the whole of it.
+///
+/// [`ExecutionResult`]: evmc_vm::ExecutionResult
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HaltReason {
+    OutOfGas(OutOfGasReason),
+    /// A failure this enum does not yet refine; carries the [`FailStatus`] it would have been
+    /// reported as before `HaltReason` existed.
+    Other(FailStatus),
+}
+
+impl From<HaltReason> for FailStatus {
+    fn from(reason: HaltReason) -> Self {
+        match reason {
+            HaltReason::OutOfGas(_) => FailStatus::OutOfGas,
+            HaltReason::Other(status) => status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_back_to_the_original_fail_status() {
+        assert_eq!(
+            FailStatus::from(HaltReason::OutOfGas(OutOfGasReason::AddressAccess)),
+            FailStatus::OutOfGas
+        );
+        assert_eq!(
+            FailStatus::from(HaltReason::Other(FailStatus::InvalidMemoryAccess)),
+            FailStatus::InvalidMemoryAccess
+        );
+    }
+}
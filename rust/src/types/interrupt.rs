@@ -0,0 +1,65 @@
+//! Cooperative cancellation for [`Interpreter::run`](crate::interpreter::Interpreter::run),
+//! independent of the EVM gas schedule - an embedder running untrusted bytecode can flip
+//! [`Interrupt`]'s flag (e.g. from a watchdog thread), give it a deadline, or give it a hard op
+//! count, to get an escape hatch out of a gas-heavy but otherwise perfectly valid loop. The op
+//! count in particular is what makes it safe to hand arbitrary bytecode to `Interpreter::run`
+//! from a fuzzer or a static-analysis pipeline: `message.gas()` alone does not bound wall-clock
+//! work if the caller also controls how much gas the message carries.
+
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+/// How many ops `Interpreter::run`/`next` dispatch between checks. Amortizes the atomic load (and
+/// the `Instant::now()` call, when there's a deadline) over a batch instead of paying for them on
+/// every single op.
+pub const CHECK_INTERVAL: u32 = 1024;
+
+/// A cancellation source, checked every [`CHECK_INTERVAL`] ops. `cancelled` is flipped from
+/// outside the running interpreter (e.g. another thread enforcing a timeout); `deadline` is a
+/// self-contained wall-clock budget checked against `Instant::now()`; `max_steps` ([`with_max_steps`
+/// ](Self::with_max_steps)) is a self-contained op-count budget. Any combination is fine - pass
+/// `Arc::new(AtomicBool::new(false))`, `None`, and no `with_max_steps` call for the ones not
+/// wanted.
+#[derive(Clone)]
+pub struct Interrupt {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+    steps_left: Option<Cell<u64>>,
+}
+
+impl Interrupt {
+    pub fn new(cancelled: Arc<AtomicBool>, deadline: Option<Instant>) -> Self {
+        Self {
+            cancelled,
+            deadline,
+            steps_left: None,
+        }
+    }
+
+    /// Opts this `Interrupt` into an additional hard op-count budget: once `max_steps` ops have
+    /// been dispatched, [`tripped`](Self::tripped) reports true same as a flipped flag or a passed
+    /// deadline would, regardless of how much gas is left.
+    pub fn with_max_steps(mut self, max_steps: u64) -> Self {
+        self.steps_left = Some(Cell::new(max_steps));
+        self
+    }
+
+    /// `true` once the flag has been flipped, the deadline has passed, or the op-count budget (if
+    /// any) has been spent. Checking the op-count budget consumes [`CHECK_INTERVAL`] of it, since
+    /// that many ops ran since the last check.
+    pub fn tripped(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            || self.steps_left.as_ref().is_some_and(|steps_left| {
+                let remaining = steps_left.get().saturating_sub(CHECK_INTERVAL as u64);
+                steps_left.set(remaining);
+                remaining == 0
+            })
+    }
+}
@@ -1,4 +1,12 @@
-use std::ops::Deref;
+//! A standalone jump-destination cache, independent of [`CodeAnalysis`](crate::types::CodeAnalysis)'s
+//! own `jumpdest`-bearing analysis cache - this one exists for a caller that wants jump-destination
+//! validity without paying for the rest of `CodeAnalysis` (basic-block splitting, stack-bound
+//! precomputation, fn-ptr conversion). Not currently constructed from anywhere in the interpreter's
+//! hot path, same as [`compiler`](crate::compiler) isn't yet wired into
+//! [`Interpreter::run`](crate::interpreter::Interpreter::run) - both are scaffolding a later change
+//! can build on rather than dead weight to delete.
+
+use std::{ops::Deref, sync::Mutex};
 #[cfg(all(feature = "jump-cache", feature = "thread-local-cache"))]
 use std::rc::Rc;
 #[cfg(all(feature = "jump-cache", not(feature = "thread-local-cache")))]
@@ -52,25 +60,91 @@ impl JumpAnalysis {
         match code_hash {
             Some(code_hash) if code_hash != u256::ZERO => {
                 JUMP_CACHE.get_or_insert(u256Hash(code_hash), || {
-                    JumpAnalysis(AnalysisContainer::from(
-                        compute_code_byte_types(code).as_slice(),
-                    ))
+                    JumpAnalysis(AnalysisContainer::from(compute_code_byte_types(code).as_slice()))
                 })
             }
-            _ => JumpAnalysis(AnalysisContainer::from(
-                compute_code_byte_types(code).as_slice(),
-            )),
+            // Never reaches `JUMP_CACHE`, so every call here would otherwise pay for a fresh
+            // `Vec<CodeByteType>` allocation - `BUFFER_POOL` (below) is what saves that for a
+            // caller making many of these back to back, e.g. a fuzzer replaying same-length
+            // inputs.
+            _ => {
+                let code_byte_types = compute_code_byte_types(code);
+                let analysis = JumpAnalysis(AnalysisContainer::from(code_byte_types.as_slice()));
+                BUFFER_POOL.release(code_byte_types);
+                analysis
+            }
         }
         #[cfg(not(feature = "jump-cache"))]
         JumpAnalysis(compute_code_byte_types(code).into_boxed_slice())
     }
 }
 
+#[cfg(not(feature = "jump-cache"))]
+impl Drop for JumpAnalysis {
+    fn drop(&mut self) {
+        // `self.0` is a `Box<[CodeByteType]>` here (no `jump-cache` feature), so this is the sole
+        // owner and it's about to be freed regardless - hand the backing allocation to
+        // `BUFFER_POOL` instead of letting it deallocate, so the next `JumpAnalysis::new` can skip
+        // its own allocation.
+        let taken = std::mem::replace(&mut self.0, Box::from([]));
+        BUFFER_POOL.release(taken.into_vec());
+    }
+}
+
+/// Recycles the `Vec<CodeByteType>` buffers [`compute_code_byte_types`] would otherwise allocate
+/// fresh on every call, so repeated analysis of same-length (but not necessarily identical) code -
+/// the common case for a fuzzer or a benchmark loop - doesn't pay for an allocation and a free
+/// every time. A `Mutex`-guarded `Vec` rather than a size-indexed structure: [`acquire`
+/// ](Self::acquire) only ever inspects the top buffer, so one that's too small is left in place
+/// instead of searched past, and a miss just falls back to allocating fresh the way this always
+/// used to. This used to be a lock-free Treiber stack (`AtomicPtr` + `Box::from_raw`), but popping
+/// a node and freeing its allocation that way is only safe with hazard pointers or epoch-based
+/// reclamation backing it - without either, two threads racing on the same observed top node could
+/// both read it before either's CAS, and the loser could still be dereferencing it after the
+/// winner's CAS has already freed it: a genuine use-after-free. Unlike
+/// [`TypedArena`](crate::types::arena::TypedArena), whose append-only design never frees or reuses
+/// an individual node and so has no such hazard, this pool's whole point is reclaiming buffers for
+/// reuse, so a mutex (the same approach [`Stack`](crate::types::Stack)'s own `REUSABLE_STACK` uses)
+/// guards a correct, if not lock-free, critical section of just a `Vec::pop`/`push`.
+struct BufferPool(Mutex<Vec<Vec<CodeByteType>>>);
+
+impl BufferPool {
+    const fn new() -> Self {
+        Self(Mutex::new(Vec::new()))
+    }
+
+    /// Pops a spare buffer with capacity for at least `len` elements, ready to be `clear`ed and
+    /// refilled, or `None` if the pool is empty or its top buffer is undersized.
+    fn acquire(&self, len: usize) -> Option<Vec<CodeByteType>> {
+        let mut pool = self.0.lock().unwrap();
+        if pool.last()?.capacity() < len {
+            return None;
+        }
+        pool.pop()
+    }
+
+    /// Pushes `buf` onto the pool for a future [`acquire`](Self::acquire) to reuse.
+    fn release(&self, mut buf: Vec<CodeByteType>) {
+        buf.clear();
+        self.0.lock().unwrap().push(buf);
+    }
+}
+
+static BUFFER_POOL: BufferPool = BufferPool::new();
+
 #[cfg(feature = "jump-cache")]
 const CACHE_SIZE: usize = 1 << 16; // value taken from evmzero
 
+/// 64 rather than [`cache::DEFAULT_SHARDS`](crate::types::cache::DEFAULT_SHARDS)'s default of 16:
+/// jump analysis is looked up on every single call into a contract, making `JUMP_CACHE` one of the
+/// hottest locks in the crate under any parallel workload, so it's worth striping it wider than
+/// the generic default.
 #[cfg(feature = "jump-cache")]
-type JumpCache = Cache<CACHE_SIZE, u256Hash, JumpAnalysis, BuildNoHashHasher<u64>>;
+const JUMP_CACHE_SHARDS: usize = 64;
+
+#[cfg(feature = "jump-cache")]
+type JumpCache =
+    Cache<CACHE_SIZE, u256Hash, JumpAnalysis, BuildNoHashHasher<u64>, JUMP_CACHE_SHARDS>;
 
 #[cfg(all(feature = "jump-cache", not(feature = "thread-local-cache")))]
 static JUMP_CACHE: JumpCache = JumpCache::new();
@@ -81,7 +155,8 @@ thread_local! {
 }
 
 fn compute_code_byte_types(code: &[u8]) -> Vec<CodeByteType> {
-    let mut code_byte_types = vec![CodeByteType::DataOrInvalid; code.len()];
+    let mut code_byte_types = BUFFER_POOL.acquire(code.len()).unwrap_or_default();
+    code_byte_types.resize(code.len(), CodeByteType::DataOrInvalid);
 
     let mut pc = 0;
     while pc < code.len() {
@@ -190,4 +265,14 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn compute_code_byte_types_reuses_pooled_buffers_without_leaking_stale_entries() {
+        // Not asserting on pooling itself (it's an opportunistic, global, test-order-dependent
+        // resource) - just that a reused buffer, when it happens to be reused, never leaks stale
+        // entries from a differently-shaped previous call into the new one.
+        let _ = compute_code_byte_types(&[Opcode::Push1 as u8, Opcode::Add as u8]);
+        let second = compute_code_byte_types(&[Opcode::JumpDest as u8]);
+        assert_eq!(*second, [CodeByteType::JumpDest]);
+    }
 }
@@ -1,83 +1,169 @@
-#[cfg(feature = "alloc-reuse")]
-use std::sync::Mutex;
-use std::{cmp::max, iter};
+use std::cmp::max;
 
 use crate::{
     types::{u256, FailStatus},
-    utils::{word_size, Gas},
+    utils::{word_size, Gas, GasSchedule},
 };
+#[cfg(all(feature = "mmap-memory", unix))]
+use crate::types::mmap_buffer::MmapBuffer as Buffer;
+#[cfg(not(all(feature = "mmap-memory", unix)))]
+use vec_buffer::VecBuffer as Buffer;
+
+/// A plain, growable `Vec<u8>`, used wherever [`MmapBuffer`](crate::types::mmap_buffer::MmapBuffer)
+/// is unavailable: the `mmap-memory` feature is disabled, or the target isn't Unix.
+#[cfg(not(all(feature = "mmap-memory", unix)))]
+mod vec_buffer {
+    #[cfg(feature = "alloc-reuse")]
+    use std::cell::RefCell;
+    use std::iter;
+
+    // Buffers are pooled per-thread rather than behind one global lock, so concurrently executing
+    // `EvmRs` instances never contend on allocation/deallocation; each thread just grows its own
+    // pool and reuses from it. This predates, and is deliberately simpler than,
+    // `buffer_pool::BufferPool`'s lock-free, size-classed, cross-thread pool added for the
+    // `CodeReader` analysis and output buffers: a VM instance's memory is only ever touched by the
+    // one thread running it, so there is nothing to gain from sharing it across threads, and a
+    // single unclassed per-thread `Vec<Vec<u8>>` is simpler than a size-classed Treiber stack for a
+    // resource nothing else ever contends on.
+    #[cfg(feature = "alloc-reuse")]
+    const REUSABLE_MEMORY_POOL_CAPACITY: usize = 16;
+    // A thread that once ran a contract which grew its memory to several MiB shouldn't keep that
+    // allocation around in its pool forever on the chance of needing it again; buffers larger than
+    // this are freed instead of pooled.
+    #[cfg(feature = "alloc-reuse")]
+    const REUSABLE_MEMORY_MAX_CAPACITY: usize = 1024 * 1024;
+
+    #[cfg(feature = "alloc-reuse")]
+    thread_local! {
+        static REUSABLE_MEMORY: RefCell<Vec<Vec<u8>>> = RefCell::new(Vec::new());
+    }
 
-#[cfg(feature = "alloc-reuse")]
-static REUSABLE_MEMORY: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::new());
+    #[derive(Debug)]
+    pub(super) struct VecBuffer(Vec<u8>);
+
+    impl VecBuffer {
+        pub(super) fn new() -> Self {
+            #[cfg(not(feature = "alloc-reuse"))]
+            let mut m = Vec::new();
+            #[cfg(feature = "alloc-reuse")]
+            let mut m =
+                REUSABLE_MEMORY.with(|pool| pool.borrow_mut().pop().unwrap_or_default());
+            m.clear();
+            Self(m)
+        }
 
-#[derive(Debug)]
-pub struct Memory(Vec<u8>);
-
-#[cfg(feature = "alloc-reuse")]
-impl Drop for Memory {
-    fn drop(&mut self) {
-        let mut memory = Vec::new();
-        std::mem::swap(&mut memory, &mut self.0);
-        REUSABLE_MEMORY.lock().unwrap().push(memory);
+        pub(super) fn as_slice(&self) -> &[u8] {
+            self.0.as_slice()
+        }
+
+        pub(super) fn as_mut_slice(&mut self) -> &mut [u8] {
+            self.0.as_mut_slice()
+        }
+
+        pub(super) fn grow_zeroed(&mut self, new_len: usize) {
+            let current_len = self.0.len();
+            if new_len > current_len {
+                self.0.extend(iter::repeat(0).take(new_len - current_len));
+            }
+        }
     }
+
+    #[cfg(feature = "alloc-reuse")]
+    impl Drop for VecBuffer {
+        fn drop(&mut self) {
+            let mut memory = Vec::new();
+            std::mem::swap(&mut memory, &mut self.0);
+            if memory.capacity() > REUSABLE_MEMORY_MAX_CAPACITY {
+                return;
+            }
+            REUSABLE_MEMORY.with(|pool| {
+                let mut pool = pool.borrow_mut();
+                if pool.len() < REUSABLE_MEMORY_POOL_CAPACITY {
+                    pool.push(memory);
+                }
+            });
+        }
+    }
+}
+
+fn memory_cost(size: u64) -> Result<u64, FailStatus> {
+    let word_size = word_size(size)?;
+    let (pow2, pow2_overflow) = word_size.overflowing_pow(2);
+    let (word_size_3, word_size_3_overflow) = word_size.overflowing_mul(3);
+    let (cost, cost_overflow) = (pow2 / 512).overflowing_add(word_size_3);
+    if pow2_overflow || word_size_3_overflow || cost_overflow {
+        return Err(FailStatus::OutOfGas);
+    };
+    Ok(cost)
+}
+
+/// EVM memory for one call frame. Backed by [`MmapBuffer`](crate::types::mmap_buffer::MmapBuffer)
+/// on Unix targets with the `mmap-memory` feature enabled, committing fresh OS-zeroed pages on
+/// demand instead of reallocating and memset-ing like a `Vec` would; a plain `Vec<u8>` everywhere
+/// else. Both expose the same API here, so nothing outside this module needs to know which one is
+/// in use.
+#[derive(Debug)]
+pub struct Memory {
+    buffer: Buffer,
+    /// `memory_cost(buffer.len())`, kept up to date alongside `buffer` so that `reserve_cost`
+    /// only has to evaluate `memory_cost` once per call (for the new length) instead of once for
+    /// the new length and once to re-derive the cost already paid for the current one.
+    current_cost: u64,
 }
 
 impl Memory {
     pub fn new(memory: &[u8]) -> Self {
-        #[cfg(not(feature = "alloc-reuse"))]
-        let mut m = Vec::new();
-        #[cfg(feature = "alloc-reuse")]
-        let mut m = REUSABLE_MEMORY.lock().unwrap().pop().unwrap_or_default();
-        m.clear();
-
-        m.extend_from_slice(memory);
-        Self(m)
+        let mut buffer = Buffer::new();
+        buffer.grow_zeroed(memory.len());
+        buffer.as_mut_slice()[..memory.len()].copy_from_slice(memory);
+        let current_cost = memory_cost(memory.len() as u64)
+            .expect("initial memory length never overflows its gas cost");
+        Self { buffer, current_cost }
     }
 
     pub fn as_slice(&self) -> &[u8] {
-        self.0.as_slice()
+        self.buffer.as_slice()
     }
 
     pub fn len(&self) -> u64 {
-        self.0.len() as u64
+        self.buffer.as_slice().len() as u64
     }
 
-    fn expand(&mut self, new_len_bytes: u64, gas_left: &mut Gas) -> Result<(), FailStatus> {
-        #[cold]
-        fn expand_raw(m: &mut Memory, new_len: u64, gas_left: &mut Gas) -> Result<(), FailStatus> {
-            let current_len = m.0.len() as u64;
-            m.consume_expansion_cost(new_len, gas_left)?;
-            m.0.extend(iter::repeat(0).take((new_len - current_len) as usize));
-            Ok(())
+    /// The marginal gas cost of growing memory to cover `offset..offset+len`, rounded up to the
+    /// next whole word, without charging `gas_left` or touching the backing buffer. Callers that
+    /// might still abort for an unrelated reason (a later stack check, a static-call write
+    /// violation, ...) can compute and even charge this cost up front and only call `grow_to` to
+    /// commit the allocation once the access is known to go ahead - so a reverting path never
+    /// pays for or performs it.
+    pub fn reserve_cost(&self, offset: u256, len: u64) -> Result<u64, FailStatus> {
+        if len == 0 {
+            return Ok(0);
         }
-
-        let current_len = self.0.len() as u64;
-        let new_len = word_size(new_len_bytes)? * 32; // word_size just did a division by 32 so * will not overflow
-        if new_len > current_len {
-            expand_raw(self, new_len, gas_left)?;
+        let (offset, offset_overflow) = offset.into_u64_with_overflow();
+        let (end, end_overflow) = offset.overflowing_add(len);
+        if offset_overflow || end_overflow {
+            return Err(FailStatus::OutOfGas);
         }
-        Ok(())
-    }
-
-    fn consume_expansion_cost(&self, new_len: u64, gas_left: &mut Gas) -> Result<(), FailStatus> {
-        fn memory_cost(size: u64) -> Result<u64, FailStatus> {
-            let word_size = word_size(size)?;
-            let (pow2, pow2_overflow) = word_size.overflowing_pow(2);
-            let (word_size_3, word_size_3_overflow) = word_size.overflowing_mul(3);
-            let (cost, cost_overflow) = (pow2 / 512).overflowing_add(word_size_3);
-            if pow2_overflow || word_size_3_overflow || cost_overflow {
-                return Err(FailStatus::OutOfGas);
-            };
-            Ok(cost)
+        let current_len = self.buffer.as_slice().len() as u64;
+        let new_len = word_size(end)? * 32; // word_size just did a division by 32 so * will not overflow
+        if new_len <= current_len {
+            return Ok(0);
         }
+        let new_cost = memory_cost(new_len)?;
+        Ok(new_cost - self.current_cost)
+    }
 
-        let current_len = self.0.len() as u64;
-
-        if new_len > current_len {
-            let memory_expansion_cost = memory_cost(new_len)? - memory_cost(current_len)?;
-            gas_left.consume(memory_expansion_cost)?;
+    /// Grows the backing buffer to `new_len_bytes` and zero-fills the new bytes, if it isn't
+    /// already that large. `new_len_bytes` must be the same word-rounded length `reserve_cost`
+    /// was just asked about; this never fails or re-derives gas cost, it only resizes.
+    #[cold]
+    pub fn grow_to(&mut self, new_len_bytes: u64) {
+        let current_len = self.buffer.as_slice().len() as u64;
+        if new_len_bytes > current_len {
+            self.current_cost = memory_cost(new_len_bytes)
+                .expect("reserve_cost already validated this length");
+            self.buffer.grow_zeroed(new_len_bytes as usize);
         }
-        Ok(())
     }
 
     pub fn get_mut_slice(
@@ -89,21 +175,33 @@ impl Memory {
         if len == 0 {
             return Ok(&mut []);
         }
-        let (offset, offset_overflow) = offset.into_u64_with_overflow();
-        let (end, end_overflow) = offset.overflowing_add(len);
-        if offset_overflow || end_overflow {
-            return Err(FailStatus::OutOfGas);
+        let cost = self.reserve_cost(offset, len)?;
+        gas_left.consume(cost)?;
+
+        // Already validated not to overflow by the successful `reserve_cost` call above.
+        let (offset, _) = offset.into_u64_with_overflow();
+        let end = offset + len;
+        if cost > 0 {
+            let new_len = word_size(end).expect("already validated by reserve_cost") * 32;
+            self.grow_to(new_len);
         }
-        self.expand(end, gas_left)?;
 
         let offset = offset as usize;
         let end = end as usize;
         unsafe {
-            std::hint::assert_unchecked(offset < end && end <= self.0.len());
+            std::hint::assert_unchecked(offset < end && end <= self.buffer.as_slice().len());
         }
-        Ok(&mut self.0[offset..end])
+        Ok(&mut self.buffer.as_mut_slice()[offset..end])
     }
 
+    /// Loads the 32 bytes at `offset` as a [`u256`], interpreted in EVM's canonical big-endian
+    /// word order. Unlike the stack - which already holds `u256` in its native little-endian limb
+    /// layout end-to-end, with no swap anywhere - this buffer is plain bytes that other opcodes
+    /// address and slice at arbitrary, non-word-aligned granularity (`MSTORE8`, `CALLDATACOPY`,
+    /// `KECCAK256`, `RETURNDATACOPY`, ...), so it has to stay in canonical byte order throughout;
+    /// there's no native-endian layout this could store instead without corrupting those. The
+    /// [`u256::from_be_bytes`](crate::types::u256::from_be_bytes) call below is already the single
+    /// swap this access needs, not a repeated one: `u256`'s own arithmetic never touches it.
     pub fn get_word(&mut self, offset: u256, gas_left: &mut Gas) -> Result<u256, FailStatus> {
         let slice = self.get_mut_slice(offset, 32, gas_left)?;
         // SAFETY:
@@ -127,6 +225,7 @@ impl Memory {
         dest_offset: u256,
         len: u256,
         gas_left: &mut Gas,
+        gas_schedule: &GasSchedule,
     ) -> Result<(), FailStatus> {
         let (src_offset, src_overflow) = src_offset.into_u64_with_overflow();
         let (dest_offset, dest_overflow) = dest_offset.into_u64_with_overflow();
@@ -135,12 +234,18 @@ impl Memory {
         if src_overflow || dest_overflow || len_overflow || end_overflow {
             return Err(FailStatus::OutOfGas);
         }
-        gas_left.consume_copy_cost(len)?;
-        self.expand(end, gas_left)?;
+        gas_left.consume_copy_cost(len, gas_schedule)?;
+        let cost = self.reserve_cost(u256::ZERO, end)?;
+        gas_left.consume(cost)?;
+        if cost > 0 {
+            let new_len = word_size(end).expect("already validated above") * 32;
+            self.grow_to(new_len);
+        }
         let src_offset = src_offset as usize;
         let dest_offset = dest_offset as usize;
         let len = len as usize;
-        self.0
+        self.buffer
+            .as_mut_slice()
             .copy_within(src_offset..src_offset + len, dest_offset); // + does not overflow
         Ok(())
     }
@@ -148,9 +253,11 @@ impl Memory {
 
 #[cfg(test)]
 mod tests {
+    use evmc_vm::Revision;
+
     use crate::{
         types::{memory::Memory, u256, FailStatus},
-        utils::Gas,
+        utils::{Gas, GasSchedule},
     };
 
     #[test]
@@ -161,57 +268,59 @@ mod tests {
     }
 
     #[test]
-    fn expand() {
+    fn grow_to() {
         let mut memory = Memory::new(&[]);
-        assert_eq!(memory.expand(1, &mut Gas::new(1_000)), Ok(()));
-        assert_eq!(memory.as_slice(), [0; 32]);
-
-        let mut memory = Memory::new(&[]);
-        assert_eq!(memory.expand(32, &mut Gas::new(1_000)), Ok(()));
+        memory.grow_to(32);
         assert_eq!(memory.as_slice(), [0; 32]);
 
         let mut memory = Memory::new(&[1; 32]);
-        assert_eq!(memory.expand(64, &mut Gas::new(1_000)), Ok(()));
+        memory.grow_to(64);
         assert_eq!(memory.as_slice(), {
             let mut mem = [1; 64];
             mem[32..].copy_from_slice(&[0; 32]);
             mem
         });
 
-        let mut memory = Memory::new(&[]);
-        assert_eq!(
-            memory.expand(u64::MAX, &mut Gas::new(1_000)),
-            Err(FailStatus::OutOfGas)
-        );
+        // A no-op when memory is already at least that large.
+        let mut memory = Memory::new(&[1; 32]);
+        memory.grow_to(1);
+        assert_eq!(memory.as_slice(), [1; 32]);
     }
 
     #[test]
-    fn consume_expansion_cost() {
+    fn reserve_cost() {
         let memory = Memory::new(&[]);
-        let mut gas_left = Gas::new(0);
-        assert_eq!(memory.consume_expansion_cost(0, &mut gas_left), Ok(()));
-        assert_eq!(gas_left, 0);
+        assert_eq!(memory.reserve_cost(u256::ZERO, 0), Ok(0));
 
-        let mut gas_left = Gas::new(3);
-        assert_eq!(memory.consume_expansion_cost(1, &mut gas_left), Ok(()));
-        assert_eq!(gas_left, 0);
+        let memory = Memory::new(&[]);
+        assert_eq!(memory.reserve_cost(u256::ZERO, 1), Ok(3));
 
-        let mut gas_left = Gas::new(3);
-        assert_eq!(memory.consume_expansion_cost(32, &mut gas_left), Ok(()));
-        assert_eq!(gas_left, 0);
+        let memory = Memory::new(&[]);
+        assert_eq!(memory.reserve_cost(u256::ZERO, 32), Ok(3));
 
         let memory = Memory::new(&[0; 32]);
-        let mut gas_left = Gas::new(3);
-        assert_eq!(memory.consume_expansion_cost(64, &mut gas_left), Ok(()));
-        assert_eq!(gas_left, 0);
+        assert_eq!(memory.reserve_cost(u256::ZERO, 64), Ok(3));
+
+        // Asking again after growing only reports the cost of the newly reached length, on top
+        // of what was already paid for the current one - this is what the cached `current_cost`
+        // is for.
+        let mut memory = Memory::new(&[0; 32]);
+        memory.grow_to(64);
+        assert_eq!(memory.reserve_cost(u256::ZERO, 96), Ok(3));
+
+        // Computing the cost never touches the backing buffer, so it can be called speculatively
+        // without committing to the allocation.
+        assert_eq!(memory.len(), 64);
 
+        let memory = Memory::new(&[0; 32]);
         assert_eq!(
-            memory.consume_expansion_cost(u64::MAX, &mut Gas::new(10_000)),
+            memory.reserve_cost(u256::ZERO, u64::MAX),
             Err(FailStatus::OutOfGas)
         );
 
+        let memory = Memory::new(&[0; 32]);
         assert_eq!(
-            memory.consume_expansion_cost(u64::MAX / 100, &mut Gas::new(10_000)),
+            memory.reserve_cost(u256::ZERO, u64::MAX / 100),
             Err(FailStatus::OutOfGas)
         );
     }
@@ -361,45 +470,47 @@ mod tests {
 
     #[test]
     fn copy_within() {
+        let schedule = GasSchedule::for_revision(Revision::EVMC_CANCUN);
+
         let mut mem = Memory::new(&[]);
         let mut gas_left = Gas::new(0);
         assert_eq!(
-            mem.copy_within(u256::ZERO, u256::ZERO, u256::ZERO, &mut gas_left),
+            mem.copy_within(u256::ZERO, u256::ZERO, u256::ZERO, &mut gas_left, &schedule),
             Ok(())
         );
 
         let mut mem = Memory::new(&[]);
         let mut gas_left = Gas::new(0);
         assert_eq!(
-            mem.copy_within(u256::ONE, u256::ZERO, u256::ZERO, &mut gas_left),
+            mem.copy_within(u256::ONE, u256::ZERO, u256::ZERO, &mut gas_left, &schedule),
             Err(FailStatus::OutOfGas)
         );
 
         let mut mem = Memory::new(&[]);
         let mut gas_left = Gas::new(0);
         assert_eq!(
-            mem.copy_within(u256::ZERO, u256::ONE, u256::ZERO, &mut gas_left),
+            mem.copy_within(u256::ZERO, u256::ONE, u256::ZERO, &mut gas_left, &schedule),
             Err(FailStatus::OutOfGas)
         );
 
         let mut mem = Memory::new(&[]);
         let mut gas_left = Gas::new(0);
         assert_eq!(
-            mem.copy_within(u256::ZERO, u256::ZERO, u256::ONE, &mut gas_left),
+            mem.copy_within(u256::ZERO, u256::ZERO, u256::ONE, &mut gas_left, &schedule),
             Err(FailStatus::OutOfGas)
         );
 
         let mut mem = Memory::new(&[]);
         let mut gas_left = Gas::new(1_000_000);
         assert_eq!(
-            mem.copy_within(u256::MAX, u256::ZERO, u256::ZERO, &mut gas_left),
+            mem.copy_within(u256::MAX, u256::ZERO, u256::ZERO, &mut gas_left, &schedule),
             Err(FailStatus::OutOfGas)
         );
 
         let mut mem = Memory::new(&[]);
         let mut gas_left = Gas::new(3 + 3);
         assert_eq!(
-            mem.copy_within(u256::ZERO, u256::ZERO, u256::ONE, &mut gas_left),
+            mem.copy_within(u256::ZERO, u256::ZERO, u256::ONE, &mut gas_left, &schedule),
             Ok(())
         );
         assert_eq!(gas_left, 0);
@@ -407,7 +518,7 @@ mod tests {
         let mut mem = Memory::new(&[1; 32]);
         let mut gas_left = Gas::new(3);
         assert_eq!(
-            mem.copy_within(u256::ZERO, u256::ZERO, u256::ONE, &mut gas_left),
+            mem.copy_within(u256::ZERO, u256::ZERO, u256::ONE, &mut gas_left, &schedule),
             Ok(())
         );
         assert_eq!(gas_left, 0);
@@ -415,7 +526,7 @@ mod tests {
         let mut mem = Memory::new(&[1; 32]);
         let mut gas_left = Gas::new(3 + 6);
         assert_eq!(
-            mem.copy_within(u256::ZERO, u256::ZERO, 33u8.into(), &mut gas_left),
+            mem.copy_within(u256::ZERO, u256::ZERO, 33u8.into(), &mut gas_left, &schedule),
             Ok(())
         );
         assert_eq!(gas_left, 0);
@@ -423,7 +534,7 @@ mod tests {
         let mut mem = Memory::new(&[1; 32]);
         let mut gas_left = Gas::new(3 + 3);
         assert_eq!(
-            mem.copy_within(32u8.into(), u256::ZERO, u256::ONE, &mut gas_left),
+            mem.copy_within(32u8.into(), u256::ZERO, u256::ONE, &mut gas_left, &schedule),
             Ok(())
         );
         assert_eq!(gas_left, 0);
@@ -431,7 +542,7 @@ mod tests {
         let mut mem = Memory::new(&[1; 32]);
         let mut gas_left = Gas::new(3 + 3);
         assert_eq!(
-            mem.copy_within(u256::ZERO, 32u8.into(), u256::ONE, &mut gas_left),
+            mem.copy_within(u256::ZERO, 32u8.into(), u256::ONE, &mut gas_left, &schedule),
             Ok(())
         );
         assert_eq!(gas_left, 0);
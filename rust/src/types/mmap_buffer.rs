@@ -0,0 +1,195 @@
+//! An mmap-reserved, lazily-committed byte buffer: the `mmap-memory` backing for [`Memory`] on
+//! Unix targets.
+//!
+//! [`Memory`]: crate::types::Memory
+//!
+//! A contract's EVM memory only ever grows, and growing a `Vec<u8>` means reallocating and
+//! memset-ing the whole new region on every expansion past capacity. [`MmapBuffer`] instead
+//! reserves a large virtual address range once, up front, and [`grow_zeroed`](Self::grow_zeroed)
+//! only `mprotect`s the additional pages the new length needs into `PROT_READ | PROT_WRITE` -
+//! the OS hands back demand-zeroed pages the first time each is touched, so there is nothing for
+//! this type itself to memset or copy.
+//!
+//! The hand-written `extern "C"` declarations below (rather than a `libc`-style dependency) match
+//! how the rest of this crate's `ffi` module talks to a C ABI directly. The numeric constants are
+//! the Linux/glibc values; other Unix targets (notably macOS, whose `MAP_ANON` bit differs) are
+//! not yet accounted for and will pick up whatever `mmap`/`mprotect` happen to resolve to at link
+//! time, which is unsound on a target where the values differ - narrowing the `cfg` on this module
+//! to the targets actually verified is a follow-up, not something to guess at here.
+
+use std::{
+    ffi::c_void,
+    fmt,
+    ptr::{self, NonNull},
+};
+
+/// Upper bound on the virtual range reserved for one call frame's memory. EVM memory expansion
+/// cost grows quadratically with length, so no contract that could actually afford to grow this
+/// far would be payable by any realistic block gas limit; reserving it costs address space only,
+/// not physical memory, so sizing it generously is free.
+const RESERVED_BYTES: usize = 4 * 1024 * 1024 * 1024; // 4 GiB
+
+const PROT_NONE: i32 = 0;
+const PROT_READ: i32 = 1;
+const PROT_WRITE: i32 = 2;
+const MAP_PRIVATE: i32 = 0x02;
+const MAP_ANONYMOUS: i32 = 0x20;
+const _SC_PAGESIZE: i32 = 30;
+
+extern "C" {
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: i32,
+        flags: i32,
+        fd: i32,
+        offset: i64,
+    ) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> i32;
+    fn mprotect(addr: *mut c_void, len: usize, prot: i32) -> i32;
+    fn sysconf(name: i32) -> i64;
+}
+
+pub struct MmapBuffer {
+    base: NonNull<u8>,
+    /// How much of the reservation is currently `mprotect`'d to `PROT_READ | PROT_WRITE`, rounded
+    /// up to a whole number of pages. Always `>= len`.
+    committed: usize,
+    /// The logical length `Memory` has grown to, i.e. what [`as_slice`](Self::as_slice) exposes.
+    len: usize,
+    page_size: usize,
+}
+
+// SAFETY: `base` points at a private anonymous mapping this value exclusively owns; no other
+// handle to it exists, so moving it between threads, or accessing it under Rust's normal
+// `&`/`&mut` aliasing rules from a different thread than it was created on, is sound.
+unsafe impl Send for MmapBuffer {}
+unsafe impl Sync for MmapBuffer {}
+
+impl MmapBuffer {
+    pub fn new() -> Self {
+        // SAFETY: querying the page size performs no memory access.
+        let page_size = unsafe { sysconf(_SC_PAGESIZE) } as usize;
+        // SAFETY: a `PROT_NONE` private anonymous mapping reserves address space without reading
+        // or writing any memory; `ptr::null_mut()` lets the kernel choose the address.
+        let base = unsafe {
+            mmap(
+                ptr::null_mut(),
+                RESERVED_BYTES,
+                PROT_NONE,
+                MAP_PRIVATE | MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        assert!(
+            !base.is_null() && base as isize != -1,
+            "failed to reserve {RESERVED_BYTES} bytes of address space for EVM memory",
+        );
+        Self {
+            // SAFETY: just checked `base` is a valid, non-null mapping.
+            base: unsafe { NonNull::new_unchecked(base.cast()) },
+            committed: 0,
+            len: 0,
+            page_size,
+        }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        // SAFETY: `[0, len)` is within `[0, committed)`, which has been `mprotect`'d to
+        // `PROT_READ | PROT_WRITE` and belongs to this mapping for the lifetime of `self`.
+        unsafe { std::slice::from_raw_parts(self.base.as_ptr(), self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        // SAFETY: see `as_slice`; `self` is borrowed mutably so no other reference can alias it.
+        unsafe { std::slice::from_raw_parts_mut(self.base.as_ptr(), self.len) }
+    }
+
+    /// Grow to exactly `new_len` bytes, `mprotect`-ing any additional pages `new_len` needs.
+    /// `new_len` is a logical length, not a page count; bytes `[len, new_len)` read as zero,
+    /// whether because the underlying page was just committed (demand-zeroed by the OS on first
+    /// touch) or because it was already committed by an earlier, now-shrunk length (`Memory` never
+    /// shrinks within a call frame, but this still zeroes defensively rather than assume that).
+    pub fn grow_zeroed(&mut self, new_len: usize) {
+        let old_committed = self.committed;
+        if new_len > self.committed {
+            assert!(
+                new_len <= RESERVED_BYTES,
+                "EVM memory length {new_len} exceeded the {RESERVED_BYTES}-byte reserved range",
+            );
+            let new_committed = new_len.next_multiple_of(self.page_size);
+            // SAFETY: `new_committed <= RESERVED_BYTES`, so this stays within the mapping reserved
+            // in `new`.
+            let result = unsafe {
+                mprotect(
+                    self.base.as_ptr().cast(),
+                    new_committed,
+                    PROT_READ | PROT_WRITE,
+                )
+            };
+            assert_eq!(result, 0, "failed to commit {new_committed} bytes of EVM memory");
+            self.committed = new_committed;
+        }
+        // Pages committed just now are demand-zeroed; only the part that was already committed
+        // can still hold bytes from an earlier, longer length.
+        let stale_end = new_len.min(old_committed);
+        if stale_end > self.len {
+            // SAFETY: `[len, stale_end)` lies within `[0, committed)`, which is
+            // `PROT_READ | PROT_WRITE` and belongs to this mapping.
+            unsafe { ptr::write_bytes(self.base.as_ptr().add(self.len), 0, stale_end - self.len) };
+        }
+        self.len = new_len;
+    }
+}
+
+impl Drop for MmapBuffer {
+    fn drop(&mut self) {
+        // SAFETY: `base`/`RESERVED_BYTES` describe exactly the mapping created in `new`, which
+        // this value exclusively owns.
+        unsafe {
+            munmap(self.base.as_ptr().cast(), RESERVED_BYTES);
+        }
+    }
+}
+
+impl fmt::Debug for MmapBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MmapBuffer")
+            .field("len", &self.len)
+            .field("committed", &self.committed)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MmapBuffer;
+
+    #[test]
+    fn freshly_grown_memory_is_zeroed() {
+        let mut buf = MmapBuffer::new();
+        buf.grow_zeroed(64);
+        assert_eq!(buf.as_slice(), [0; 64]);
+    }
+
+    #[test]
+    fn growth_preserves_existing_bytes() {
+        let mut buf = MmapBuffer::new();
+        buf.grow_zeroed(32);
+        buf.as_mut_slice().fill(0xAB);
+        buf.grow_zeroed(64);
+        assert_eq!(&buf.as_slice()[..32], [0xAB; 32]);
+        assert_eq!(&buf.as_slice()[32..], [0; 32]);
+    }
+
+    #[test]
+    fn growth_across_a_page_boundary_commits_fresh_pages() {
+        let mut buf = MmapBuffer::new();
+        buf.grow_zeroed(1);
+        let page_size = buf.page_size;
+        buf.grow_zeroed(page_size + 1);
+        assert_eq!(buf.as_slice().len(), page_size + 1);
+        assert!(buf.as_slice().iter().all(|&b| b == 0));
+    }
+}
@@ -0,0 +1,410 @@
+//! A stateful, in-memory [`ExecutionContextTrait`] implementation for tests.
+//!
+//! Unlike [`MockExecutionContextTrait`](crate::types::MockExecutionContextTrait), which only
+//! records an ordered sequence of `expect_*` calls via `mockall`, [`MockedHost`] keeps real
+//! backing state, so it can execute bytecode that reads back what it previously wrote (repeated
+//! `SLOAD`/`SSTORE`, `BALANCE`, `CALL`, ...).
+
+use std::collections::HashMap;
+
+use evmc_vm::{
+    AccessStatus, Address, ExecutionMessage, ExecutionResult, StatusCode, StorageStatus, Uint256,
+};
+
+use crate::types::{u256, ExecutionContextTrait, ExecutionTxContext};
+
+/// A single emitted log, recorded verbatim for later assertions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockedLog {
+    pub address: Address,
+    pub data: Vec<u8>,
+    pub topics: Vec<Uint256>,
+}
+
+/// The account-level state tracked by [`MockedHost`].
+#[derive(Debug, Clone, Default)]
+pub struct MockedAccount {
+    pub balance: Uint256,
+    pub code: Vec<u8>,
+    pub code_hash: Uint256,
+    pub nonce: u64,
+    pub storage: HashMap<Uint256, Uint256>,
+}
+
+/// A stateful, in-memory host implementing [`ExecutionContextTrait`].
+///
+/// An account exists once [`MockedHost::set_account`], `set_code`, `set_storage_at` or a
+/// `set_storage` write has created it, or a `selfdestruct` has credited it as beneficiary. Every
+/// other account referenced via `get_balance`/`get_code_size`/... is treated as non-existent,
+/// matching EVMC semantics (`account_exists` returns `false`, reads return zero).
+#[derive(Debug, Default)]
+pub struct MockedHost {
+    pub accounts: HashMap<Address, MockedAccount>,
+    pub transient_storage: HashMap<(Address, Uint256), Uint256>,
+    pub logs: Vec<MockedLog>,
+    pub warm_accounts: Vec<Address>,
+    pub warm_storage: Vec<(Address, Uint256)>,
+    pub block_hashes: HashMap<i64, Uint256>,
+    pub call_result: ExecutionResult,
+    pub tx_context: ExecutionTxContext<'static>,
+}
+
+impl MockedHost {
+    pub fn new() -> Self {
+        Self {
+            call_result: ExecutionResult::new(StatusCode::EVMC_SUCCESS, 0, 0, None),
+            tx_context: ExecutionTxContext {
+                tx_gas_price: u256::ZERO.into(),
+                tx_origin: u256::ZERO.into(),
+                block_coinbase: u256::ZERO.into(),
+                block_number: 0,
+                block_timestamp: 0,
+                block_gas_limit: 0,
+                block_prev_randao: u256::ZERO.into(),
+                chain_id: u256::ZERO.into(),
+                block_base_fee: u256::ZERO.into(),
+                blob_base_fee: u256::ZERO.into(),
+                blob_hashes: &[],
+                initcodes: &[],
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn set_account(&mut self, address: Address, account: MockedAccount) {
+        self.accounts.insert(address, account);
+    }
+
+    /// Seeds `address`'s code, creating the account (with zero balance/nonce) if it doesn't
+    /// exist yet.
+    pub fn set_code(&mut self, address: Address, code: Vec<u8>) {
+        self.accounts.entry(address).or_default().code = code;
+    }
+
+    /// Seeds a single storage slot, creating the account if it doesn't exist yet. Unlike
+    /// [`ExecutionContextTrait::set_storage`], this skips the [`StorageStatus`] bookkeeping a
+    /// `SSTORE` needs, since a caller seeding pre-execution state doesn't have a "current value"
+    /// to diff against.
+    pub fn set_storage_at(&mut self, address: Address, key: Uint256, value: Uint256) {
+        self.accounts
+            .entry(address)
+            .or_default()
+            .storage
+            .insert(key, value);
+    }
+
+    /// The full post-execution storage map for `address`, for asserting against in tests -
+    /// `None` if the account was never touched.
+    pub fn storage_of(&self, address: &Address) -> Option<&HashMap<Uint256, Uint256>> {
+        self.accounts.get(address).map(|account| &account.storage)
+    }
+
+    /// Clear all transient storage, as happens at the end of a transaction (EIP-1153).
+    pub fn clear_transient_storage(&mut self) {
+        self.transient_storage.clear();
+    }
+
+    /// Capture the state mutated by `set_storage`/`set_transient_storage`/`emit_log`/
+    /// `selfdestruct`, to restore later via [`revert_to`](Self::revert_to). This is what a real
+    /// EVMC host is required to do internally when a call frame it's driving returns
+    /// `EVMC_REVERT` or fails; `MockedHost` doesn't do it automatically (nothing here calls this
+    /// on its own), so a test exercising revert behavior takes its own checkpoint before the call
+    /// and reverts to it afterward based on the returned status code.
+    ///
+    /// EIP-2929 warm/cold access-list status is deliberately not part of this snapshot: a real
+    /// host doesn't roll that back on `REVERT` either, since it's the *interpreter's own*
+    /// [`Journal`](crate::interpreter::journal::Journal) that's responsible for undoing it.
+    pub fn checkpoint(&self) -> HostCheckpoint {
+        HostCheckpoint {
+            accounts: self.accounts.clone(),
+            transient_storage: self.transient_storage.clone(),
+            logs: self.logs.clone(),
+        }
+    }
+
+    /// Restore state captured by [`checkpoint`](Self::checkpoint).
+    pub fn revert_to(&mut self, checkpoint: HostCheckpoint) {
+        self.accounts = checkpoint.accounts;
+        self.transient_storage = checkpoint.transient_storage;
+        self.logs = checkpoint.logs;
+    }
+}
+
+/// An opaque snapshot returned by [`MockedHost::checkpoint`].
+#[derive(Debug, Clone)]
+pub struct HostCheckpoint {
+    accounts: HashMap<Address, MockedAccount>,
+    transient_storage: HashMap<(Address, Uint256), Uint256>,
+    logs: Vec<MockedLog>,
+}
+
+impl ExecutionContextTrait for MockedHost {
+    fn get_tx_context(&mut self) -> &ExecutionTxContext {
+        &self.tx_context
+    }
+
+    fn account_exists(&self, address: &Address) -> bool {
+        self.accounts.contains_key(address)
+    }
+
+    fn get_storage(&self, address: &Address, key: &Uint256) -> Uint256 {
+        self.accounts
+            .get(address)
+            .and_then(|account| account.storage.get(key))
+            .copied()
+            .unwrap_or(u256::ZERO.into())
+    }
+
+    fn set_storage(&mut self, address: &Address, key: &Uint256, value: &Uint256) -> StorageStatus {
+        let account = self.accounts.entry(*address).or_default();
+        let current = account
+            .storage
+            .get(key)
+            .copied()
+            .unwrap_or(u256::ZERO.into());
+        let zero: Uint256 = u256::ZERO.into();
+
+        let status = if current == *value {
+            StorageStatus::EVMC_STORAGE_ASSIGNED
+        } else if *value == zero {
+            StorageStatus::EVMC_STORAGE_DELETED
+        } else if current == zero {
+            StorageStatus::EVMC_STORAGE_ADDED
+        } else {
+            StorageStatus::EVMC_STORAGE_MODIFIED
+        };
+
+        account.storage.insert(*key, *value);
+        status
+    }
+
+    fn get_balance(&self, address: &Address) -> Uint256 {
+        self.accounts
+            .get(address)
+            .map(|account| account.balance)
+            .unwrap_or(u256::ZERO.into())
+    }
+
+    fn get_code_size(&self, address: &Address) -> usize {
+        self.accounts
+            .get(address)
+            .map(|account| account.code.len())
+            .unwrap_or(0)
+    }
+
+    fn get_code_hash(&self, address: &Address) -> Uint256 {
+        self.accounts
+            .get(address)
+            .map(|account| account.code_hash)
+            .unwrap_or(u256::ZERO.into())
+    }
+
+    fn copy_code(&self, address: &Address, code_offset: usize, buffer: &mut [u8]) -> usize {
+        let Some(account) = self.accounts.get(address) else {
+            return 0;
+        };
+        if code_offset >= account.code.len() {
+            return 0;
+        }
+        let copy_len = buffer.len().min(account.code.len() - code_offset);
+        buffer[..copy_len].copy_from_slice(&account.code[code_offset..code_offset + copy_len]);
+        copy_len
+    }
+
+    fn selfdestruct(&mut self, address: &Address, beneficiary: &Address) -> bool {
+        let Some(account) = self.accounts.remove(address) else {
+            return false;
+        };
+        let beneficiary_account = self.accounts.entry(*beneficiary).or_default();
+        beneficiary_account.balance = (u256::from(beneficiary_account.balance)
+            + u256::from(account.balance))
+        .into();
+        true
+    }
+
+    fn call(&mut self, _message: &ExecutionMessage) -> ExecutionResult {
+        ExecutionResult::new(
+            self.call_result.status_code(),
+            self.call_result.gas_left(),
+            self.call_result.gas_refund(),
+            self.call_result.output().map(<[u8]>::to_vec),
+        )
+    }
+
+    fn get_block_hash(&self, num: i64) -> Uint256 {
+        self.block_hashes
+            .get(&num)
+            .copied()
+            .unwrap_or(u256::ZERO.into())
+    }
+
+    fn emit_log(&mut self, address: &Address, data: &[u8], topics: &[Uint256]) {
+        self.logs.push(MockedLog {
+            address: *address,
+            data: data.to_vec(),
+            topics: topics.to_vec(),
+        });
+    }
+
+    fn access_account(&mut self, address: &Address) -> AccessStatus {
+        if self.warm_accounts.contains(address) {
+            AccessStatus::EVMC_ACCESS_WARM
+        } else {
+            self.warm_accounts.push(*address);
+            AccessStatus::EVMC_ACCESS_COLD
+        }
+    }
+
+    fn access_storage(&mut self, address: &Address, key: &Uint256) -> AccessStatus {
+        let slot = (*address, *key);
+        if self.warm_storage.contains(&slot) {
+            AccessStatus::EVMC_ACCESS_WARM
+        } else {
+            self.warm_storage.push(slot);
+            AccessStatus::EVMC_ACCESS_COLD
+        }
+    }
+
+    fn get_transient_storage(&self, address: &Address, key: &Uint256) -> Uint256 {
+        self.transient_storage
+            .get(&(*address, *key))
+            .copied()
+            .unwrap_or(u256::ZERO.into())
+    }
+
+    fn set_transient_storage(&mut self, address: &Address, key: &Uint256, value: &Uint256) {
+        self.transient_storage.insert((*address, *key), *value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage_status_transitions() {
+        let mut host = MockedHost::new();
+        let address = u256::ZERO.into();
+        let key: Uint256 = u256::from(1u8).into();
+        let value: Uint256 = u256::from(2u8).into();
+        let other: Uint256 = u256::from(3u8).into();
+        let zero: Uint256 = u256::ZERO.into();
+
+        assert_eq!(
+            host.set_storage(&address, &key, &value),
+            StorageStatus::EVMC_STORAGE_ADDED
+        );
+        assert_eq!(
+            host.set_storage(&address, &key, &value),
+            StorageStatus::EVMC_STORAGE_ASSIGNED
+        );
+        assert_eq!(
+            host.set_storage(&address, &key, &other),
+            StorageStatus::EVMC_STORAGE_MODIFIED
+        );
+        assert_eq!(
+            host.set_storage(&address, &key, &zero),
+            StorageStatus::EVMC_STORAGE_DELETED
+        );
+        assert_eq!(host.get_storage(&address, &key), zero);
+    }
+
+    #[test]
+    fn account_lifecycle() {
+        let mut host = MockedHost::new();
+        let address = u256::from(1u8).into();
+        assert!(!host.account_exists(&address));
+        host.set_account(
+            address,
+            MockedAccount {
+                balance: u256::from(42u8).into(),
+                ..Default::default()
+            },
+        );
+        assert!(host.account_exists(&address));
+        assert_eq!(host.get_balance(&address), u256::from(42u8).into());
+    }
+
+    #[test]
+    fn access_lists_warm_up_on_first_touch() {
+        let mut host = MockedHost::new();
+        let address = u256::from(1u8).into();
+        assert_eq!(host.access_account(&address), AccessStatus::EVMC_ACCESS_COLD);
+        assert_eq!(host.access_account(&address), AccessStatus::EVMC_ACCESS_WARM);
+    }
+
+    #[test]
+    fn transient_storage_round_trips_and_clears() {
+        let mut host = MockedHost::new();
+        let address = u256::from(1u8).into();
+        let key: Uint256 = u256::from(1u8).into();
+        let value: Uint256 = u256::from(9u8).into();
+        host.set_transient_storage(&address, &key, &value);
+        assert_eq!(host.get_transient_storage(&address, &key), value);
+        host.clear_transient_storage();
+        assert_eq!(
+            host.get_transient_storage(&address, &key),
+            u256::ZERO.into()
+        );
+    }
+
+    #[test]
+    fn seeding_helpers_are_visible_through_normal_reads_and_readback() {
+        let mut host = MockedHost::new();
+        let address = u256::from(1u8).into();
+        let key: Uint256 = u256::from(1u8).into();
+        let value: Uint256 = u256::from(7u8).into();
+
+        host.set_code(address, vec![0x60, 0x01]);
+        host.set_storage_at(address, key, value);
+
+        assert_eq!(host.get_code_size(&address), 2);
+        assert_eq!(host.get_storage(&address, &key), value);
+        assert_eq!(host.storage_of(&address).unwrap().get(&key), Some(&value));
+        assert!(host.storage_of(&u256::from(2u8).into()).is_none());
+    }
+
+    #[test]
+    fn checkpoint_and_revert_undoes_storage_transient_log_and_selfdestruct_changes() {
+        let mut host = MockedHost::new();
+        let address = u256::from(1u8).into();
+        let beneficiary = u256::from(2u8).into();
+        let key: Uint256 = u256::from(1u8).into();
+        let pre_value: Uint256 = u256::from(9u8).into();
+        host.set_storage_at(address, key, pre_value);
+        host.set_account(
+            beneficiary,
+            MockedAccount {
+                balance: u256::from(5u8).into(),
+                ..Default::default()
+            },
+        );
+
+        let checkpoint = host.checkpoint();
+
+        host.set_storage(&address, &key, &u256::from(42u8).into());
+        host.set_transient_storage(&address, &key, &u256::from(7u8).into());
+        host.emit_log(&address, &[1, 2, 3], &[]);
+        host.selfdestruct(&address, &beneficiary);
+
+        assert_eq!(host.get_storage(&address, &key), u256::from(42u8).into());
+        assert_eq!(
+            host.get_transient_storage(&address, &key),
+            u256::from(7u8).into()
+        );
+        assert_eq!(host.logs.len(), 1);
+        assert!(!host.account_exists(&address));
+        assert_eq!(host.get_balance(&beneficiary), u256::from(5u8).into());
+
+        host.revert_to(checkpoint);
+
+        assert_eq!(host.get_storage(&address, &key), pre_value);
+        assert_eq!(
+            host.get_transient_storage(&address, &key),
+            u256::ZERO.into()
+        );
+        assert!(host.logs.is_empty());
+        assert!(host.account_exists(&address));
+        assert_eq!(host.get_balance(&beneficiary), u256::from(5u8).into());
+    }
+}
@@ -1,36 +1,88 @@
 mod amount;
+mod arena;
 #[cfg(feature = "needs-cache")]
 mod cache;
+#[cfg(feature = "buffer-pool")]
+mod buffer_pool;
 mod code_analysis;
 mod code_reader;
+#[cfg(feature = "disasm")]
+mod disassembler;
 mod execution_context;
+#[cfg(feature = "external-module")]
+mod external_module;
+mod gas_left;
+mod halt_reason;
 pub mod hash_cache;
+#[cfg(feature = "interrupt")]
+mod interrupt;
 mod memory;
+#[cfg(all(feature = "mmap-memory", unix))]
+mod mmap_buffer;
 mod mock_execution_message;
+#[cfg(feature = "mock")]
+mod mocked_host;
+mod modexp;
+mod observer;
+mod recording_context;
+mod structured_tracer;
 #[cfg(feature = "needs-fn-ptr-conversion")]
 mod op_fn_data;
 mod opcode;
 #[cfg(feature = "needs-fn-ptr-conversion")]
 mod pc_map;
+mod precompiles;
 mod stack;
 mod status_code;
+#[cfg(all(feature = "needs-fn-ptr-conversion", feature = "superinstruction-fusion"))]
+mod superinstruction;
 mod tx_context;
 
 pub use amount::u256;
+pub use arena::TypedArena;
 #[cfg(feature = "needs-cache")]
 pub use cache::Cache;
+#[cfg(feature = "buffer-pool")]
+pub use buffer_pool::BufferPool;
 #[cfg(all(feature = "thread-local-cache", feature = "needs-cache"))]
 pub use cache::LocalKeyExt;
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+pub use code_analysis::BasicBlock;
+#[cfg(feature = "needs-fn-ptr-conversion")]
+pub use code_analysis::{analyze_sections, Section};
+#[cfg(feature = "code-analysis-cache")]
+pub use code_analysis::{CacheStats, CodeAnalysisCache};
+#[cfg(not(feature = "needs-fn-ptr-conversion"))]
+pub use code_analysis::DecodeAnalysisError;
 pub use code_analysis::{AnalysisContainer, CodeAnalysis};
 pub use code_reader::{CodeReader, GetOpcodeError};
+#[cfg(feature = "disasm")]
+pub use disassembler::{disassemble, render, DisasmInstr};
 pub use execution_context::*;
+#[cfg(feature = "external-module")]
+pub use external_module::{ExternalModule, ExternalModuleError, ExternalModuleRegistry};
+#[cfg(feature = "buffer-pool")]
+pub use gas_left::release_output;
+pub use gas_left::GasLeft;
+pub use halt_reason::{HaltReason, OutOfGasReason};
+#[cfg(feature = "interrupt")]
+pub use interrupt::{Interrupt, CHECK_INTERVAL as INTERRUPT_CHECK_INTERVAL};
 pub use memory::Memory;
 pub use mock_execution_message::MockExecutionMessage;
+#[cfg(feature = "mock")]
+pub use mocked_host::{HostCheckpoint, MockedAccount, MockedHost, MockedLog};
+pub use modexp::modexp;
+pub use observer::*;
+pub use recording_context::{Interaction, RecordingContext};
+pub use structured_tracer::StructuredTracer;
 #[cfg(feature = "needs-fn-ptr-conversion")]
 pub use op_fn_data::OpFnData;
 pub use opcode::*;
 #[cfg(feature = "needs-fn-ptr-conversion")]
 pub use pc_map::PcMap;
+pub use precompiles::Precompile;
 pub use stack::Stack;
 pub use status_code::{ExecStatus, FailStatus};
+#[cfg(all(feature = "needs-fn-ptr-conversion", feature = "superinstruction-fusion"))]
+pub use superinstruction::{find_fusions, FusionMatch, FusionPattern, FUSION_TABLE};
 pub use tx_context::ExecutionTxContext;
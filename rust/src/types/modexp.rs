@@ -0,0 +1,240 @@
+//! Arbitrary-length modular exponentiation, as required by the `MODEXP` precompile (EIP-198):
+//! base, exponent, and modulus there are independent byte strings, not bounded to 256 bits the
+//! way [`u256::modexp`](crate::types::u256::modexp) is.
+
+/// Upper bound on any single operand's byte length. `mod_pow` below is schoolbook long
+/// division/multiplication, quadratic (or worse) in operand size, so an operand isn't just an
+/// allocation concern but a CPU-time one: without a cap, a caller with a generous gas limit could
+/// still ask for a multi-megabyte modulus and tie up the interpreter for an unreasonable amount
+/// of wall-clock time per gas unit spent. 1 MiB is already far beyond any `MODEXP` input seen on
+/// Fantom or Ethereum mainnet.
+pub(crate) const MAX_OPERAND_LEN: usize = 1 << 20;
+
+/// `base^exp mod modulus`, each given as a big-endian byte string of arbitrary length. The
+/// result is left-padded/truncated to `modulus.len()` bytes, as the `MODEXP` precompile requires.
+/// Returns `None` if any operand exceeds [`MAX_OPERAND_LEN`] bytes, rather than performing
+/// unbounded-size arithmetic on it.
+pub fn modexp(base: &[u8], exp: &[u8], modulus: &[u8]) -> Option<Vec<u8>> {
+    if base.len() > MAX_OPERAND_LEN || exp.len() > MAX_OPERAND_LEN || modulus.len() > MAX_OPERAND_LEN {
+        return None;
+    }
+
+    let mod_len = modulus.len();
+    let modulus = BigUint::from_be_bytes(modulus);
+    if modulus.is_zero() {
+        return Some(vec![0; mod_len]);
+    }
+
+    let result = BigUint::from_be_bytes(base).mod_pow(&BigUint::from_be_bytes(exp), &modulus);
+    let mut output = result.to_be_bytes();
+    if output.len() < mod_len {
+        let mut padded = vec![0; mod_len - output.len()];
+        padded.append(&mut output);
+        output = padded;
+    } else if output.len() > mod_len {
+        output = output[output.len() - mod_len..].to_vec();
+    }
+    Some(output)
+}
+
+/// A minimal little-endian, base-2^64-limb arbitrary-precision unsigned integer, just capable
+/// enough to implement schoolbook `mod_pow`.
+struct BigUint(Vec<u64>);
+
+impl BigUint {
+    fn from_be_bytes(bytes: &[u8]) -> Self {
+        let mut limbs = vec![0u64; bytes.len().div_ceil(8)];
+        for (i, byte) in bytes.iter().rev().enumerate() {
+            limbs[i / 8] |= (*byte as u64) << (8 * (i % 8));
+        }
+        let mut value = Self(limbs);
+        value.trim();
+        value
+    }
+
+    fn to_be_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.0.iter().flat_map(|limb| limb.to_le_bytes()).collect();
+        while bytes.len() > 1 && bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        bytes.reverse();
+        bytes
+    }
+
+    fn trim(&mut self) {
+        while self.0.len() > 1 && self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.iter().all(|&limb| limb == 0)
+    }
+
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let len = self.0.len().max(other.0.len());
+        for i in (0..len).rev() {
+            let a = self.0.get(i).copied().unwrap_or(0);
+            let b = other.0.get(i).copied().unwrap_or(0);
+            let ord = a.cmp(&b);
+            if ord != std::cmp::Ordering::Equal {
+                return ord;
+            }
+        }
+        std::cmp::Ordering::Equal
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        self.0
+            .get(index / 64)
+            .is_some_and(|limb| (limb >> (index % 64)) & 1 == 1)
+    }
+
+    fn bit_len(&self) -> usize {
+        let top = self.0.len() * 64;
+        (0..top).rev().find(|&i| self.bit(i)).map_or(0, |i| i + 1)
+    }
+
+    fn shl1(&mut self) {
+        let mut carry = 0u64;
+        for limb in self.0.iter_mut() {
+            let new_carry = *limb >> 63;
+            *limb = (*limb << 1) | carry;
+            carry = new_carry;
+        }
+        if carry != 0 {
+            self.0.push(carry);
+        }
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        let mut result = vec![0u64; self.0.len()];
+        let mut borrow = 0i128;
+        for i in 0..self.0.len() {
+            let a = self.0[i] as i128;
+            let b = other.0.get(i).copied().unwrap_or(0) as i128;
+            let mut diff = a - b - borrow;
+            if diff < 0 {
+                diff += 1 << 64;
+                borrow = 1;
+            } else {
+                borrow = 0;
+            }
+            result[i] = diff as u64;
+        }
+        let mut value = Self(result);
+        value.trim();
+        value
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let len = self.0.len().max(other.0.len());
+        let mut result = Vec::with_capacity(len + 1);
+        let mut carry = 0u128;
+        for i in 0..len {
+            let a = self.0.get(i).copied().unwrap_or(0) as u128;
+            let b = other.0.get(i).copied().unwrap_or(0) as u128;
+            let sum = a + b + carry;
+            result.push(sum as u64);
+            carry = sum >> 64;
+        }
+        if carry != 0 {
+            result.push(carry as u64);
+        }
+        let mut value = Self(result);
+        value.trim();
+        value
+    }
+
+    /// `self mod modulus` via binary long division.
+    fn rem(&self, modulus: &Self) -> Self {
+        let mut remainder = BigUint(vec![0]);
+        for i in (0..self.bit_len()).rev() {
+            remainder.shl1();
+            if self.bit(i) {
+                remainder.0[0] |= 1;
+            }
+            if remainder.cmp(modulus) != std::cmp::Ordering::Less {
+                remainder = remainder.sub(modulus);
+            }
+        }
+        remainder
+    }
+
+    /// `self * other mod modulus`, via repeated doubling (shift-and-add), which avoids needing a
+    /// full multi-limb multiplication routine.
+    fn mul_mod(&self, other: &Self, modulus: &Self) -> Self {
+        let mut result = BigUint::from_be_bytes(&[]);
+        let mut base = self.rem(modulus);
+        for i in 0..other.bit_len() {
+            if other.bit(i) {
+                result = result.add(&base).rem(modulus);
+            }
+            base.shl1();
+            base = base.rem(modulus);
+        }
+        result
+    }
+
+    fn mod_pow(&self, exponent: &Self, modulus: &Self) -> Self {
+        if modulus.0 == [1] {
+            return BigUint::from_be_bytes(&[]);
+        }
+        let mut result = BigUint::from_be_bytes(&[1]);
+        let base = self.rem(modulus);
+        for i in (0..exponent.bit_len()).rev() {
+            result = result.mul_mod(&result, modulus);
+            if exponent.bit(i) {
+                result = result.mul_mod(&base, modulus);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::modexp;
+
+    #[test]
+    fn small_numbers() {
+        // 3^5 mod 7 = 243 mod 7 = 5
+        assert_eq!(modexp(&[3], &[5], &[7]), Some(vec![5]));
+    }
+
+    #[test]
+    fn modulus_of_one_is_always_zero() {
+        assert_eq!(modexp(&[5], &[5], &[1]), Some(vec![0]));
+    }
+
+    #[test]
+    fn zero_modulus_yields_all_zero_output_of_modulus_length() {
+        assert_eq!(modexp(&[5], &[5], &[0, 0, 0]), Some(vec![0, 0, 0]));
+    }
+
+    #[test]
+    fn pads_output_to_modulus_length() {
+        assert_eq!(modexp(&[3], &[1], &[0, 0, 7]), Some(vec![0, 0, 3]));
+    }
+
+    #[test]
+    fn matches_u256_modexp_for_256_bit_operands() {
+        use crate::types::u256;
+
+        let base = u256::from(3u8);
+        let exp = u256::from(5u8);
+        let modulus = u256::from(7u8);
+        assert_eq!(
+            modexp(&base.to_be_bytes(), &exp.to_be_bytes(), &modulus.to_be_bytes()),
+            Some(base.modexp(exp, modulus).to_be_bytes())
+        );
+    }
+
+    #[test]
+    fn rejects_operand_longer_than_max_operand_len() {
+        let oversized = vec![0u8; super::MAX_OPERAND_LEN + 1];
+        assert_eq!(modexp(&oversized, &[5], &[7]), None);
+        assert_eq!(modexp(&[3], &oversized, &[7]), None);
+        assert_eq!(modexp(&[3], &[5], &oversized), None);
+    }
+}
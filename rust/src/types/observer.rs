@@ -1,7 +1,6 @@
 use std::{borrow::Cow, io::Write};
 
 use crate::interpreter::Interpreter;
-#[cfg(feature = "needs-fn-ptr-conversion")]
 use crate::types::Opcode;
 
 pub trait Observer<const STEPPABLE: bool> {
@@ -9,9 +8,36 @@ pub trait Observer<const STEPPABLE: bool> {
 
     fn post_op(&mut self, interpreter: &Interpreter<STEPPABLE>);
 
+    /// Called right before a `CALL`/`CALLCODE`/`DELEGATECALL`/`STATICCALL`/`CREATE`/`CREATE2` is
+    /// dispatched, in addition to the [`pre_op`](Self::pre_op) already called for it. Note that
+    /// the sub-call this introduces isn't traced by this same observer instance: per the EVMC ABI
+    /// the call is handed off to the host (`ExecutionContextTrait::call`), which may run it
+    /// through a brand new `Interpreter`/observer pair of its own rather than recursing within
+    /// this one - only the enter/exit of the frame at *this* depth is visible here.
+    fn frame_enter(&mut self, _interpreter: &Interpreter<STEPPABLE>) {}
+
+    /// Called right after the call/create opcode's sub-call has returned, once its result is
+    /// folded back into `interpreter`'s stack and gas. See [`frame_enter`](Self::frame_enter) for
+    /// why this doesn't see inside the sub-call itself.
+    fn frame_exit(&mut self, _interpreter: &Interpreter<STEPPABLE>) {}
+
     fn log(&mut self, message: Cow<str>);
 }
 
+/// Whether `op` hands off execution to a sub-call or contract creation, i.e. whether
+/// [`Observer::frame_enter`]/[`frame_exit`](Observer::frame_exit) should bracket it.
+pub(crate) fn is_frame_op(op: Opcode) -> bool {
+    matches!(
+        op,
+        Opcode::Call
+            | Opcode::CallCode
+            | Opcode::DelegateCall
+            | Opcode::StaticCall
+            | Opcode::Create
+            | Opcode::Create2
+    )
+}
+
 pub struct NoOpObserver();
 
 impl<const STEPPABLE: bool> Observer<STEPPABLE> for NoOpObserver {
@@ -58,6 +84,16 @@ impl<W: Write, const STEPPABLE: bool> Observer<STEPPABLE> for LoggingObserver<W>
 
     fn post_op(&mut self, _interpreter: &Interpreter<STEPPABLE>) {}
 
+    fn frame_enter(&mut self, interpreter: &Interpreter<STEPPABLE>) {
+        writeln!(self.writer, "entering frame at depth {}", interpreter.message.depth() + 1).unwrap();
+        self.writer.flush().unwrap();
+    }
+
+    fn frame_exit(&mut self, interpreter: &Interpreter<STEPPABLE>) {
+        writeln!(self.writer, "exiting frame at depth {}", interpreter.message.depth() + 1).unwrap();
+        self.writer.flush().unwrap();
+    }
+
     fn log(&mut self, message: Cow<str>) {
         writeln!(self.writer, "{message}").unwrap();
         self.writer.flush().unwrap();
@@ -68,4 +104,6 @@ impl<W: Write, const STEPPABLE: bool> Observer<STEPPABLE> for LoggingObserver<W>
 pub enum ObserverType {
     NoOp,
     Logging,
+    Eip3155,
+    Tracing,
 }
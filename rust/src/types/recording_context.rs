@@ -0,0 +1,241 @@
+//! A recording wrapper around [`ExecutionContextTrait`].
+//!
+//! [`RecordingContext`] wraps any host implementation and records the calls that take `&mut self`
+//! (`get_tx_context`, `set_storage`, `selfdestruct`, `call`, `emit_log`, `access_account`,
+//! `access_storage`, `set_transient_storage`) into an ordered [`Vec<Interaction>`]. The read-only
+//! queries (`account_exists`, `get_storage`, `get_balance`, `get_code_size`, `get_code_hash`,
+//! `copy_code`, `get_block_hash`, `get_transient_storage`) are forwarded without being recorded,
+//! so their [`Interaction`] variants are never produced. Nothing in this module replays a
+//! recording. This is useful to capture a host's behavior during a real execution and inspect it
+//! later, instead of hand-writing `mockall` expectations in call order.
+//!
+//! Wrapping [`MockedHost`](crate::types::MockedHost) with this is how a test asserts on the
+//! *sequence* of host interactions a run produced rather than just the mocked return values:
+//! EIP-2929 warm/cold [`AccessAccount`](Interaction::AccessAccount)/
+//! [`AccessStorage`](Interaction::AccessStorage) ordering, that
+//! [`EmitLog`](Interaction::EmitLog) carries the expected topics, or that
+//! [`SelfDestruct`](Interaction::SelfDestruct)/[`Call`](Interaction::Call) happen in the right
+//! order - drain the log with [`into_interactions`](RecordingContext::into_interactions) once
+//! execution finishes and assert on it directly.
+
+use evmc_vm::{AccessStatus, Address, ExecutionMessage, ExecutionResult, StorageStatus, Uint256};
+
+use crate::types::{ExecutionContextTrait, ExecutionTxContext};
+
+/// A single recorded call to an [`ExecutionContextTrait`] method, together with its result.
+#[derive(Debug, Clone)]
+pub enum Interaction {
+    GetTxContext(ExecutionTxContext<'static>),
+    AccountExists {
+        address: Address,
+        result: bool,
+    },
+    GetStorage {
+        address: Address,
+        key: Uint256,
+        result: Uint256,
+    },
+    SetStorage {
+        address: Address,
+        key: Uint256,
+        value: Uint256,
+        result: StorageStatus,
+    },
+    GetBalance {
+        address: Address,
+        result: Uint256,
+    },
+    GetCodeSize {
+        address: Address,
+        result: usize,
+    },
+    GetCodeHash {
+        address: Address,
+        result: Uint256,
+    },
+    CopyCode {
+        address: Address,
+        code_offset: usize,
+        copied: Vec<u8>,
+    },
+    SelfDestruct {
+        address: Address,
+        beneficiary: Address,
+        result: bool,
+    },
+    Call,
+    GetBlockHash {
+        num: i64,
+        result: Uint256,
+    },
+    EmitLog {
+        address: Address,
+        data: Vec<u8>,
+        topics: Vec<Uint256>,
+    },
+    AccessAccount {
+        address: Address,
+        result: AccessStatus,
+    },
+    AccessStorage {
+        address: Address,
+        key: Uint256,
+        result: AccessStatus,
+    },
+    GetTransientStorage {
+        address: Address,
+        key: Uint256,
+        result: Uint256,
+    },
+    SetTransientStorage {
+        address: Address,
+        key: Uint256,
+        value: Uint256,
+    },
+}
+
+/// Wraps a `C: ExecutionContextTrait` and records, in order, the `&mut self` calls made through it
+/// (see the module doc comment for which calls those are).
+pub struct RecordingContext<C> {
+    inner: C,
+    pub interactions: Vec<Interaction>,
+}
+
+impl<C: ExecutionContextTrait> RecordingContext<C> {
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            interactions: Vec::new(),
+        }
+    }
+
+    pub fn into_interactions(self) -> Vec<Interaction> {
+        self.interactions
+    }
+}
+
+impl<C: ExecutionContextTrait> ExecutionContextTrait for RecordingContext<C> {
+    fn get_tx_context(&mut self) -> &ExecutionTxContext {
+        let tx_context = *self.inner.get_tx_context();
+        self.interactions.push(Interaction::GetTxContext(tx_context));
+        self.inner.get_tx_context()
+    }
+
+    fn account_exists(&self, address: &Address) -> bool {
+        self.inner.account_exists(address)
+    }
+
+    fn get_storage(&self, address: &Address, key: &Uint256) -> Uint256 {
+        self.inner.get_storage(address, key)
+    }
+
+    fn set_storage(&mut self, address: &Address, key: &Uint256, value: &Uint256) -> StorageStatus {
+        let result = self.inner.set_storage(address, key, value);
+        self.interactions.push(Interaction::SetStorage {
+            address: *address,
+            key: *key,
+            value: *value,
+            result,
+        });
+        result
+    }
+
+    fn get_balance(&self, address: &Address) -> Uint256 {
+        self.inner.get_balance(address)
+    }
+
+    fn get_code_size(&self, address: &Address) -> usize {
+        self.inner.get_code_size(address)
+    }
+
+    fn get_code_hash(&self, address: &Address) -> Uint256 {
+        self.inner.get_code_hash(address)
+    }
+
+    fn copy_code(&self, address: &Address, code_offset: usize, buffer: &mut [u8]) -> usize {
+        self.inner.copy_code(address, code_offset, buffer)
+    }
+
+    fn selfdestruct(&mut self, address: &Address, beneficiary: &Address) -> bool {
+        let result = self.inner.selfdestruct(address, beneficiary);
+        self.interactions.push(Interaction::SelfDestruct {
+            address: *address,
+            beneficiary: *beneficiary,
+            result,
+        });
+        result
+    }
+
+    fn call(&mut self, message: &ExecutionMessage) -> ExecutionResult {
+        // `ExecutionResult` is not `Clone`, so only the fact that a call happened is recorded;
+        // callers that need the result should hold onto it themselves.
+        self.interactions.push(Interaction::Call);
+        self.inner.call(message)
+    }
+
+    fn get_block_hash(&self, num: i64) -> Uint256 {
+        self.inner.get_block_hash(num)
+    }
+
+    fn emit_log(&mut self, address: &Address, data: &[u8], topics: &[Uint256]) {
+        self.interactions.push(Interaction::EmitLog {
+            address: *address,
+            data: data.to_vec(),
+            topics: topics.to_vec(),
+        });
+        self.inner.emit_log(address, data, topics);
+    }
+
+    fn access_account(&mut self, address: &Address) -> AccessStatus {
+        let result = self.inner.access_account(address);
+        self.interactions.push(Interaction::AccessAccount {
+            address: *address,
+            result,
+        });
+        result
+    }
+
+    fn access_storage(&mut self, address: &Address, key: &Uint256) -> AccessStatus {
+        let result = self.inner.access_storage(address, key);
+        self.interactions.push(Interaction::AccessStorage {
+            address: *address,
+            key: *key,
+            result,
+        });
+        result
+    }
+
+    fn get_transient_storage(&self, address: &Address, key: &Uint256) -> Uint256 {
+        self.inner.get_transient_storage(address, key)
+    }
+
+    fn set_transient_storage(&mut self, address: &Address, key: &Uint256, value: &Uint256) {
+        self.interactions.push(Interaction::SetTransientStorage {
+            address: *address,
+            key: *key,
+            value: *value,
+        });
+        self.inner.set_transient_storage(address, key, value);
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::types::{u256, MockedHost};
+
+    #[test]
+    fn records_interactions_in_order() {
+        let mut recorder = RecordingContext::new(MockedHost::new());
+        let address = u256::from(1u8).into();
+        let key: Uint256 = u256::from(2u8).into();
+        let value: Uint256 = u256::from(3u8).into();
+
+        recorder.set_storage(&address, &key, &value);
+        recorder.access_account(&address);
+
+        let interactions = recorder.into_interactions();
+        assert!(matches!(interactions[0], Interaction::SetStorage { .. }));
+        assert!(matches!(interactions[1], Interaction::AccessAccount { .. }));
+    }
+}
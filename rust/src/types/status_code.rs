@@ -13,6 +13,16 @@ pub enum ExecStatus {
 }
 
 /// This type combines the failure variants of [`EvmcStatusCode`] and [`EvmcStepStatusCode`].
+///
+/// A handful of variants already name the specific condition rather than collapsing into
+/// [`Failure`](Self::Failure), which is what makes it possible for
+/// `driver::statetest::check_result` to assert Tosca rejected a state test for the *same* reason
+/// as a reference client instead of merely "some" reason: [`InvalidInstruction`](Self::InvalidInstruction)
+/// is the literal `INVALID` (`0xFE`) opcode, distinct from
+/// [`UndefinedInstruction`](Self::UndefinedInstruction), which is only ever raised by
+/// `check_min_revision` for an opcode that exists but isn't active yet at the current
+/// [`Revision`] (e.g. `PUSH0` before Shanghai); [`StaticModeViolation`](Self::StaticModeViolation)
+/// is `check_not_read_only` specifically, not a generic failure.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FailStatus {
     Failure = EvmcStatusCode::EVMC_FAILURE as isize,
@@ -34,6 +44,20 @@ pub enum FailStatus {
     InternalError = EvmcStatusCode::EVMC_INTERNAL_ERROR as isize,
     Rejected = EvmcStatusCode::EVMC_REJECTED as isize,
     OutOfMemory = EvmcStatusCode::EVMC_OUT_OF_MEMORY as isize,
+    /// `Interpreter::run` was stopped by an `Interrupt` (`types::interrupt`) rather than by
+    /// anything about the code it was executing. Evmc has no status code for this - it's not part
+    /// of the ABI this crate implements, just a host-side escape hatch - so this reuses
+    /// `EVMC_INTERNAL_ERROR`'s discriminant/mapping, the closest existing "not a fault in the
+    /// contract" bucket.
+    #[cfg(feature = "interrupt")]
+    Interrupted = EvmcStatusCode::EVMC_INTERNAL_ERROR as isize,
+    /// An opcode needing host access (`SLOAD`, `BALANCE`, `LOG*`, a non-precompile `CALL`, ...) ran
+    /// in a context-less [`Interpreter`](crate::interpreter::Interpreter) - one built from
+    /// `Interpreter::new`/`new_steppable` with `context: None`, mirroring `ExecutionContext`'s own
+    /// `Option<&mut ExecutionContext>` for host-less invocation. Evmc has no status code for this
+    /// either, so like [`Interrupted`](Self::Interrupted) this reuses `EVMC_INTERNAL_ERROR`'s
+    /// discriminant/mapping rather than inventing one outside the ABI.
+    MissingHost = EvmcStatusCode::EVMC_INTERNAL_ERROR as isize,
 }
 
 impl From<FailStatus> for EvmcStatusCode {
@@ -58,6 +82,9 @@ impl From<FailStatus> for EvmcStatusCode {
             FailStatus::InternalError => Self::EVMC_INTERNAL_ERROR,
             FailStatus::Rejected => Self::EVMC_REJECTED,
             FailStatus::OutOfMemory => Self::EVMC_OUT_OF_MEMORY,
+            #[cfg(feature = "interrupt")]
+            FailStatus::Interrupted => Self::EVMC_INTERNAL_ERROR,
+            FailStatus::MissingHost => Self::EVMC_INTERNAL_ERROR,
         }
     }
 }
@@ -0,0 +1,149 @@
+//! Scaffolding for fusing short, hot opcode sequences ("superinstructions") into a single
+//! dispatch slot during code analysis, as a cheaper alternative to threading through [`OpFnData`]
+//! once per opcode. See [`find_fusions`] for what this currently does and does not do.
+//!
+//! Only `push1_add` (the first [`FUSION_TABLE`] entry) is actually dispatched as a fused slot so
+//! far, via `Opcode::FusedPush1Add` and the matching entry in `analyze_code`'s
+//! `fn-ptr-conversion-expanded-dispatch` path (see `types::code_analysis`); that path doesn't call
+//! into this module's scan at all; it does the same bigram check directly since it only needs one
+//! pattern. `dup1_mload`, `swap1_pop`, `iszero_push1_jumpi`, and `inline-dispatch` support remain
+//! candidates only - this module's scan still finds them, nothing dispatches them yet.
+//!
+//! [`OpFnData`]: crate::types::OpFnData
+
+use crate::types::{code_byte_type, Opcode};
+
+/// A short sequence of opcodes that is common enough to be worth dispatching as a single fused
+/// slot instead of one per opcode, e.g. a `PUSH1` immediately followed by the `ADD` that consumes
+/// it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FusionPattern {
+    pub ops: &'static [u8],
+    pub name: &'static str,
+}
+
+/// The table of opcode sequences [`find_fusions`] scans for. Deliberately small for now: each
+/// entry needs a hand-written fused [`OpFn`] before it can actually be dispatched (see the module
+/// doc comment), so the table only grows as those handlers get written.
+///
+/// [`OpFn`]: crate::interpreter::OpFn
+pub const FUSION_TABLE: &[FusionPattern] = &[
+    FusionPattern {
+        ops: &[Opcode::Push1 as u8, Opcode::Add as u8],
+        name: "push1_add",
+    },
+    FusionPattern {
+        ops: &[Opcode::Dup1 as u8, Opcode::MLoad as u8],
+        name: "dup1_mload",
+    },
+    FusionPattern {
+        ops: &[Opcode::Swap1 as u8, Opcode::Pop as u8],
+        name: "swap1_pop",
+    },
+    FusionPattern {
+        ops: &[Opcode::IsZero as u8, Opcode::Push1 as u8, Opcode::JumpI as u8],
+        name: "iszero_push1_jumpi",
+    },
+];
+
+/// One occurrence of a [`FusionPattern`] found in analyzed code, anchored at the pc of its first
+/// opcode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FusionMatch {
+    pub start_pc: usize,
+    pub pattern: &'static FusionPattern,
+}
+
+/// Scan `code` for non-overlapping occurrences of any [`FUSION_TABLE`] entry.
+///
+/// Matching walks opcodes, not raw bytes, so a `PUSH1`'s immediate operand is skipped the same
+/// way [`code_byte_type`] skips it elsewhere; a pattern byte lines up with the next opcode, not
+/// whatever byte happens to follow. No entry in [`FUSION_TABLE`] contains `JUMPDEST` itself (doing
+/// so would be pointless - it is a no-op placeholder), so a match can never hide one: every byte a
+/// pattern matched is, by construction, not a valid jump target, and the fused slot stays exactly
+/// as reachable by fall-through as the individual opcodes it replaces.
+///
+/// This only locates candidates; it does not fuse them, and `analyze_code` does not call it (see
+/// the module doc comment for why `push1_add` is wired up as a direct bigram check instead).
+/// Actually dispatching the remaining entries means code analysis emitting one new [`OpFnData`]
+/// entry per match whose handler performs the combined semantics of every opcode in the pattern -
+/// including packing any immediate operand (e.g. `PUSH1`'s byte) into the existing `data` slot -
+/// in place of the handler for the first opcode alone. That is a hand-written [`OpFn`] per table
+/// entry plus changes to both the `fn-ptr-conversion-expanded-dispatch` and
+/// `fn-ptr-conversion-inline-dispatch` encodings, which is considerably more code than this
+/// scanning pass, and is left for a follow-up change.
+///
+/// [`OpFnData`]: crate::types::OpFnData
+/// [`OpFn`]: crate::interpreter::OpFn
+pub fn find_fusions(code: &[u8]) -> Vec<FusionMatch> {
+    let mut steps = Vec::new();
+    let mut pc = 0;
+    while let Some(op) = code.get(pc).copied() {
+        let (_, data) = code_byte_type(op);
+        steps.push((pc, op));
+        pc += 1 + data;
+    }
+
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i < steps.len() {
+        if let Some(pattern) = FUSION_TABLE.iter().find(|pattern| matches_at(&steps, i, pattern)) {
+            matches.push(FusionMatch {
+                start_pc: steps[i].0,
+                pattern,
+            });
+            i += pattern.ops.len();
+        } else {
+            i += 1;
+        }
+    }
+    matches
+}
+
+fn matches_at(steps: &[(usize, u8)], i: usize, pattern: &FusionPattern) -> bool {
+    if i + pattern.ops.len() > steps.len() {
+        return false;
+    }
+    pattern
+        .ops
+        .iter()
+        .enumerate()
+        .all(|(offset, &expected)| steps[i + offset].1 == expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_fusions, FusionMatch, FUSION_TABLE};
+    use crate::types::Opcode;
+
+    #[test]
+    fn find_fusions_matches_push1_add() {
+        // PUSH1 1 PUSH1 2 ADD
+        let code = [Opcode::Push1 as u8, 1, Opcode::Push1 as u8, 2, Opcode::Add as u8];
+        let matches = find_fusions(&code);
+        assert_eq!(
+            matches,
+            [FusionMatch {
+                start_pc: 2,
+                pattern: &FUSION_TABLE[0],
+            }]
+        );
+    }
+
+    #[test]
+    fn find_fusions_does_not_match_across_an_intervening_jumpdest() {
+        // SWAP1 JUMPDEST POP: the JUMPDEST breaks up what would otherwise be a swap1_pop match.
+        let code = [
+            Opcode::Swap1 as u8,
+            Opcode::JumpDest as u8,
+            Opcode::Pop as u8,
+        ];
+        assert_eq!(find_fusions(&code), []);
+    }
+
+    #[test]
+    fn find_fusions_ignores_non_matching_code() {
+        let code = [Opcode::Add as u8, Opcode::Mul as u8, Opcode::Pop as u8];
+        assert_eq!(find_fusions(&code), []);
+    }
+}
@@ -1,10 +1,100 @@
-use evmc_vm::{AccessStatus, Address, Revision};
+use evmc_vm::{AccessStatus, Address, Revision, StorageStatus};
 
 use crate::{
-    types::{u256, ExecutionContextTrait, FailStatus},
+    interpreter::{access_list::AccessList, journal::Journal},
+    types::{u256, ExecutionContextTrait, FailStatus, HaltReason, OutOfGasReason},
     utils::word_size,
 };
 
+/// The dynamic gas cost and refund-counter delta for an `SSTORE`, per EIP-2200/EIP-2929/EIP-3529's
+/// net-metering rules, given the [`StorageStatus`] `context.set_storage` just reported. `revision`
+/// alone decides the constants (`dyn_gas_1/2/3`, `refund_1/2/3` below); `status` alone decides
+/// which of those constants apply, mirroring how `sstore`'s EIP spec text itself is structured as
+/// a revision-gated constant table keyed by a handful of before/after storage-value transitions.
+/// EIP-2929's cold-storage-slot surcharge is a separate, access-list-dependent concern layered on
+/// top of this by the caller, not part of this table.
+pub fn sstore_gas_and_refund(revision: Revision, status: StorageStatus) -> (u64, i64) {
+    let (dyn_gas_1, dyn_gas_2, dyn_gas_3, refund_1, refund_2, refund_3) =
+        if revision >= Revision::EVMC_LONDON {
+            (100, 2_900, 20_000, 5_000 - 2_100 - 100, 4_800, 20_000 - 100)
+        } else if revision >= Revision::EVMC_BERLIN {
+            (
+                100,
+                2_900,
+                20_000,
+                5_000 - 2_100 - 100,
+                15_000,
+                20_000 - 100,
+            )
+        } else if revision >= Revision::EVMC_ISTANBUL {
+            (800, 5_000, 20_000, 4_200, 15_000, 19_200)
+        } else {
+            (5_000, 5_000, 20_000, 0, 0, 0)
+        };
+
+    match status {
+        StorageStatus::EVMC_STORAGE_ASSIGNED => (dyn_gas_1, 0),
+        StorageStatus::EVMC_STORAGE_ADDED => (dyn_gas_3, 0),
+        StorageStatus::EVMC_STORAGE_DELETED => (dyn_gas_2, refund_2),
+        StorageStatus::EVMC_STORAGE_MODIFIED => (dyn_gas_2, 0),
+        StorageStatus::EVMC_STORAGE_DELETED_ADDED => (dyn_gas_1, -refund_2),
+        StorageStatus::EVMC_STORAGE_MODIFIED_DELETED => (dyn_gas_1, refund_2),
+        StorageStatus::EVMC_STORAGE_DELETED_RESTORED => (dyn_gas_1, -refund_2 + refund_1),
+        StorageStatus::EVMC_STORAGE_ADDED_DELETED => (dyn_gas_1, refund_3),
+        StorageStatus::EVMC_STORAGE_MODIFIED_RESTORED => (dyn_gas_1, refund_1),
+    }
+}
+
+/// The chain-specific constants `Gas`'s `consume_*` methods charge, looked up once per execution
+/// via [`GasSchedule::for_revision`] instead of being baked into the methods themselves. This is
+/// the one place a downstream integrator targeting a chain that prices these differently (e.g. a
+/// Fantom/Sonic-style fork) needs to override.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasSchedule {
+    /// The surcharge for sending a non-zero value (`CALL`, `CALLCODE`).
+    pub positive_value_transfer_cost: u64,
+    /// The surcharge for sending value to an account that does not yet exist.
+    pub value_to_empty_account_cost: u64,
+    /// EIP-2929 cold account access.
+    pub cold_account_access_cost: u64,
+    /// EIP-2929 warm account access.
+    pub warm_account_access_cost: u64,
+    /// The flat `BALANCE`/`EXTCODE*`/`CALL`-family account access cost before EIP-2929 introduced
+    /// the cold/warm split.
+    pub pre_berlin_account_access_cost: u64,
+    /// `SLOAD`'s cold-access cost from EIP-2929 onward. Unlike [`cold_account_access_cost`
+    /// ](Self::cold_account_access_cost), storage slots priced this separately from accounts even
+    /// before Berlin, so this has no pre-Berlin counterpart sharing a field with it the way
+    /// [`pre_berlin_account_access_cost`](Self::pre_berlin_account_access_cost) does - see
+    /// [`pre_berlin_sload_cost`](Self::pre_berlin_sload_cost) instead.
+    pub cold_sload_cost: u64,
+    /// `SLOAD`'s flat cost before EIP-2929. `SLOAD`'s warm-access cost from Berlin onward equals
+    /// [`warm_account_access_cost`](Self::warm_account_access_cost), so it has no field of its own.
+    pub pre_berlin_sload_cost: u64,
+    /// The per-word cost of copying memory (`CALLDATACOPY`, `CODECOPY`, ...).
+    pub copy_word_cost: u64,
+}
+
+impl GasSchedule {
+    /// The canonical mainnet values for `revision`. None of these have actually changed across
+    /// forks to date - EIP-2929's cold/warm split only changes which of them
+    /// [`Gas::consume_address_access_cost`] charges for `revision` (the flat pre-Berlin cost, or
+    /// the cold or warm cost), not their values - but the schedule still takes `revision` so a future fork that does reprice one of
+    /// these has a single place to do it.
+    pub fn for_revision(_revision: Revision) -> Self {
+        Self {
+            positive_value_transfer_cost: 9_000,
+            value_to_empty_account_cost: 25_000,
+            cold_account_access_cost: 2_600,
+            warm_account_access_cost: 100,
+            pre_berlin_account_access_cost: 700,
+            cold_sload_cost: 2_100,
+            pre_berlin_sload_cost: 800,
+            copy_word_cost: 3,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct GasRefund(i64);
 
@@ -18,14 +108,32 @@ impl GasRefund {
     }
 
     #[inline(always)]
-    pub fn add(&mut self, gas: i64) -> Result<(), FailStatus> {
+    pub fn add(&mut self, gas: i64) -> Result<(), HaltReason> {
         let (gas, overflow) = self.0.overflowing_add(gas);
         if overflow {
-            return Err(FailStatus::OutOfGas);
+            return Err(HaltReason::OutOfGas(OutOfGasReason::RefundOverflow));
         }
         self.0 = gas;
         Ok(())
     }
+
+    /// `self.add(-gas)`, so SSTORE-clear reversals can subtract from the refund counter without
+    /// negating the delta themselves.
+    #[inline(always)]
+    pub fn sub(&mut self, gas: i64) -> Result<(), HaltReason> {
+        let Some(gas) = gas.checked_neg() else {
+            return Err(HaltReason::OutOfGas(OutOfGasReason::RefundOverflow));
+        };
+        self.add(gas)
+    }
+
+    /// The refund the host actually sees at the end of a transaction: never negative, and capped
+    /// at `gas_used / denom` per EIP-3529, which dropped `denom` from 2 to 5 starting at London.
+    /// Handing back the raw counter instead of this would over-refund on post-London chains.
+    pub fn settle(&self, gas_used: u64, revision: Revision) -> i64 {
+        let denom: u64 = if revision >= Revision::EVMC_LONDON { 5 } else { 2 };
+        self.0.max(0).min((gas_used / denom) as i64)
+    }
 }
 
 // Invariant: gas <= i64::MAX
@@ -77,9 +185,14 @@ impl Gas {
     }
 
     #[inline(always)]
-    pub fn consume_positive_value_cost(&mut self, value: &u256) -> Result<(), FailStatus> {
+    pub fn consume_positive_value_cost(
+        &mut self,
+        value: &u256,
+        schedule: &GasSchedule,
+    ) -> Result<(), HaltReason> {
         if *value != u256::ZERO {
-            self.consume(9_000)?;
+            self.consume(schedule.positive_value_transfer_cost)
+                .map_err(|_| HaltReason::OutOfGas(OutOfGasReason::PositiveValueTransfer))?;
         }
         Ok(())
     }
@@ -90,34 +203,82 @@ impl Gas {
         value: &u256,
         addr: &Address,
         context: &mut dyn ExecutionContextTrait,
-    ) -> Result<(), FailStatus> {
+        schedule: &GasSchedule,
+    ) -> Result<(), HaltReason> {
         if *value != u256::ZERO && !context.account_exists(addr) {
-            self.consume(25_000)?;
+            self.consume(schedule.value_to_empty_account_cost)
+                .map_err(|_| HaltReason::OutOfGas(OutOfGasReason::ValueToEmptyAccount))?;
         }
         Ok(())
     }
 
+    /// The cost of touching `addr`: the flat pre-EIP-2929 charge below Berlin, or the cold/warm
+    /// tiered charge from Berlin onward, consulted against the interpreter's own `access_list`
+    /// first and falling back to `context` on a miss (see the [`access_list`](crate::interpreter::access_list)
+    /// module docs), same as `SLOAD`/`SSTORE`/`SELFDESTRUCT` already do. Charges something on every
+    /// branch, so callers across `BALANCE`/`EXTCODE*`/the `CALL` family can charge for an
+    /// address access unconditionally instead of special-casing the pre-Berlin revisions
+    /// themselves.
     #[inline(always)]
     pub fn consume_address_access_cost(
         &mut self,
         addr: &Address,
         revision: Revision,
-        context: &mut dyn ExecutionContextTrait,
-    ) -> Result<(), FailStatus> {
-        if revision < Revision::EVMC_BERLIN {
-            return Ok(());
-        }
-        if context.access_account(addr) == AccessStatus::EVMC_ACCESS_COLD {
-            self.consume(2_600)
+        access_list: &mut AccessList,
+        journal: &mut Journal,
+        context: Option<&mut dyn ExecutionContextTrait>,
+        schedule: &GasSchedule,
+    ) -> Result<(), HaltReason> {
+        let cost = if revision < Revision::EVMC_BERLIN {
+            schedule.pre_berlin_account_access_cost
+        } else if access_list.access_account(addr, context) == AccessStatus::EVMC_ACCESS_COLD {
+            journal.record_accessed_account(*addr);
+            schedule.cold_account_access_cost
         } else {
-            self.consume(100)
-        }
+            schedule.warm_account_access_cost
+        };
+        self.consume(cost)
+            .map_err(|_| HaltReason::OutOfGas(OutOfGasReason::AddressAccess))
     }
 
     #[inline(always)]
-    pub fn consume_copy_cost(&mut self, len: u64) -> Result<(), FailStatus> {
-        let cost = word_size(len)? * 3; // does not overflow because word_size divides by 32
+    pub fn consume_copy_cost(
+        &mut self,
+        len: u64,
+        schedule: &GasSchedule,
+    ) -> Result<(), HaltReason> {
+        // does not overflow because word_size divides by 32
+        let cost = word_size(len).map_err(HaltReason::Other)? * schedule.copy_word_cost;
         self.consume(cost)
+            .map_err(|_| HaltReason::OutOfGas(OutOfGasReason::Copy))
+    }
+
+    /// The quadratic cost (`3*w + w*w/512`) of growing memory from `current_words` to
+    /// `new_words`, charged as the difference between the two so that a call frame is only ever
+    /// billed for the portion of the high-water mark it newly reaches. A no-op, not an error, when
+    /// `new_words` does not exceed `current_words` - shrinking memory is free.
+    #[inline(always)]
+    pub fn consume_memory_expansion_cost(
+        &mut self,
+        current_words: u64,
+        new_words: u64,
+    ) -> Result<(), HaltReason> {
+        if new_words <= current_words {
+            return Ok(());
+        }
+        fn cost(words: u64) -> Result<u64, FailStatus> {
+            let (squared, squared_overflow) = words.overflowing_mul(words);
+            let (linear, linear_overflow) = words.overflowing_mul(3);
+            let (cost, cost_overflow) = (squared / 512).overflowing_add(linear);
+            if squared_overflow || linear_overflow || cost_overflow {
+                return Err(FailStatus::OutOfGas);
+            }
+            Ok(cost)
+        }
+        let new_cost = cost(new_words).map_err(HaltReason::Other)?;
+        let current_cost = cost(current_words).map_err(HaltReason::Other)?;
+        self.consume(new_cost - current_cost)
+            .map_err(|_| HaltReason::OutOfGas(OutOfGasReason::MemoryExpansion))
     }
 }
 
@@ -127,9 +288,12 @@ mod tests {
     use mockall::predicate;
 
     use crate::{
-        interpreter::Interpreter,
-        types::{u256, FailStatus, MockExecutionContextTrait, MockExecutionMessage, Opcode},
-        utils::Gas,
+        interpreter::{access_list::AccessList, journal::Journal, Interpreter},
+        types::{
+            u256, FailStatus, HaltReason, MockExecutionContextTrait, MockExecutionMessage, Opcode,
+            OutOfGasReason,
+        },
+        utils::{Gas, GasRefund, GasSchedule},
     };
 
     #[test]
@@ -149,18 +313,26 @@ mod tests {
 
     #[test]
     fn consume_positive_value_cost() {
+        let schedule = GasSchedule::for_revision(Revision::EVMC_CANCUN);
+
         let mut gas_left = Gas::new(1);
-        assert_eq!(gas_left.consume_positive_value_cost(&u256::ZERO), Ok(()));
+        assert_eq!(
+            gas_left.consume_positive_value_cost(&u256::ZERO, &schedule),
+            Ok(())
+        );
         assert_eq!(gas_left, 1);
 
         let mut gas_left = Gas::new(9_000);
-        assert_eq!(gas_left.consume_positive_value_cost(&u256::ONE), Ok(()));
+        assert_eq!(
+            gas_left.consume_positive_value_cost(&u256::ONE, &schedule),
+            Ok(())
+        );
         assert_eq!(gas_left, 0);
 
         let mut gas_left = Gas::new(1);
         assert_eq!(
-            gas_left.consume_positive_value_cost(&u256::ONE),
-            Err(FailStatus::OutOfGas)
+            gas_left.consume_positive_value_cost(&u256::ONE, &schedule),
+            Err(HaltReason::OutOfGas(OutOfGasReason::PositiveValueTransfer))
         );
         assert_eq!(gas_left, 1);
     }
@@ -194,16 +366,19 @@ mod tests {
             let mut interpreter = Interpreter::new(
                 Revision::EVMC_ISTANBUL,
                 &message,
-                &mut context,
+                Some(&mut context),
                 &[Opcode::Call as u8],
-            );
+            )
+            .unwrap();
             interpreter.gas_left = Gas::new(if consume { 25_000 } else { 0 });
+            let schedule = GasSchedule::for_revision(interpreter.revision);
 
             assert_eq!(
                 interpreter.gas_left.consume_value_to_empty_account_cost(
                     &value,
                     &addr,
-                    interpreter.context
+                    interpreter.context.as_deref_mut().unwrap(),
+                    &schedule,
                 ),
                 Ok(())
             );
@@ -216,9 +391,10 @@ mod tests {
                     interpreter.gas_left.consume_value_to_empty_account_cost(
                         &value,
                         &addr,
-                        interpreter.context
+                        interpreter.context.as_deref_mut().unwrap(),
+                        &schedule,
                     ),
-                    Err(FailStatus::OutOfGas)
+                    Err(HaltReason::OutOfGas(OutOfGasReason::ValueToEmptyAccount))
                 );
             }
         }
@@ -227,46 +403,43 @@ mod tests {
     #[test]
     fn consume_address_access_cost() {
         let cases = [
-            (
-                Revision::EVMC_ISTANBUL,
-                AccessStatus::EVMC_ACCESS_COLD,
-                Gas::new(0),
-            ),
-            (
-                Revision::EVMC_BERLIN,
-                AccessStatus::EVMC_ACCESS_COLD,
-                Gas::new(2_600),
-            ),
-            (
-                Revision::EVMC_BERLIN,
-                AccessStatus::EVMC_ACCESS_WARM,
-                Gas::new(100),
-            ),
+            (Revision::EVMC_ISTANBUL, false, Gas::new(700)),
+            (Revision::EVMC_BERLIN, false, Gas::new(2_600)),
+            (Revision::EVMC_BERLIN, true, Gas::new(100)),
         ];
-        for (revision, access_status, gas) in cases {
+        for (revision, pre_warmed, gas) in cases {
             let addr = Address::from(u256::ONE);
+            // Depth 1: a nested call frame, not the transaction's top-level one, so
+            // `Interpreter::new` won't push its pre-warmed addresses to the host itself and the
+            // only `access_account` call below is `consume_address_access_cost`'s own fallback on
+            // a local miss.
             let message = MockExecutionMessage::default().into();
-
             let mut context = MockExecutionContextTrait::new();
             context
                 .expect_access_account()
-                .times(if revision < Revision::EVMC_BERLIN {
-                    0
-                } else {
-                    1
-                })
+                .times(usize::from(revision >= Revision::EVMC_BERLIN && !pre_warmed))
                 .with(predicate::eq(addr))
-                .return_const(access_status);
+                .return_const(AccessStatus::EVMC_ACCESS_COLD);
 
             let mut interpreter =
-                Interpreter::new(revision, &message, &mut context, &[Opcode::Call as u8]);
+                Interpreter::new(revision, &message, Some(&mut context), &[Opcode::Call as u8])
+                    .unwrap();
             interpreter.gas_left = gas;
+            let schedule = GasSchedule::for_revision(revision);
+            let mut access_list = AccessList::new([]);
+            if pre_warmed {
+                access_list.access_account(&addr, None);
+            }
+            let mut journal = Journal::new();
 
             assert_eq!(
                 interpreter.gas_left.consume_address_access_cost(
                     &addr,
                     interpreter.revision,
-                    interpreter.context
+                    &mut access_list,
+                    &mut journal,
+                    interpreter.context.as_deref_mut(),
+                    &schedule,
                 ),
                 Ok(())
             );
@@ -276,31 +449,141 @@ mod tests {
 
     #[test]
     fn consume_copy_cost() {
+        let schedule = GasSchedule::for_revision(Revision::EVMC_CANCUN);
+
         let mut gas_left = Gas::new(1);
-        assert_eq!(gas_left.consume_copy_cost(0), Ok(()));
+        assert_eq!(gas_left.consume_copy_cost(0, &schedule), Ok(()));
         assert_eq!(gas_left, 1);
 
         let mut gas_left = Gas::new(3);
-        assert_eq!(gas_left.consume_copy_cost(1), Ok(()));
+        assert_eq!(gas_left.consume_copy_cost(1, &schedule), Ok(()));
         assert_eq!(gas_left, 0);
 
         let mut gas_left = Gas::new(3);
-        assert_eq!(gas_left.consume_copy_cost(32), Ok(()));
+        assert_eq!(gas_left.consume_copy_cost(32, &schedule), Ok(()));
         assert_eq!(gas_left, 0);
 
         let mut gas_left = Gas::new(6);
-        assert_eq!(gas_left.consume_copy_cost(33), Ok(()));
+        assert_eq!(gas_left.consume_copy_cost(33, &schedule), Ok(()));
         assert_eq!(gas_left, 0);
 
         let mut gas_left = Gas::new(2);
-        assert_eq!(gas_left.consume_copy_cost(1), Err(FailStatus::OutOfGas));
+        assert_eq!(
+            gas_left.consume_copy_cost(1, &schedule),
+            Err(HaltReason::OutOfGas(OutOfGasReason::Copy))
+        );
         assert_eq!(gas_left, 2);
 
         let mut gas_left = Gas::new(2);
         assert_eq!(
-            gas_left.consume_copy_cost(u64::MAX),
-            Err(FailStatus::OutOfGas)
+            gas_left.consume_copy_cost(u64::MAX, &schedule),
+            Err(HaltReason::Other(FailStatus::OutOfGas))
+        );
+        assert_eq!(gas_left, 2);
+    }
+
+    #[test]
+    fn consume_memory_expansion_cost() {
+        // No-op when memory does not need to grow.
+        let mut gas_left = Gas::new(0);
+        assert_eq!(gas_left.consume_memory_expansion_cost(1, 1), Ok(()));
+        assert_eq!(gas_left, 0);
+
+        let mut gas_left = Gas::new(0);
+        assert_eq!(gas_left.consume_memory_expansion_cost(1, 0), Ok(()));
+        assert_eq!(gas_left, 0);
+
+        // cost(1) = 3*1 + 1*1/512 = 3
+        let mut gas_left = Gas::new(3);
+        assert_eq!(gas_left.consume_memory_expansion_cost(0, 1), Ok(()));
+        assert_eq!(gas_left, 0);
+
+        // Charging again for a further expansion only consumes the gas for the newly reached
+        // word count, on top of what the previous call already charged: cost(2) - cost(1) = 3.
+        let mut gas_left = Gas::new(3);
+        assert_eq!(gas_left.consume_memory_expansion_cost(1, 2), Ok(()));
+        assert_eq!(gas_left, 0);
+
+        let mut gas_left = Gas::new(2);
+        assert_eq!(
+            gas_left.consume_memory_expansion_cost(0, 1),
+            Err(HaltReason::OutOfGas(OutOfGasReason::MemoryExpansion))
         );
         assert_eq!(gas_left, 2);
+
+        // `w*w` would overflow u64.
+        let mut gas_left = Gas::new(u64::MAX);
+        assert_eq!(
+            gas_left.consume_memory_expansion_cost(0, u64::MAX),
+            Err(HaltReason::Other(FailStatus::OutOfGas))
+        );
+        assert_eq!(gas_left, u64::MAX);
+    }
+
+    #[test]
+    fn gas_refund_sub() {
+        let mut gas_refund = GasRefund::new(10);
+        assert_eq!(gas_refund.sub(4), Ok(()));
+        assert_eq!(gas_refund.as_i64(), 6);
+
+        let mut gas_refund = GasRefund::new(0);
+        assert_eq!(
+            gas_refund.sub(i64::MIN),
+            Err(HaltReason::OutOfGas(OutOfGasReason::RefundOverflow))
+        );
+    }
+
+    #[test]
+    fn gas_refund_settle() {
+        // Capped at gas_used / 2 before London.
+        assert_eq!(
+            GasRefund::new(1_000).settle(1_000, Revision::EVMC_BERLIN),
+            500
+        );
+        // Capped at gas_used / 5 from London onward (EIP-3529).
+        assert_eq!(
+            GasRefund::new(1_000).settle(1_000, Revision::EVMC_LONDON),
+            200
+        );
+        // Under the cap, the raw counter is returned as-is.
+        assert_eq!(GasRefund::new(100).settle(1_000, Revision::EVMC_LONDON), 100);
+        // Never negative.
+        assert_eq!(GasRefund::new(-100).settle(1_000, Revision::EVMC_LONDON), 0);
+    }
+
+    #[test]
+    fn sstore_gas_and_refund() {
+        use evmc_vm::StorageStatus;
+
+        // A fresh write to an untouched slot costs the full 20,000 at every revision, with no
+        // refund.
+        for revision in [
+            Revision::EVMC_FRONTIER,
+            Revision::EVMC_ISTANBUL,
+            Revision::EVMC_BERLIN,
+            Revision::EVMC_LONDON,
+        ] {
+            assert_eq!(
+                super::sstore_gas_and_refund(revision, StorageStatus::EVMC_STORAGE_ADDED),
+                (20_000, 0)
+            );
+        }
+
+        // Clearing a slot back to zero earns EIP-3529's reduced 4,800 refund from London onward,
+        // down from the pre-London 15,000.
+        assert_eq!(
+            super::sstore_gas_and_refund(
+                Revision::EVMC_BERLIN,
+                StorageStatus::EVMC_STORAGE_DELETED
+            ),
+            (2_900, 15_000)
+        );
+        assert_eq!(
+            super::sstore_gas_and_refund(
+                Revision::EVMC_LONDON,
+                StorageStatus::EVMC_STORAGE_DELETED
+            ),
+            (2_900, 4_800)
+        );
     }
 }
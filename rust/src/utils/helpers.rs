@@ -4,13 +4,18 @@ use evmc_vm::{ExecutionMessage, MessageFlags, Revision};
 
 use crate::{
     types::{u256, FailStatus},
-    utils::Gas,
+    utils::{Gas, GasSchedule},
 };
 
 pub trait SliceExt {
     fn get_within_bounds(&self, offset: u256, len: u64) -> &[u8];
 
-    fn copy_padded(&mut self, src: &[u8], gas_left: &mut Gas) -> Result<(), FailStatus>;
+    fn copy_padded(
+        &mut self,
+        src: &[u8],
+        gas_left: &mut Gas,
+        gas_schedule: &GasSchedule,
+    ) -> Result<(), FailStatus>;
 }
 
 impl SliceExt for [u8] {
@@ -34,8 +39,13 @@ impl SliceExt for [u8] {
     }
 
     #[inline(always)]
-    fn copy_padded(&mut self, src: &[u8], gas_left: &mut Gas) -> Result<(), FailStatus> {
-        gas_left.consume_copy_cost(self.len() as u64)?;
+    fn copy_padded(
+        &mut self,
+        src: &[u8],
+        gas_left: &mut Gas,
+        gas_schedule: &GasSchedule,
+    ) -> Result<(), FailStatus> {
+        gas_left.consume_copy_cost(self.len() as u64, gas_schedule)?;
         self[..src.len()].copy_from_slice(src);
         self[src.len()..].fill(0);
         Ok(())
@@ -74,7 +84,7 @@ mod tests {
     use crate::{
         interpreter::Interpreter,
         types::{u256, FailStatus, MockExecutionContextTrait, MockExecutionMessage},
-        utils::{self, Gas, SliceExt},
+        utils::{self, Gas, GasSchedule, SliceExt},
     };
 
     #[test]
@@ -89,29 +99,43 @@ mod tests {
 
     #[test]
     fn copy_padded() {
+        let schedule = GasSchedule::for_revision(Revision::EVMC_CANCUN);
+
         let src = [];
         let mut dest = [];
-        assert_eq!(dest.copy_padded(&src, &mut Gas::new(1_000_000)), Ok(()));
+        assert_eq!(
+            dest.copy_padded(&src, &mut Gas::new(1_000_000), &schedule),
+            Ok(())
+        );
 
         let src = [];
         let mut dest = [1];
-        assert_eq!(dest.copy_padded(&src, &mut Gas::new(1_000_000)), Ok(()));
+        assert_eq!(
+            dest.copy_padded(&src, &mut Gas::new(1_000_000), &schedule),
+            Ok(())
+        );
         assert_eq!(dest, [0]);
 
         let src = [2];
         let mut dest = [1];
-        assert_eq!(dest.copy_padded(&src, &mut Gas::new(1_000_000)), Ok(()));
+        assert_eq!(
+            dest.copy_padded(&src, &mut Gas::new(1_000_000), &schedule),
+            Ok(())
+        );
         assert_eq!(dest, [2]);
 
         let src = [3];
         let mut dest = [1, 2];
-        assert_eq!(dest.copy_padded(&src, &mut Gas::new(1_000_000)), Ok(()));
+        assert_eq!(
+            dest.copy_padded(&src, &mut Gas::new(1_000_000), &schedule),
+            Ok(())
+        );
         assert_eq!(dest, [3, 0]);
 
         let src = [2];
         let mut dest = [1];
         assert_eq!(
-            dest.copy_padded(&src, &mut Gas::new(0)),
+            dest.copy_padded(&src, &mut Gas::new(0), &schedule),
             Err(FailStatus::OutOfGas)
         );
     }
@@ -145,7 +169,8 @@ mod tests {
     fn check_not_read_only() {
         let message = MockExecutionMessage::default().into();
         let mut context = MockExecutionContextTrait::new();
-        let interpreter = Interpreter::new(Revision::EVMC_CANCUN, &message, &mut context, &[]);
+        let interpreter =
+            Interpreter::new(Revision::EVMC_CANCUN, &message, Some(&mut context), &[]).unwrap();
         assert_eq!(utils::check_not_read_only(&interpreter), Ok(()));
 
         let message = MockExecutionMessage {
@@ -153,7 +178,8 @@ mod tests {
             ..Default::default()
         };
         let message = message.into();
-        let interpreter = Interpreter::new(Revision::EVMC_CANCUN, &message, &mut context, &[]);
+        let interpreter =
+            Interpreter::new(Revision::EVMC_CANCUN, &message, Some(&mut context), &[]).unwrap();
         assert_eq!(
             utils::check_not_read_only(&interpreter),
             Err(FailStatus::StaticModeViolation)
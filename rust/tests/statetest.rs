@@ -0,0 +1,52 @@
+#![allow(unused_crate_dependencies)]
+use common::evmc_vm::{ExecutionResult, StatusCode};
+use driver::statetest::{check_result, status_for_exception_label, ExpectedOutcome, Mismatch};
+
+#[test]
+fn matching_success_has_no_mismatches() {
+    let expected = ExpectedOutcome::Success {
+        gas_used: 21_000,
+        output: vec![1, 2, 3],
+    };
+    let actual = ExecutionResult::new(StatusCode::EVMC_SUCCESS, 79_000, 0, Some(&[1, 2, 3]));
+    assert_eq!(check_result(&expected, &actual, 100_000), Vec::new());
+}
+
+#[test]
+fn gas_used_mismatch_is_reported() {
+    let expected = ExpectedOutcome::Success {
+        gas_used: 21_000,
+        output: Vec::new(),
+    };
+    let actual = ExecutionResult::new(StatusCode::EVMC_SUCCESS, 70_000, 0, None);
+    assert_eq!(
+        check_result(&expected, &actual, 100_000),
+        vec![Mismatch::GasUsed {
+            expected: 21_000,
+            got: 30_000,
+        }]
+    );
+}
+
+#[test]
+fn matching_exception_has_no_mismatches() {
+    let expected = ExpectedOutcome::Exception("StackUnderflow".into());
+    let actual = ExecutionResult::new(StatusCode::EVMC_STACK_UNDERFLOW, 0, 0, None);
+    assert_eq!(check_result(&expected, &actual, 100_000), Vec::new());
+}
+
+#[test]
+fn unmapped_exception_label_is_reported_rather_than_silently_passed() {
+    // "TR_BLOBLIST_OVERSIZE" names an outer transaction-validity rejection this interpreter's
+    // `execute` has no status code for - see `status_for_exception_label`'s doc comment.
+    assert_eq!(status_for_exception_label("TR_BLOBLIST_OVERSIZE"), None);
+    let expected = ExpectedOutcome::Exception("TR_BLOBLIST_OVERSIZE".into());
+    let actual = ExecutionResult::new(StatusCode::EVMC_SUCCESS, 100_000, 0, None);
+    assert_eq!(
+        check_result(&expected, &actual, 100_000),
+        vec![Mismatch::UnexpectedException {
+            expected: "TR_BLOBLIST_OVERSIZE".into(),
+            got: None,
+        }]
+    );
+}
@@ -0,0 +1,282 @@
+//! This crate provides the [`tosca_declare_vm`] attribute macro, which generates the EVMC C ABI
+//! (the `evmc_create_*` entry point plus the `create`/`destroy`/`execute`/`get_capabilities`/
+//! `set_option` trampolines) from an `impl EvmcVm for ...` block. It is modeled on EVMC's own
+//! `evmc_declare_vm`. Only the `EvmcVm` ABI is generated: `SteppableEvmcVm` is not handled, and
+//! its glue stays hand-written in `src/ffi.rs`. The macro's capability list seeds the flagset
+//! `get_capabilities` reports, but a host can reconfigure it afterwards via
+//! `set_option("capabilities", "EVM1,PRECOMPILES")`; `execute`'s null-host check always consults
+//! the live value rather than the compile-time default.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Ident, ItemImpl, LitStr, Token,
+};
+
+/// Arguments accepted by `#[tosca_declare_vm("name", "evm, ewasm", "1.2.3")]`.
+struct DeclareVmArgs {
+    name: LitStr,
+    capabilities: LitStr,
+    version: LitStr,
+}
+
+impl Parse for DeclareVmArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let args = Punctuated::<LitStr, Token![,]>::parse_terminated(input)?;
+        let mut args = args.into_iter();
+        let (Some(name), Some(capabilities), Some(version), None) =
+            (args.next(), args.next(), args.next(), args.next())
+        else {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "expected exactly 3 string literals: name, capabilities, version",
+            ));
+        };
+        Ok(Self {
+            name,
+            capabilities,
+            version,
+        })
+    }
+}
+
+/// One capability recognized in the comma-separated capability list.
+fn parse_capability(cap: &str) -> syn::Result<Ident> {
+    match cap.trim() {
+        "evm" => Ok(format_ident!("EVMC_CAPABILITY_EVM1")),
+        "ewasm" => Ok(format_ident!("EVMC_CAPABILITY_EWASM")),
+        "precompiles" => Ok(format_ident!("EVMC_CAPABILITY_PRECOMPILES")),
+        other => Err(syn::Error::new(
+            Span::call_site(),
+            format!("unknown capability `{other}`, expected one of: evm, ewasm, precompiles"),
+        )),
+    }
+}
+
+fn check_semver(version: &str) -> syn::Result<()> {
+    let parts: Vec<_> = version.split('.').collect();
+    if parts.len() != 3 || parts.iter().any(|part| part.parse::<u64>().is_err()) {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            format!("`{version}` is not a valid semver version of the form MAJOR.MINOR.PATCH"),
+        ));
+    }
+    Ok(())
+}
+
+/// Generate the EVMC ABI for the annotated `impl EvmcVm for Type` block.
+///
+/// ```ignore
+/// #[tosca_declare_vm("evmrs", "evm", "0.1.0")]
+/// impl EvmcVm for EvmRs { ... }
+/// ```
+#[proc_macro_attribute]
+pub fn tosca_declare_vm(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as DeclareVmArgs);
+    let item_impl = parse_macro_input!(item as ItemImpl);
+
+    match expand(args, item_impl) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(args: DeclareVmArgs, item_impl: ItemImpl) -> syn::Result<TokenStream2> {
+    check_semver(&args.version.value())?;
+
+    let capabilities = args
+        .capabilities
+        .value()
+        .split(',')
+        .map(|cap| parse_capability(cap.as_ref()))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let vm_type = &item_impl.self_ty;
+    let name = args.name.value();
+    let name_cstr = format!("{name}\0");
+    let version = args.version.value();
+    let version_cstr = format!("{version}\0");
+    let create_fn = format_ident!("evmc_create_{name}");
+
+    Ok(quote! {
+        #item_impl
+
+        static __TOSCA_DECLARE_VM_NAME: &::std::ffi::CStr =
+            unsafe { ::std::ffi::CStr::from_bytes_with_nul_unchecked(#name_cstr.as_bytes()) };
+        static __TOSCA_DECLARE_VM_VERSION: &::std::ffi::CStr =
+            unsafe { ::std::ffi::CStr::from_bytes_with_nul_unchecked(#version_cstr.as_bytes()) };
+
+        /// The compile-time capability list from the `#[tosca_declare_vm(...)]` attribute, used
+        /// only to seed `__TOSCA_DECLARE_VM_CAPABILITIES` below.
+        const __TOSCA_DECLARE_VM_DEFAULT_CAPABILITIES: u32 =
+            #(::evmc_vm::ffi::evmc_capabilities::#capabilities as u32)|*;
+
+        /// The flagset `__tosca_declare_vm_get_capabilities` currently reports, overridable at runtime via
+        /// `set_option("capabilities", "EVM1,PRECOMPILES")`. Stored process-wide rather than per
+        /// `EvmcContainer` instance: a host only ever creates one VM instance per process through
+        /// `#create_fn`, so the two coincide in practice and an atomic avoids the `&mut self`
+        /// `EvmcContainer` access `__tosca_declare_vm_get_capabilities` doesn't otherwise need.
+        static __TOSCA_DECLARE_VM_CAPABILITIES: ::std::sync::atomic::AtomicU32 =
+            ::std::sync::atomic::AtomicU32::new(__TOSCA_DECLARE_VM_DEFAULT_CAPABILITIES);
+
+        /// Generated by `#[tosca_declare_vm]`. Allocates a new `#vm_type`, boxes it behind an
+        /// `EvmcContainer` and hands ownership over to the host as a raw `evmc_vm*`.
+        #[no_mangle]
+        pub extern "C" fn #create_fn() -> *mut ::evmc_vm::ffi::evmc_vm {
+            let new_instance = ::evmc_vm::ffi::evmc_vm {
+                abi_version: ::evmc_vm::ffi::EVMC_ABI_VERSION as i32,
+                name: __TOSCA_DECLARE_VM_NAME.as_ptr(),
+                version: __TOSCA_DECLARE_VM_VERSION.as_ptr(),
+                destroy: Some(__tosca_declare_vm_destroy),
+                execute: Some(__tosca_declare_vm_execute),
+                get_capabilities: Some(__tosca_declare_vm_get_capabilities),
+                set_option: Some(__tosca_declare_vm_set_option),
+            };
+            let container = ::evmc_vm::EvmcContainer::<#vm_type>::new(new_instance);
+            // SAFETY: `into_ffi_pointer` only boxes the container, which is always sound.
+            unsafe { ::evmc_vm::EvmcContainer::into_ffi_pointer(container) }
+        }
+
+        extern "C" fn __tosca_declare_vm_destroy(instance: *mut ::evmc_vm::ffi::evmc_vm) {
+            if instance.is_null() {
+                ::std::process::abort();
+            }
+            // SAFETY: the host only ever passes back a pointer obtained from #create_fn.
+            unsafe {
+                ::evmc_vm::EvmcContainer::<#vm_type>::from_ffi_pointer(instance);
+            }
+        }
+
+        extern "C" fn __tosca_declare_vm_get_capabilities(
+            _instance: *mut ::evmc_vm::ffi::evmc_vm,
+        ) -> ::evmc_vm::ffi::evmc_capabilities_flagset {
+            __TOSCA_DECLARE_VM_CAPABILITIES.load(::std::sync::atomic::Ordering::Relaxed)
+                as ::evmc_vm::ffi::evmc_capabilities_flagset
+        }
+
+        extern "C" fn __tosca_declare_vm_set_option(
+            instance: *mut ::evmc_vm::ffi::evmc_vm,
+            key: *const ::std::ffi::c_char,
+            value: *const ::std::ffi::c_char,
+        ) -> ::evmc_vm::ffi::evmc_set_option_result {
+            if instance.is_null() || key.is_null() {
+                return ::evmc_vm::ffi::evmc_set_option_result::EVMC_SET_OPTION_INVALID_NAME;
+            }
+            // SAFETY: `key` is not null and the host guarantees it is a valid C string.
+            let Ok(key) = unsafe { ::std::ffi::CStr::from_ptr(key) }.to_str() else {
+                return ::evmc_vm::ffi::evmc_set_option_result::EVMC_SET_OPTION_INVALID_NAME;
+            };
+            let value = if value.is_null() {
+                ::std::borrow::Cow::Borrowed("")
+            } else {
+                // SAFETY: `value` is not null and the host guarantees it is a valid C string.
+                match unsafe { ::std::ffi::CStr::from_ptr(value) }.to_str() {
+                    Ok(value) => ::std::borrow::Cow::Borrowed(value),
+                    Err(_) => {
+                        return ::evmc_vm::ffi::evmc_set_option_result::EVMC_SET_OPTION_INVALID_VALUE
+                    }
+                }
+            };
+            // Handled here rather than forwarded to `EvmcVm::set_option`: the reported
+            // capability flagset is macro-owned state (`__TOSCA_DECLARE_VM_CAPABILITIES`), not
+            // something the annotated type's `set_option` impl has any access to.
+            if key == "capabilities" {
+                let mut flagset: u32 = 0;
+                for token in value.split(',') {
+                    match token.trim() {
+                        "EVM1" => {
+                            flagset |= ::evmc_vm::ffi::evmc_capabilities::EVMC_CAPABILITY_EVM1 as u32
+                        }
+                        "EWASM" => {
+                            flagset |=
+                                ::evmc_vm::ffi::evmc_capabilities::EVMC_CAPABILITY_EWASM as u32
+                        }
+                        "PRECOMPILES" => {
+                            flagset |= ::evmc_vm::ffi::evmc_capabilities::EVMC_CAPABILITY_PRECOMPILES
+                                as u32
+                        }
+                        _ => {
+                            return ::evmc_vm::ffi::evmc_set_option_result::EVMC_SET_OPTION_INVALID_VALUE
+                        }
+                    }
+                }
+                __TOSCA_DECLARE_VM_CAPABILITIES.store(flagset, ::std::sync::atomic::Ordering::Relaxed);
+                return ::evmc_vm::ffi::evmc_set_option_result::EVMC_SET_OPTION_SUCCESS;
+            }
+            // SAFETY: `instance` is not null and points to a valid `EvmcContainer::<#vm_type>`.
+            let container = unsafe { &mut *(instance as *mut ::evmc_vm::EvmcContainer<#vm_type>) };
+            match ::evmc_vm::EvmcVm::set_option(&mut **container, key, &value) {
+                Ok(()) => ::evmc_vm::ffi::evmc_set_option_result::EVMC_SET_OPTION_SUCCESS,
+                Err(::evmc_vm::SetOptionError::InvalidKey) => {
+                    ::evmc_vm::ffi::evmc_set_option_result::EVMC_SET_OPTION_INVALID_NAME
+                }
+                Err(::evmc_vm::SetOptionError::InvalidValue) => {
+                    ::evmc_vm::ffi::evmc_set_option_result::EVMC_SET_OPTION_INVALID_VALUE
+                }
+            }
+        }
+
+        extern "C" fn __tosca_declare_vm_execute(
+            instance: *mut ::evmc_vm::ffi::evmc_vm,
+            host: *const ::evmc_vm::ffi::evmc_host_interface,
+            context: *mut ::std::ffi::c_void,
+            revision: ::evmc_vm::ffi::evmc_revision,
+            message: *const ::evmc_vm::ffi::evmc_message,
+            code: *const u8,
+            code_size: usize,
+        ) -> ::evmc_vm::ffi::evmc_result {
+            // A null host is only ever acceptable for a precompile-only call (see
+            // `EvmcVm::execute`'s own host-less branch), so it's only safe to let one through
+            // here while the currently-configured flagset actually advertises that capability.
+            let host_required = __TOSCA_DECLARE_VM_CAPABILITIES.load(::std::sync::atomic::Ordering::Relaxed)
+                & (::evmc_vm::ffi::evmc_capabilities::EVMC_CAPABILITY_PRECOMPILES as u32)
+                == 0;
+            if instance.is_null()
+                || (host.is_null() && host_required)
+                || message.is_null()
+                || (code.is_null() && code_size > 0)
+            {
+                ::std::process::abort();
+            }
+            // SAFETY: `message` is not null and the host guarantees it is valid for the call.
+            let execution_message: ::evmc_vm::ExecutionMessage = unsafe { (&*message).into() };
+            let code_ref: &[u8] = if code.is_null() {
+                &[]
+            } else {
+                // SAFETY: `code` is not null and `code_size` describes its length.
+                unsafe { ::std::slice::from_raw_parts(code, code_size) }
+            };
+            // SAFETY: `instance` points to a valid `EvmcContainer::<#vm_type>`.
+            let container = unsafe { &*(instance as *const ::evmc_vm::EvmcContainer<#vm_type>) };
+            let result = ::std::panic::catch_unwind(|| {
+                let mut execution_context = if host.is_null() {
+                    None
+                } else {
+                    // SAFETY: `host` is not null.
+                    Some(unsafe { ::evmc_vm::ExecutionContext::new(&*host, context) })
+                };
+                ::evmc_vm::EvmcVm::execute(
+                    &**container,
+                    revision,
+                    code_ref,
+                    &execution_message,
+                    execution_context.as_mut(),
+                )
+            });
+            result
+                .unwrap_or_else(|_| {
+                    ::evmc_vm::ExecutionResult::new(
+                        ::evmc_vm::ffi::evmc_status_code::EVMC_INTERNAL_ERROR.into(),
+                        0,
+                        0,
+                        None,
+                    )
+                })
+                .into()
+        }
+    })
+}